@@ -0,0 +1,146 @@
+#ifndef CARDSEED_H
+#define CARDSEED_H
+
+/* This file is generated by cbindgen. Do not edit it by hand. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * The size of a full valid deck with no duplicates.
+ */
+#define DECK_SIZE 52
+
+/**
+ * The number of cards in a suit.
+ */
+#define SUIT_SIZE (DECK_SIZE / 4)
+
+/**
+ * Status codes returned by fallible FFI functions.
+ */
+typedef enum CardseedStatus {
+  /**
+   * The call succeeded.
+   */
+  CARDSEED_STATUS_OK = 0,
+  /**
+   * A required pointer argument was null.
+   */
+  CARDSEED_STATUS_NULL_POINTER = 1,
+  /**
+   * A string argument was not valid UTF-8.
+   */
+  CARDSEED_STATUS_INVALID_UTF8 = 2,
+  /**
+   * A card string could not be parsed.
+   */
+  CARDSEED_STATUS_UNKNOWN_CARD = 3,
+  /**
+   * The deck contains a duplicate card.
+   */
+  CARDSEED_STATUS_DUPLICATE = 4,
+  /**
+   * The deck is missing cards.
+   */
+  CARDSEED_STATUS_INCOMPLETE = 5,
+  /**
+   * The deck has the wrong number of cards.
+   */
+  CARDSEED_STATUS_WRONG_LENGTH = 6,
+} CardseedStatus;
+
+/**
+ * An opaque handle to a `Deck`.
+ */
+typedef struct CardseedDeck CardseedDeck;
+
+/**
+ * Parses a deck from a NUL-terminated string of whitespace-delimited card strings.
+ * On success, a new deck handle is written to `out`.
+ *
+ * # Safety
+ *
+ * `s` must be a valid NUL-terminated string, and `out` must be a valid pointer
+ * to write a `CardseedDeck` pointer to.
+ */
+enum CardseedStatus cardseed_deck_parse(const char *s, struct CardseedDeck **out);
+
+/**
+ * Creates a new deck handle in canonical order.
+ */
+struct CardseedDeck *cardseed_deck_new(void);
+
+/**
+ * Creates a randomly shuffled copy of a deck, using a secure OS RNG.
+ * Returns null if `deck` is null.
+ *
+ * # Safety
+ *
+ * `deck` must be null or a live handle returned by this module.
+ */
+struct CardseedDeck *cardseed_deck_shuffle(const struct CardseedDeck *deck);
+
+/**
+ * Releases a deck handle. Passing null is a no-op.
+ *
+ * # Safety
+ *
+ * `deck` must be null or a live handle returned by this module, and must
+ * not be used again afterwards.
+ */
+void cardseed_deck_free(struct CardseedDeck *deck);
+
+/**
+ * Returns the number of cards in a deck, or zero if `deck` is null.
+ *
+ * # Safety
+ *
+ * `deck` must be null or a live handle returned by this module.
+ */
+uintptr_t cardseed_deck_len(const struct CardseedDeck *deck);
+
+/**
+ * Checks that a deck is a complete 52-card deck without duplicates.
+ *
+ * # Safety
+ *
+ * `deck` must be null or a live handle returned by this module.
+ */
+enum CardseedStatus cardseed_deck_validate(const struct CardseedDeck *deck);
+
+/**
+ * Derives a 32-byte secret from a deck, writing it to `out`. `password` may
+ * be null if no password is used.
+ *
+ * # Safety
+ *
+ * `deck` must be a live handle returned by this module, `password` must be null
+ * or a valid NUL-terminated string, and `out` must point to 32 writable bytes.
+ */
+enum CardseedStatus cardseed_deck_hash(const struct CardseedDeck *deck,
+                                       const char *password,
+                                       uint8_t *out);
+
+/**
+ * Formats a deck as a newly allocated, NUL-terminated string of space-delimited
+ * cards. Returns null if `deck` is null. The string must be released with
+ * `cardseed_string_free`.
+ *
+ * # Safety
+ *
+ * `deck` must be null or a live handle returned by this module.
+ */
+char *cardseed_deck_to_string(const struct CardseedDeck *deck);
+
+/**
+ * Releases a string returned by this module. Passing null is a no-op.
+ *
+ * # Safety
+ *
+ * `s` must be null or a string returned by this module, and must not be
+ * used again afterwards.
+ */
+void cardseed_string_free(char *s);
+
+#endif  /* CARDSEED_H */
@@ -0,0 +1,340 @@
+//! The [AEZ v5](https://www.cs.ucdavis.edu/~rogaway/aez/) authenticated encryption
+//! scheme, as used by aezeed. Only messages which are shorter than 32 bytes once the
+//! authenticator is appended are supported, since aezeed never encrypts more; those
+//! are enciphered by AEZ-tiny.
+
+use aes::hazmat::cipher_round;
+use blake2::digest::consts::U48;
+use blake2::{Blake2b, Digest};
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, Zeroizing};
+
+type Block = [u8; 16];
+
+/// The longest message, including the authenticator, which AEZ-tiny can encipher.
+const MAX_TINY_LEN: usize = 31;
+
+fn xor(a: &Block, b: &Block) -> Block {
+    let mut out = *a;
+    for (o, b) in out.iter_mut().zip(b) {
+        *o ^= b;
+    }
+    out
+}
+
+/// Multiplies a block by 2 in GF(2^128).
+fn double(block: &Block) -> Block {
+    let mut out = [0u8; 16];
+    for i in 0..15 {
+        out[i] = block[i] << 1 | block[i + 1] >> 7;
+    }
+    out[15] = (block[15] << 1) ^ ((block[0] >> 7) * 0x87);
+    out
+}
+
+/// Multiplies a block by a small integer in GF(2^128).
+fn multiply(mut x: u32, block: &Block) -> Block {
+    let mut power = *block;
+    let mut out = [0u8; 16];
+    while x != 0 {
+        if x & 1 == 1 {
+            out = xor(&out, &power);
+        }
+        power = double(&power);
+        x >>= 1;
+    }
+    out
+}
+
+/// Shifts a block left by 4 bits, as a big-endian bit string.
+fn shift_left4(block: &Block) -> Block {
+    let mut out = [0u8; 16];
+    for i in 0..15 {
+        out[i] = block[i] << 4 | block[i + 1] >> 4;
+    }
+    out[15] = block[15] << 4;
+    out
+}
+
+/// Shifts a block right by 4 bits, as a big-endian bit string.
+fn shift_right4(block: &Block) -> Block {
+    let mut out = [0u8; 16];
+    out[0] = block[0] >> 4;
+    for i in 1..16 {
+        out[i] = block[i] >> 4 | block[i - 1] << 4;
+    }
+    out
+}
+
+/// An AEZ key, split into the three subkeys `I`, `J` and `L`.
+pub(crate) struct Aez {
+    i: Block,
+    j: Block,
+    l: Block,
+}
+
+impl Drop for Aez {
+    fn drop(&mut self) {
+        self.i.zeroize();
+        self.j.zeroize();
+        self.l.zeroize();
+    }
+}
+
+impl Aez {
+    /// Extracts the subkeys from a key of any length. 48-byte keys are used as they
+    /// are, and others are hashed with BLAKE2b-384.
+    pub(crate) fn new(key: &[u8]) -> Aez {
+        let mut extracted = Zeroizing::new([0u8; 48]);
+        if key.len() == extracted.len() {
+            extracted.copy_from_slice(key);
+        } else {
+            extracted.copy_from_slice(&Blake2b::<U48>::digest(key));
+        }
+        let mut aez = Aez {
+            i: [0; 16],
+            j: [0; 16],
+            l: [0; 16],
+        };
+        aez.i.copy_from_slice(&extracted[..16]);
+        aez.j.copy_from_slice(&extracted[16..32]);
+        aez.l.copy_from_slice(&extracted[32..]);
+        aez
+    }
+
+    /// Four AES rounds keyed by `J`, `I`, `L` and zero.
+    fn aes4(&self, x: &Block) -> Block {
+        let mut block = (*x).into();
+        for key in [&self.j, &self.i, &self.l, &[0; 16]] {
+            cipher_round(&mut block, key.into());
+        }
+        block.into()
+    }
+
+    /// The offset `jJ ⊕ 2^⌈i/8⌉ I ⊕ (i mod 8) L` which tweaks `E(j, i)`.
+    fn offset(&self, j: u32, i: u32) -> Block {
+        let mut i_power = self.i;
+        for _ in 0..i.div_ceil(8) {
+            i_power = double(&i_power);
+        }
+        xor(
+            &xor(&multiply(j, &self.j), &i_power),
+            &multiply(i % 8, &self.l),
+        )
+    }
+
+    /// The tweakable block cipher `E(j, i)`, for `j >= 0`.
+    fn e(&self, j: u32, i: u32, x: &Block) -> Block {
+        self.aes4(&xor(x, &self.offset(j, i)))
+    }
+
+    /// AEZ-hash of the authenticator length `tau` in bytes, followed by each tweak.
+    fn hash(&self, tau: usize, tweaks: &[&[u8]]) -> Block {
+        let mut tau_block = [0u8; 16];
+        tau_block[12..].copy_from_slice(&(8 * tau as u32).to_be_bytes());
+        let mut sum = self.e(3, 1, &tau_block);
+
+        for (j, tweak) in (4..).zip(tweaks) {
+            let mut chunks = tweak.chunks_exact(16);
+            for (i, chunk) in (1..).zip(&mut chunks) {
+                let chunk = chunk.try_into().expect("chunks are 16 bytes");
+                sum = xor(&sum, &self.e(j, i, chunk));
+            }
+            let rest = chunks.remainder();
+            if !rest.is_empty() || tweak.is_empty() {
+                let mut padded = [0u8; 16];
+                padded[..rest.len()].copy_from_slice(rest);
+                padded[rest.len()] = 0x80;
+                sum = xor(&sum, &self.e(j, 0, &padded));
+            }
+        }
+        sum
+    }
+
+    /// AEZ-tiny: enciphers or deciphers a message of 1 to 31 bytes under the tweak `t`,
+    /// with a balanced Feistel network over its two halves, which meet mid-byte if
+    /// the length is odd.
+    fn tiny(&self, t: &Block, input: &[u8], decipher: bool) -> Zeroizing<Vec<u8>> {
+        let n = input.len();
+        let half = n / 2;
+        let mut buf = Zeroizing::new([0u8; 32]);
+        buf[..n].copy_from_slice(input);
+
+        let mut l: Block = buf[..16].try_into().expect("slice is 16 bytes");
+        let mut r: Block = buf[half..half + 16].try_into().expect("slice is 16 bytes");
+        let mut mask_ff = [0u8; 16];
+        let mut mask_10 = [0u8; 16];
+        mask_ff[..half].fill(0xff);
+        if n % 2 == 1 {
+            mask_ff[half] = 0xf0;
+            mask_10[half] = 0x08;
+            r = shift_left4(&r);
+        } else {
+            mask_10[half] = 0x80;
+        }
+        let mask = |x: Block| -> Block {
+            let mut out = [0u8; 16];
+            for k in 0..16 {
+                out[k] = x[k] & mask_ff[k] | mask_10[k];
+            }
+            out
+        };
+        r = mask(r);
+
+        let (i, rounds) = match n {
+            16.. => (6, 8),
+            3.. => (7, 10),
+            2 => (7, 16),
+            _ => (7, 24),
+        };
+        let round_tweak = xor(t, &self.offset(0, i));
+
+        // A Feistel network only computes even permutations, which is noticeable
+        // on messages shorter than a block, so their first bit is tweaked as well.
+        let first_bit = |block: &Block| {
+            let mut x = *block;
+            x[n.min(16)..].fill(0);
+            x[0] |= 0x80;
+            self.e(0, 3, &xor(t, &x))[0] & 0x80
+        };
+        if decipher && n < 16 {
+            l[0] ^= first_bit(&l);
+        }
+
+        for k in 0..rounds {
+            let mut round = [0u8; 16];
+            round[15] = if decipher { rounds - 1 - k } else { k };
+            let (a, b) = if k % 2 == 0 {
+                (&mut l, &r)
+            } else {
+                (&mut r, &l)
+            };
+            let f = self.aes4(&xor(&xor(&round_tweak, b), &round));
+            *a = mask(xor(&f, a));
+        }
+
+        buf[..16].copy_from_slice(&r);
+        if n % 2 == 1 {
+            l = shift_right4(&l);
+            l[0] |= buf[half] & 0xf0;
+        }
+        buf[half..half + 16].copy_from_slice(&l);
+
+        if !decipher && n < 16 {
+            let head = buf[..16].try_into().expect("slice is 16 bytes");
+            buf[0] ^= first_bit(&head);
+        }
+        Zeroizing::new(buf[..n].to_vec())
+    }
+
+    /// Encrypts `plaintext` with the nonce and associated data, appending an
+    /// authenticator of `tau` bytes.
+    ///
+    /// Panics if the plaintext is empty, or if the ciphertext would be 32 bytes or longer.
+    pub(crate) fn encrypt(
+        &self,
+        nonce: &[u8],
+        ad: &[&[u8]],
+        tau: usize,
+        plaintext: &[u8],
+    ) -> Vec<u8> {
+        let len = plaintext.len() + tau;
+        assert!(
+            !plaintext.is_empty() && len <= MAX_TINY_LEN,
+            "AEZ-tiny cannot encrypt {} bytes with a {tau}-byte authenticator",
+            plaintext.len()
+        );
+
+        let mut tweaks = vec![nonce];
+        tweaks.extend_from_slice(ad);
+        let t = self.hash(tau, &tweaks);
+
+        let mut message = Zeroizing::new(plaintext.to_vec());
+        message.resize(len, 0);
+        self.tiny(&t, &message, false).to_vec()
+    }
+
+    /// Decrypts a ciphertext produced by `Aez::encrypt`, returning `None` if its
+    /// authenticator is wrong.
+    pub(crate) fn decrypt(
+        &self,
+        nonce: &[u8],
+        ad: &[&[u8]],
+        tau: usize,
+        ciphertext: &[u8],
+    ) -> Option<Zeroizing<Vec<u8>>> {
+        let len = ciphertext.len();
+        if len <= tau || len > MAX_TINY_LEN {
+            return None;
+        }
+
+        let mut tweaks = vec![nonce];
+        tweaks.extend_from_slice(ad);
+        let t = self.hash(tau, &tweaks);
+
+        let message = self.tiny(&t, ciphertext, true);
+        let (plaintext, authenticator) = message.split_at(len - tau);
+        if bool::from(authenticator.ct_eq(&vec![0; tau])) {
+            Some(Zeroizing::new(plaintext.to_vec()))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks a test vector from the AEZ v5 reference implementation.
+    fn check(key: &str, nonce: &str, ad: [&str; 3], tau: usize, plaintext: &str, ciphertext: &str) {
+        let aez = Aez::new(&hex::decode(key).unwrap());
+        let nonce = hex::decode(nonce).unwrap();
+        let ad: Vec<Vec<u8>> = ad.iter().map(|d| hex::decode(d).unwrap()).collect();
+        let ad: Vec<&[u8]> = ad.iter().map(Vec::as_slice).collect();
+        let plaintext = hex::decode(plaintext).unwrap();
+
+        let encrypted = aez.encrypt(&nonce, &ad, tau, &plaintext);
+        assert_eq!(hex::encode(&encrypted), ciphertext);
+        let decrypted = aez.decrypt(&nonce, &ad, tau, &encrypted).unwrap();
+        assert_eq!(*decrypted, plaintext);
+    }
+
+    #[test]
+    fn vectors() {
+        check(
+            "bf93b0b28d554ec44e4cd805ff36358965a290ddd7981161bf716902c241de6ac194a9b592a12bebff3427030692d4d6",
+            "d7136281e933ac0c67fae9b465b6df8c",
+            ["cc97def9e074b5270908", "", "345d34fdc62f0a0ca9dd12d65a6025"],
+            0,
+            "76d7f6",
+            "971bdc",
+        );
+        check(
+            "772d745f91663b6b599a44f0b7f918217f41cebe50f5ac37d189d385ab3c203dfce91c6857bc3a19bc35ba4d99871c03",
+            "27d4c48213aad80b2f81d09221bbd50e",
+            ["b182570ae089aaeb8c2f", "", "c12e5d5fea70bc0d9b9d4bc3c2043f"],
+            16,
+            "8e3de4dde317d9",
+            "a3e4286cfb4ddd4f973d5b93e15d7fba2f6c690daecc09",
+        );
+        check(
+            "05b02f3c425fc1b201e4240687f05bd2d7ae485af1d8914ba8cc6671d0ea53e8d6e057d30cdf650d7a9c1972a2ea8b44",
+            "63219e98d3452670201cd963abaa0991",
+            ["5fb1f17af6f323c518e0", "", "923f273d5b1ed59dab1cbf4c2f1909"],
+            0,
+            "fcc4059b28f829cc9374d049c0437e826b7fe232fc9c6b",
+            "1dca2fdf7748ad3512da830a0675c0c5898d0f6198879f",
+        );
+    }
+
+    #[test]
+    fn tampering() {
+        let aez = Aez::new(b"key");
+        let mut ciphertext = aez.encrypt(&[], &[b"ad"], 4, b"attack at dawn");
+        assert!(aez.decrypt(&[], &[b"ad"], 4, &ciphertext).is_some());
+        assert!(aez.decrypt(&[], &[b"da"], 4, &ciphertext).is_none());
+        ciphertext[0] ^= 1;
+        assert!(aez.decrypt(&[], &[b"ad"], 4, &ciphertext).is_none());
+    }
+}
@@ -0,0 +1,571 @@
+use crate::card::{Card, STANDARD_DECK};
+use crate::deck::Deck;
+use crate::errors;
+use crate::suit::Suit;
+use crate::DECK_SIZE;
+use std::fmt;
+
+/// Decks shorter than this are too short for the statistics in an `Analysis`
+/// to be meaningful, and never produce any `Finding`s.
+const MIN_CARDS: usize = 20;
+
+/// The number of standard deviations from the expected value at which a statistic
+/// is considered suspicious. For a well-shuffled deck, each test raises a false
+/// alarm with a probability below 0.1%.
+const Z_THRESHOLD: f64 = 3.5;
+
+/// A deck with at least this many cards directly followed by their successor in
+/// canonical order is suspicious. A shuffled deck expects about one such pair,
+/// and has six or more with a probability below 0.1%.
+const SUCCESSOR_THRESHOLD: usize = 6;
+
+/// A deck with at least this many cards in their canonical position is suspicious.
+/// A shuffled deck expects one such card, and has six or more with a probability
+/// below 0.1%.
+const FIXED_POINT_THRESHOLD: usize = 6;
+
+/// Collections of fewer decks than this are too small for the statistics in a
+/// `BiasReport` to be meaningful, and never produce any `Finding`s. With this many
+/// decks, each card is expected in each position at least five times.
+const MIN_DECKS: usize = 5 * DECK_SIZE;
+
+/// The chi-square critical value for 9 degrees of freedom at a significance of 0.1%.
+const CHI_SQUARE_THRESHOLD: f64 = 27.877;
+
+/// A statistical check which an `Analysis` found a deck to fail, or a `BiasReport`
+/// found a collection of decks to fail.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Finding {
+    /// Too many cards are directly followed by the next card in canonical order.
+    SuccessorPairs,
+    /// The deck splits into too few rising sequences, a signature of too few riffle shuffles.
+    RisingSequences,
+    /// The deck has too many or too few runs of ascending and descending cards.
+    Runs,
+    /// The indexes of neighboring cards are correlated.
+    SerialCorrelation,
+    /// Cards of the same suit are clustered in one part of the deck.
+    SuitClustering,
+    /// Too many cards are still in their canonical position.
+    FixedPoints,
+    /// Across a collection of decks, cards land in some positions more often than others.
+    PositionalBias,
+    /// Across a collection of decks, some cards follow each other more often than others.
+    Clumping,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Finding::SuccessorPairs => write!(f, "too many cards are followed by their successor"),
+            Finding::RisingSequences => write!(f, "too few rising sequences"),
+            Finding::Runs => write!(f, "unusual number of ascending and descending runs"),
+            Finding::SerialCorrelation => write!(f, "neighboring cards are correlated"),
+            Finding::SuitClustering => write!(f, "suits are clustered together"),
+            Finding::FixedPoints => write!(f, "too many cards are in their canonical position"),
+            Finding::PositionalBias => write!(f, "cards favor some positions over others"),
+            Finding::Clumping => write!(f, "some cards follow each other too often"),
+        }
+    }
+}
+
+/// The results of a battery of statistical tests run over the card sequence of a
+/// single `Deck`, produced by `Deck::analyze`.
+///
+/// These tests cannot prove a deck was shuffled fairly, but can detect decks
+/// which were plainly not shuffled, or not shuffled enough, so that applications
+/// can warn the user before deriving a secret from them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Analysis {
+    /// The number of cards in the analyzed deck.
+    pub cards: usize,
+    /// The number of cards which are directly followed by the next card in
+    /// canonical order, such as `4H 5H`.
+    pub successor_pairs: usize,
+    /// The number of rising sequences: maximal subsets of consecutive cards (in
+    /// canonical order) which appear in ascending order in the deck. A deck in
+    /// canonical order has one, while a shuffled deck of 52 cards averages 26.5.
+    pub rising_sequences: usize,
+    /// The z-score of the rising sequence count, compared to a shuffled deck.
+    pub rising_sequences_z: f64,
+    /// The number of runs of ascending or descending card indexes.
+    pub runs: usize,
+    /// The z-score of the run count, compared to a shuffled deck.
+    pub runs_z: f64,
+    /// The Pearson correlation between the indexes of each card and the next.
+    pub serial_correlation: f64,
+    /// The z-score of the serial correlation, compared to a shuffled deck.
+    pub serial_correlation_z: f64,
+    /// The chi-square statistic of a contingency table counting each suit in each
+    /// quarter of the deck.
+    pub suit_chi_square: f64,
+    /// The number of cards in their canonical position, as returned by
+    /// `Deck::fixed_points`.
+    pub fixed_points: usize,
+}
+
+impl Analysis {
+    /// Returns the checks which the deck failed, if any. Decks shorter than 20
+    /// cards are too short to judge, and never produce findings.
+    pub fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        if self.cards < MIN_CARDS {
+            return findings;
+        }
+
+        if self.successor_pairs >= SUCCESSOR_THRESHOLD {
+            findings.push(Finding::SuccessorPairs);
+        }
+        if self.rising_sequences_z < -Z_THRESHOLD {
+            findings.push(Finding::RisingSequences);
+        }
+        if self.runs_z.abs() > Z_THRESHOLD {
+            findings.push(Finding::Runs);
+        }
+        if self.serial_correlation_z.abs() > Z_THRESHOLD {
+            findings.push(Finding::SerialCorrelation);
+        }
+        if self.suit_chi_square > CHI_SQUARE_THRESHOLD {
+            findings.push(Finding::SuitClustering);
+        }
+        if self.fixed_points >= FIXED_POINT_THRESHOLD {
+            findings.push(Finding::FixedPoints);
+        }
+        findings
+    }
+
+    /// Returns true if the deck passed every check.
+    pub fn looks_shuffled(&self) -> bool {
+        self.findings().is_empty()
+    }
+}
+
+impl Deck {
+    /// Runs a battery of statistical tests over the order of the cards in the `Deck`,
+    /// to detect decks which do not look shuffled.
+    ///
+    /// ```
+    /// use cardseed::{Deck, Finding};
+    ///
+    /// let analysis = Deck::new().analyze();
+    /// assert!(!analysis.looks_shuffled());
+    /// assert!(analysis.findings().contains(&Finding::RisingSequences));
+    /// ```
+    pub fn analyze(&self) -> Analysis {
+        let indexes: Vec<u32> = self.cards.iter().map(|card| card.index()).collect();
+        let n = indexes.len();
+
+        let successor_pairs = indexes.windows(2).filter(|w| w[1] == w[0] + 1).count();
+
+        // Rank the cards by index, breaking ties between duplicates by position.
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by_key(|&i| (indexes[i], i));
+        let rising_sequences = 1 + order.windows(2).filter(|w| w[1] < w[0]).count();
+        let rising_sequences_z = z_score(
+            rising_sequences as f64,
+            (n as f64 + 1.0) / 2.0,
+            (n as f64 + 1.0) / 12.0,
+        );
+
+        let runs = count_runs(&indexes);
+        let runs_z = z_score(
+            runs as f64,
+            (2.0 * n as f64 - 1.0) / 3.0,
+            (16.0 * n as f64 - 29.0) / 90.0,
+        );
+
+        let serial_correlation = serial_correlation(&indexes);
+        let serial_correlation_z = serial_correlation * (n as f64).sqrt();
+
+        Analysis {
+            cards: n,
+            successor_pairs,
+            rising_sequences,
+            rising_sequences_z,
+            runs,
+            runs_z,
+            serial_correlation,
+            serial_correlation_z,
+            suit_chi_square: suit_chi_square(self),
+            fixed_points: self.fixed_points().len(),
+        }
+    }
+}
+
+/// The results of statistical tests run over many observed orderings of a deck,
+/// such as those dealt by a casino shuffler or produced by repeating a personal
+/// shuffling routine, produced by `BiasReport::from_decks`.
+///
+/// Where an `Analysis` can only catch a single deck which was plainly not
+/// shuffled, a `BiasReport` can detect subtle biases which a shuffling procedure
+/// shows consistently, given enough decks: at least 260, and ideally many more.
+///
+/// ```
+/// use cardseed::{BiasReport, Deck, Finding};
+///
+/// // A "shuffle" which only ever cuts the deck.
+/// let decks: Vec<Deck> = (0..520)
+///     .map(|i| {
+///         let mut deck = Deck::new();
+///         deck.cards.rotate_left(i % 52);
+///         deck
+///     })
+///     .collect();
+/// let report = BiasReport::from_decks(&decks).unwrap();
+/// assert!(report.findings().contains(&Finding::Clumping));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct BiasReport {
+    /// The number of decks analyzed.
+    pub decks: usize,
+    /// The chi-square statistic of the table counting how often each card landed in
+    /// each position, against an even spread.
+    pub position_chi_square: f64,
+    /// The z-score of the positional chi-square statistic, compared to decks
+    /// shuffled uniformly at random.
+    pub position_z: f64,
+    /// The chi-square statistic of the table counting how often each card was
+    /// directly followed by each other card, against an even spread.
+    pub adjacency_chi_square: f64,
+    /// The z-score of the adjacency chi-square statistic, compared to decks
+    /// shuffled uniformly at random.
+    pub adjacency_z: f64,
+    /// The mean number of cards per deck directly followed by the next card in
+    /// canonical order. Decks shuffled uniformly at random average 51/52.
+    pub successor_pairs: f64,
+    /// The z-score of the total number of successor pairs, compared to decks
+    /// shuffled uniformly at random.
+    pub successor_pairs_z: f64,
+    /// How often each card, by index, landed in each position.
+    position_counts: Vec<[u32; DECK_SIZE]>,
+}
+
+impl BiasReport {
+    /// Tallies the positions and neighbors of every card across a collection of
+    /// decks. Returns an `Err` if any deck is not a complete deck of 52 cards
+    /// without duplicates.
+    pub fn from_decks(decks: &[Deck]) -> Result<BiasReport, errors::DeckError> {
+        let mut position_counts = vec![[0u32; DECK_SIZE]; DECK_SIZE];
+        let mut adjacency_counts = vec![[0u32; DECK_SIZE]; DECK_SIZE];
+        let mut successors = 0;
+        for deck in decks {
+            deck.validate()?;
+            for (position, card) in deck.cards.iter().enumerate() {
+                position_counts[card.index() as usize][position] += 1;
+            }
+            for w in deck.cards.windows(2) {
+                adjacency_counts[w[0].index() as usize][w[1].index() as usize] += 1;
+                successors += (w[1].index() == w[0].index() + 1) as usize;
+            }
+        }
+
+        let n = decks.len() as f64;
+        let cells = DECK_SIZE as f64;
+
+        // Every card is expected in every position n / 52 times. Each of the 52 * 52
+        // counts is binomial, contributing 51 / 52 to the statistic on average.
+        let position_chi_square = chi_square(&position_counts, n / cells, false);
+        let position_z = chi_square_z(position_chi_square, cells * (cells - 1.0), n);
+
+        // Each of the 52 * 51 ordered pairs of distinct cards is expected to be
+        // adjacent n / 52 times, since a deck has 51 neighboring pairs.
+        let adjacency_chi_square = chi_square(&adjacency_counts, n / cells, true);
+        let adjacency_z = chi_square_z(adjacency_chi_square, (cells - 1.0).powi(2), n);
+
+        let expected_successors = n * (cells - 1.0) / cells;
+        let successor_pairs_z =
+            z_score(successors as f64, expected_successors, expected_successors);
+
+        Ok(BiasReport {
+            decks: decks.len(),
+            position_chi_square,
+            position_z,
+            adjacency_chi_square,
+            adjacency_z,
+            successor_pairs: if decks.is_empty() {
+                0.0
+            } else {
+                successors as f64 / n
+            },
+            successor_pairs_z,
+            position_counts,
+        })
+    }
+
+    /// Returns how many times `card` landed at `position`, counted from zero at the
+    /// top of the deck. Positions past the end of a deck are never counted.
+    pub fn position_count(&self, card: Card, position: usize) -> usize {
+        self.position_counts[card.index() as usize]
+            .get(position)
+            .map_or(0, |&count| count as usize)
+    }
+
+    /// Returns the card whose positions were spread least evenly across the decks,
+    /// with the chi-square statistic of its positions, or `None` if no decks were
+    /// analyzed. This points to where a biased procedure goes wrong.
+    pub fn most_biased_card(&self) -> Option<(Card, f64)> {
+        if self.decks == 0 {
+            return None;
+        }
+        let expected = self.decks as f64 / DECK_SIZE as f64;
+        STANDARD_DECK
+            .iter()
+            .map(|&card| {
+                let row = &self.position_counts[card.index() as usize];
+                (card, chi_square(std::slice::from_ref(row), expected, false))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Returns the checks which the collection failed, if any. Collections of
+    /// fewer than 260 decks are too small to judge, and never produce findings.
+    pub fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        if self.decks < MIN_DECKS {
+            return findings;
+        }
+
+        if self.position_z > Z_THRESHOLD {
+            findings.push(Finding::PositionalBias);
+        }
+        if self.adjacency_z > Z_THRESHOLD || self.successor_pairs_z.abs() > Z_THRESHOLD {
+            findings.push(Finding::Clumping);
+        }
+        findings
+    }
+
+    /// Returns true if the collection passed every check.
+    pub fn looks_shuffled(&self) -> bool {
+        self.findings().is_empty()
+    }
+}
+
+/// Computes the chi-square statistic of a table of counts which are each expected
+/// to equal `expected`. If `skip_diagonal` is set, the cells whose row and column
+/// are equal, which count a card following itself, are left out.
+fn chi_square(table: &[[u32; DECK_SIZE]], expected: f64, skip_diagonal: bool) -> f64 {
+    if expected <= 0.0 {
+        return 0.0;
+    }
+    let mut chi_square = 0.0;
+    for (i, row) in table.iter().enumerate() {
+        for (j, &observed) in row.iter().enumerate() {
+            if skip_diagonal && i == j {
+                continue;
+            }
+            chi_square += (observed as f64 - expected).powi(2) / expected;
+        }
+    }
+    chi_square
+}
+
+/// Converts a chi-square statistic with the given mean, its degrees of freedom,
+/// into an approximately standard normal z-score with the Wilson–Hilferty
+/// transformation, which corrects for the skew of the chi-square distribution.
+/// Returns zero if no decks were analyzed.
+fn chi_square_z(chi_square: f64, degrees_of_freedom: f64, decks: f64) -> f64 {
+    if decks == 0.0 {
+        return 0.0;
+    }
+    let variance = 2.0 / (9.0 * degrees_of_freedom);
+    z_score(
+        (chi_square / degrees_of_freedom).cbrt(),
+        1.0 - variance,
+        variance,
+    )
+}
+
+/// Returns how many standard deviations `x` is from `mean`, or zero if the
+/// variance is not positive.
+fn z_score(x: f64, mean: f64, variance: f64) -> f64 {
+    if variance > 0.0 {
+        (x - mean) / variance.sqrt()
+    } else {
+        0.0
+    }
+}
+
+/// Counts the maximal runs of strictly ascending or descending values.
+fn count_runs(xs: &[u32]) -> usize {
+    let mut runs = 0;
+    let mut ascending = None;
+    for w in xs.windows(2) {
+        let up = w[1] > w[0];
+        if ascending != Some(up) {
+            runs += 1;
+            ascending = Some(up);
+        }
+    }
+    runs
+}
+
+/// Computes the Pearson correlation between each value and the next, or zero if
+/// it is undefined.
+fn serial_correlation(xs: &[u32]) -> f64 {
+    if xs.len() < 3 {
+        return 0.0;
+    }
+    let (a, b) = (&xs[..xs.len() - 1], &xs[1..]);
+    let mean = |s: &[u32]| s.iter().map(|&x| x as f64).sum::<f64>() / s.len() as f64;
+    let (mean_a, mean_b) = (mean(a), mean(b));
+
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (&x, &y) in a.iter().zip(b) {
+        let (dx, dy) = (x as f64 - mean_a, y as f64 - mean_b);
+        cov += dx * dy;
+        var_a += dx * dx;
+        var_b += dy * dy;
+    }
+    if var_a == 0.0 || var_b == 0.0 {
+        return 0.0;
+    }
+    cov / (var_a * var_b).sqrt()
+}
+
+/// Computes the chi-square statistic of a table counting each suit in each quarter
+/// of the deck, against the counts expected if suits were spread evenly.
+fn suit_chi_square(deck: &Deck) -> f64 {
+    let n = deck.cards.len();
+    let mut table = [[0usize; 4]; 4];
+    for (position, card) in deck.cards.iter().enumerate() {
+        let quarter = position * 4 / n;
+        table[quarter][u32::from(card.suit()) as usize] += 1;
+    }
+
+    let quarter_totals = table.map(|row| row.iter().sum::<usize>());
+    let suit_totals: Vec<usize> = Suit::all()
+        .map(|suit| table.iter().map(|row| row[u32::from(suit) as usize]).sum())
+        .collect();
+
+    let mut chi_square = 0.0;
+    for (quarter, row) in table.iter().enumerate() {
+        for (suit, &observed) in row.iter().enumerate() {
+            let expected = (quarter_totals[quarter] * suit_totals[suit]) as f64 / n as f64;
+            if expected > 0.0 {
+                chi_square += (observed as f64 - expected).powi(2) / expected;
+            }
+        }
+    }
+    chi_square
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical() {
+        let analysis = Deck::new().analyze();
+        assert_eq!(analysis.cards, 52);
+        assert_eq!(analysis.successor_pairs, 51);
+        assert_eq!(analysis.rising_sequences, 1);
+        assert_eq!(analysis.runs, 1);
+        assert!((analysis.serial_correlation - 1.0).abs() < 1e-9);
+        assert_eq!(
+            analysis.findings(),
+            vec![
+                Finding::SuccessorPairs,
+                Finding::RisingSequences,
+                Finding::Runs,
+                Finding::SerialCorrelation,
+                Finding::SuitClustering,
+                Finding::FixedPoints,
+            ]
+        );
+        assert_eq!(analysis.fixed_points, 52);
+    }
+
+    #[test]
+    fn reversed() {
+        let mut deck = Deck::new();
+        deck.cards.reverse();
+        let analysis = deck.analyze();
+        assert_eq!(analysis.successor_pairs, 0);
+        assert_eq!(analysis.rising_sequences, 52);
+        assert_eq!(analysis.fixed_points, 0);
+        assert!(analysis.findings().contains(&Finding::SerialCorrelation));
+        assert!(!analysis.looks_shuffled());
+    }
+
+    #[test]
+    fn one_riffle() {
+        // Interleave the two halves of a canonical deck perfectly.
+        let deck = Deck::new();
+        let (top, bottom) = deck.cards.split_at(26);
+        let cards = top.iter().zip(bottom).flat_map(|(&a, &b)| [a, b]).collect();
+        let analysis = Deck { cards }.analyze();
+        assert_eq!(analysis.rising_sequences, 2);
+        assert!(analysis.findings().contains(&Finding::RisingSequences));
+    }
+
+    #[test]
+    fn bias_report() {
+        let report = BiasReport::from_decks(&[]).unwrap();
+        assert_eq!(report.decks, 0);
+        assert_eq!(report.most_biased_card(), None);
+        assert!(report.looks_shuffled());
+
+        // Repeatedly cutting the deck keeps every neighbor together.
+        let cuts: Vec<Deck> = (0..MIN_DECKS)
+            .map(|i| {
+                let mut deck = Deck::new();
+                deck.cards.rotate_left(i % DECK_SIZE);
+                deck
+            })
+            .collect();
+        let report = BiasReport::from_decks(&cuts).unwrap();
+        assert_eq!(report.position_chi_square, 0.0);
+        assert!(report.successor_pairs > 50.0);
+        assert_eq!(report.findings(), vec![Finding::Clumping]);
+        assert_eq!(report.position_count(Card::ace_of_spades(), 0), 5);
+        assert_eq!(report.position_count(Card::ace_of_spades(), 52), 0);
+
+        // A deck which is never shuffled at all puts every card in the same place.
+        let report = BiasReport::from_decks(&vec![Deck::new(); MIN_DECKS]).unwrap();
+        assert_eq!(
+            report.findings(),
+            vec![Finding::PositionalBias, Finding::Clumping]
+        );
+        assert!(BiasReport::from_decks(&[Deck::new(), "AS".parse().unwrap()]).is_err());
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn bias_report_shuffled() {
+        let decks: Vec<Deck> = (0..2 * MIN_DECKS).map(|_| Deck::new().shuffle()).collect();
+        let report = BiasReport::from_decks(&decks).unwrap();
+        // Each check has a false alarm rate below 0.1%, so test against a wider
+        // margin rather than make this test flaky.
+        assert!(report.position_z.abs() < 5.0);
+        assert!(report.adjacency_z.abs() < 5.0);
+        assert!(report.successor_pairs_z.abs() < 5.0);
+        assert!((report.successor_pairs - 51.0 / 52.0).abs() < 0.2);
+
+        // Moving the ace of spades to the top of every deck is a positional bias.
+        let biased: Vec<Deck> = decks
+            .into_iter()
+            .map(|mut deck| {
+                let position = deck.position(Card::ace_of_spades()).unwrap();
+                deck.cards[..=position].rotate_right(1);
+                deck
+            })
+            .collect();
+        let report = BiasReport::from_decks(&biased).unwrap();
+        assert!(report.findings().contains(&Finding::PositionalBias));
+        assert_eq!(report.most_biased_card().unwrap().0, Card::ace_of_spades());
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn shuffled() {
+        // Each check has a false alarm rate below 0.1%, so allow the occasional
+        // unlucky shuffle rather than make this test flaky.
+        let passed = (0..20)
+            .filter(|_| Deck::new().shuffle().analyze().looks_shuffled())
+            .count();
+        assert!(passed >= 18);
+
+        let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+        assert!(deck.analyze().looks_shuffled());
+    }
+}
@@ -0,0 +1,57 @@
+use crate::card::{Card, STANDARD_DECK};
+use crate::deck::Deck;
+use crate::suit::{Suit, ALL_SUITS};
+use crate::DECK_SIZE;
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+impl<'a> Arbitrary<'a> for Suit {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Suit> {
+        u.choose(&ALL_SUITS).copied()
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        u8::size_hint(depth)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Card {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Card> {
+        Ok(STANDARD_DECK[u.int_in_range(0..=DECK_SIZE - 1)?])
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        u8::size_hint(depth)
+    }
+}
+
+/// Generates any sequence of cards, of any length and possibly with duplicates,
+/// so that fuzzing also exercises the paths which reject malformed decks.
+impl<'a> Arbitrary<'a> for Deck {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Deck> {
+        let cards = u.arbitrary_iter()?.collect::<Result<Vec<Card>>>()?;
+        Ok(Deck { cards })
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arbitrary() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let mut u = Unstructured::new(&bytes);
+        let card = Card::arbitrary(&mut u).unwrap();
+        assert!(card.index() < DECK_SIZE as u32);
+        assert!(ALL_SUITS.contains(&Suit::arbitrary(&mut u).unwrap()));
+        assert!(Deck::arbitrary(&mut u).is_ok());
+
+        let deck = Deck::arbitrary(&mut Unstructured::new(&[0xFF; 64])).unwrap();
+        assert!(deck.len() > 1);
+        assert!(deck.has_duplicates());
+    }
+}
@@ -0,0 +1,164 @@
+//! Command-line interface to `cardseed`. Decks are read from a file, or from stdin
+//! if no file is given, as whitespace-delimited card strings.
+
+use cardseed::{bip39, Deck, HashOptions, DECK_SIZE};
+use clap::{Args, Parser, Subcommand};
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufReader, IsTerminal};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print a randomly shuffled deck.
+    Shuffle,
+    /// Check that a deck is a complete 52-card deck without duplicates.
+    Validate(Input),
+    /// Derive a 32-byte secret from a deck, printed as hex.
+    Hash(Derive),
+    /// Derive a 24-word BIP39 mnemonic from a deck.
+    Mnemonic(Derive),
+    /// Print the number of bits of entropy in a deck, assuming it was randomly shuffled.
+    Entropy(Input),
+}
+
+#[derive(Args)]
+struct Input {
+    /// File to read the deck from. Reads from stdin if omitted.
+    file: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct Derive {
+    #[command(flatten)]
+    input: Input,
+
+    /// Prompt for a password to mix into the derivation.
+    #[arg(short, long)]
+    password: bool,
+
+    /// Derive from a deck which is incomplete, instead of refusing it. Such a deck
+    /// holds less entropy than a full shuffled deck. Decks with duplicate cards are
+    /// always refused.
+    #[arg(long)]
+    partial: bool,
+}
+
+impl Input {
+    fn read_deck(&self) -> Result<Deck, Box<dyn Error>> {
+        let deck = match &self.file {
+            Some(path) => Deck::read_from(BufReader::new(File::open(path)?), DECK_SIZE)?,
+            None => Deck::read_from(io::stdin().lock(), DECK_SIZE)?,
+        };
+        Ok(deck)
+    }
+}
+
+impl Derive {
+    fn read_password(&self) -> Result<Option<String>, Box<dyn Error>> {
+        if !self.password {
+            return Ok(None);
+        }
+
+        let password = rpassword::prompt_password("Password: ")?;
+        let confirmation = rpassword::prompt_password("Confirm password: ")?;
+        if password != confirmation {
+            return Err("passwords do not match".into());
+        }
+        Ok(Some(password))
+    }
+
+    /// Reads the deck and password, and derives a secret from them, showing
+    /// the progress of the derivation if stderr is a terminal. The deck must be
+    /// complete unless `--partial` was given.
+    fn derive(&self) -> Result<[u8; 32], Box<dyn Error>> {
+        let deck = self.input.read_deck()?;
+        if !self.partial {
+            deck.validate()?;
+        }
+        let password = self.read_password()?;
+
+        let show = io::stderr().is_terminal();
+        let secret = deck.hash_with_progress(
+            password.as_deref(),
+            &HashOptions::default(),
+            |done, total| {
+                if show {
+                    eprint!("\rDeriving... {:>3}%", done as u64 * 100 / total as u64);
+                }
+            },
+        );
+        if show {
+            eprint!("\r\x1b[K");
+        }
+        Ok(secret)
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    match cli.command {
+        Command::Shuffle => println!("{}", Deck::new().shuffle_checked()?),
+        Command::Validate(input) => {
+            input.read_deck()?.validate()?;
+            println!("ok");
+        }
+        Command::Hash(derive) => println!("{}", hex::encode(derive.derive()?)),
+        Command::Mnemonic(derive) => {
+            println!("{}", bip39::Mnemonic::from_entropy(&derive.derive()?)?);
+        }
+        Command::Entropy(input) => println!("{:.2}", input.read_deck()?.entropy_bits()),
+    };
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprint!("error: {}", e);
+            let mut source = e.source();
+            while let Some(e) = source {
+                eprint!(": {}", e);
+                source = e.source();
+            }
+            eprintln!();
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn derive(args: &[&str]) -> Result<[u8; 32], Box<dyn Error>> {
+        match Cli::try_parse_from(args)?.command {
+            Command::Hash(derive) => derive.derive(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn partial() {
+        let path = std::env::temp_dir().join(format!("cardseed-cli-{}.txt", std::process::id()));
+        let file = path.to_str().unwrap();
+
+        std::fs::write(&path, "AS 3H KC 3C").unwrap();
+        assert!(derive(&["cardseed", "hash", file]).is_err());
+        let secret = derive(&["cardseed", "hash", "--partial", file]).unwrap();
+        assert_eq!(secret, "AS 3H KC 3C".parse::<Deck>().unwrap().hash(None));
+
+        std::fs::write(&path, "AS 3H AS").unwrap();
+        assert!(derive(&["cardseed", "hash", "--partial", file]).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
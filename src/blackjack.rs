@@ -0,0 +1,117 @@
+//! Blackjack hand totals, for simulations which deal from a shuffled `Deck`.
+//!
+//! ```
+//! use cardseed::blackjack;
+//! use cardseed::Card;
+//!
+//! let cards = |s: &str| -> Vec<Card> { s.split(' ').map(|c| c.parse().unwrap()).collect() };
+//! let total = blackjack::total(&cards("AS 6H"));
+//! assert_eq!(total.value(), 17);
+//! assert!(total.is_soft());
+//! assert!(blackjack::total(&cards("KD 6H 9C")).is_bust());
+//! ```
+
+use crate::card::Card;
+use std::fmt;
+
+/// The highest total which isn't bust.
+pub const BLACKJACK: u32 = 21;
+
+/// The total of a blackjack hand.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Total {
+    value: u32,
+    soft: bool,
+}
+
+impl Total {
+    /// Returns the best value of the hand: the highest which doesn't exceed 21 if
+    /// possible, counting each ace as 1 or 11.
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    /// Returns true if the hand is soft, meaning one ace is counted as 11, so that
+    /// drawing another card can't bust it.
+    pub fn is_soft(&self) -> bool {
+        self.soft
+    }
+
+    /// Returns true if the value of the hand exceeds 21.
+    pub fn is_bust(&self) -> bool {
+        self.value > BLACKJACK
+    }
+}
+
+impl fmt::Display for Total {
+    /// Formats the total as it is usually called, such as `soft 17` or `hard 12`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = if self.soft { "soft" } else { "hard" };
+        write!(f, "{kind} {}", self.value)
+    }
+}
+
+/// Returns the blackjack value of a card, counting an ace as 1 and face cards as 10.
+pub fn card_value(card: Card) -> u32 {
+    (card.value() + 1).min(10)
+}
+
+/// Computes the total of a blackjack hand. At most one ace is ever counted as 11,
+/// and only if that doesn't bust the hand.
+pub fn total(cards: &[Card]) -> Total {
+    let hard: u32 = cards.iter().map(|&card| card_value(card)).sum();
+    let has_ace = cards.iter().any(|card| card.value() == 0);
+    if has_ace && hard + 10 <= BLACKJACK {
+        Total {
+            value: hard + 10,
+            soft: true,
+        }
+    } else {
+        Total {
+            value: hard,
+            soft: false,
+        }
+    }
+}
+
+/// Returns true if the cards are a natural blackjack: an ace and a ten-valued card,
+/// as the first two cards of a hand.
+pub fn is_blackjack(cards: &[Card]) -> bool {
+    cards.len() == 2 && total(cards).value() == BLACKJACK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(s: &str) -> Vec<Card> {
+        s.split(' ').map(|c| c.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn totals() {
+        let cases = [
+            ("2C 3D", "hard 5"),
+            ("AC 6D", "soft 17"),
+            ("AC 6D KH", "hard 17"),
+            ("AC AD", "soft 12"),
+            ("AC AD AH AS 7C", "soft 21"),
+            ("JC QD", "hard 20"),
+            ("JC QD 2H", "hard 22"),
+        ];
+        for (hand, expected) in cases {
+            assert_eq!(total(&cards(hand)).to_string(), expected, "{hand}");
+        }
+        assert!(total(&cards("JC QD 2H")).is_bust());
+        assert!(!total(&cards("AC AD AH AS 7C")).is_bust());
+        assert_eq!(total(&[]).value(), 0);
+    }
+
+    #[test]
+    fn naturals() {
+        assert!(is_blackjack(&cards("AS TD")));
+        assert!(is_blackjack(&cards("KH AC")));
+        assert!(!is_blackjack(&cards("7S 7D 7C")));
+        assert!(!is_blackjack(&cards("AS 9D")));
+    }
+}
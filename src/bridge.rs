@@ -0,0 +1,279 @@
+//! Dealing a `Deck` into four bridge hands, and converting deals to and from the
+//! deal strings of the [Portable Bridge Notation](https://www.tistis.nl/pbn/)
+//! (PBN) format used by most bridge software.
+//!
+//! ```
+//! use cardseed::bridge::{Deal, Seat};
+//! use cardseed::Deck;
+//!
+//! # #[cfg(feature = "rand")] {
+//! let deal = Deal::from_deck(&Deck::new().shuffle()).unwrap();
+//! let pbn = deal.to_string();
+//! assert!(pbn.starts_with("N:"));
+//! assert_eq!(pbn.parse::<Deal>().unwrap(), deal);
+//! assert_eq!(deal.hand(Seat::South).len(), 13);
+//! # }
+//! ```
+
+use crate::card::Card;
+use crate::deck::{CardSet, Deck};
+use crate::errors;
+use crate::suit::Suit;
+use crate::DECK_SIZE;
+use std::fmt;
+
+/// The number of cards in each bridge hand.
+pub const HAND_SIZE: usize = DECK_SIZE / 4;
+
+/// The suits in the order PBN lists them within a hand.
+const PBN_SUITS: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+/// A seat at a bridge table.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Seat {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Seat {
+    /// Returns every seat in clockwise order, starting from North.
+    pub fn all() -> [Seat; 4] {
+        [Seat::North, Seat::East, Seat::South, Seat::West]
+    }
+
+    /// Returns the seat which plays after this one, going clockwise.
+    pub fn next(self) -> Seat {
+        Seat::all()[(self as usize + 1) % 4]
+    }
+}
+
+impl fmt::Display for Seat {
+    /// Formats the seat as its initial: `N`, `E`, `S`, or `W`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let c = match self {
+            Seat::North => 'N',
+            Seat::East => 'E',
+            Seat::South => 'S',
+            Seat::West => 'W',
+        };
+        write!(f, "{c}")
+    }
+}
+
+impl TryFrom<char> for Seat {
+    type Error = errors::ParseError;
+
+    fn try_from(c: char) -> Result<Seat, errors::ParseError> {
+        match c {
+            'N' => Ok(Seat::North),
+            'E' => Ok(Seat::East),
+            'S' => Ok(Seat::South),
+            'W' => Ok(Seat::West),
+            _ => Err(errors::ParseError::BadChar(c)),
+        }
+    }
+}
+
+/// Four bridge hands of 13 cards each, which together hold every card once.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Deal {
+    hands: [Vec<Card>; 4],
+}
+
+impl Deal {
+    /// Deals a full `Deck` one card at a time to each seat in turn, starting with
+    /// North and going clockwise. Each hand is sorted by suit, from spades to clubs,
+    /// and then from ace down to deuce. Returns an `Err` if the deck isn't a complete
+    /// deck of 52 cards.
+    pub fn from_deck(deck: &Deck) -> Result<Deal, errors::BridgeError> {
+        deck.validate()?;
+        let mut hands: [Vec<Card>; 4] = Default::default();
+        for (i, &card) in deck.cards.iter().enumerate() {
+            hands[i % 4].push(card);
+        }
+        Ok(Deal::sorted(hands))
+    }
+
+    /// Sorts each hand into the order PBN lists cards in.
+    fn sorted(mut hands: [Vec<Card>; 4]) -> Deal {
+        for hand in &mut hands {
+            hand.sort_by_key(|&card| (pbn_suit_index(card.suit()), pbn_rank(card)));
+        }
+        Deal { hands }
+    }
+
+    /// Returns the cards dealt to a seat, sorted by suit from spades to clubs, and
+    /// then from ace down to deuce.
+    pub fn hand(&self, seat: Seat) -> &[Card] {
+        &self.hands[seat as usize]
+    }
+
+    /// Formats the deal as a PBN deal string, listing the hands clockwise starting
+    /// from `first`. `Deal`'s `Display` implementation starts from North.
+    ///
+    /// ```
+    /// use cardseed::bridge::{Deal, Seat};
+    ///
+    /// let pbn = "N:AKQJ.T98.765.432 T98.765.432.AKQJ 765.432.AKQJ.T98 432.AKQJ.T98.765";
+    /// let deal = pbn.parse::<Deal>().unwrap();
+    /// assert_eq!(
+    ///     deal.to_pbn(Seat::West),
+    ///     "W:432.AKQJ.T98.765 AKQJ.T98.765.432 T98.765.432.AKQJ 765.432.AKQJ.T98"
+    /// );
+    /// ```
+    pub fn to_pbn(&self, first: Seat) -> String {
+        let mut pbn = format!("{first}:");
+        let mut seat = first;
+        for i in 0..4 {
+            if i > 0 {
+                pbn.push(' ');
+            }
+            for (j, &suit) in PBN_SUITS.iter().enumerate() {
+                if j > 0 {
+                    pbn.push('.');
+                }
+                for card in self.hand(seat).iter().filter(|c| c.suit() == suit) {
+                    let s = card.to_string();
+                    pbn.push_str(&s[..1]);
+                }
+            }
+            seat = seat.next();
+        }
+        pbn
+    }
+}
+
+/// Returns the position of a suit in PBN order.
+fn pbn_suit_index(suit: Suit) -> usize {
+    PBN_SUITS
+        .iter()
+        .position(|&s| s == suit)
+        .expect("every suit is listed")
+}
+
+/// Returns the position of a card within its suit in PBN order, which lists the ace
+/// first and the deuce last.
+fn pbn_rank(card: Card) -> u32 {
+    match card.value() {
+        0 => 0,
+        value => 13 - value,
+    }
+}
+
+impl fmt::Display for Deal {
+    /// Formats the deal as a PBN deal string starting from North.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_pbn(Seat::North))
+    }
+}
+
+impl std::str::FromStr for Deal {
+    type Err = errors::BridgeError;
+
+    /// Parses a PBN deal string, such as `N:AKQJ.T98.765.432 T98.765.432.AKQJ ...`.
+    /// The hands may start from any seat. Every hand must be given in full, so the
+    /// `-` placeholder for an unknown hand isn't supported.
+    fn from_str(s: &str) -> Result<Deal, errors::BridgeError> {
+        let malformed = || errors::ParseError::Malformed(String::from(s));
+        let (first, hands) = s.split_once(':').ok_or_else(malformed)?;
+        let mut first_chars = first.trim().chars();
+        let mut seat = match (first_chars.next(), first_chars.next()) {
+            (Some(c), None) => Seat::try_from(c)?,
+            _ => return Err(malformed().into()),
+        };
+
+        let hands: Vec<&str> = hands.split_whitespace().collect();
+        if hands.len() != 4 {
+            return Err(malformed().into());
+        }
+
+        let mut dealt: [Vec<Card>; 4] = Default::default();
+        let mut seen = CardSet::default();
+        for hand in hands {
+            let suits: Vec<&str> = hand.split('.').collect();
+            if suits.len() != 4 {
+                return Err(malformed().into());
+            }
+            for (holding, suit) in suits.into_iter().zip(PBN_SUITS) {
+                for c in holding.chars() {
+                    let card = format!("{c}{suit}").parse::<Card>()?;
+                    if !seen.insert(card) {
+                        return Err(errors::DeckError::Duplicate(card).into());
+                    }
+                    dealt[seat as usize].push(card);
+                }
+            }
+            let len = dealt[seat as usize].len();
+            if len != HAND_SIZE {
+                return Err(errors::BridgeError::HandSize { seat, len });
+            }
+            seat = seat.next();
+        }
+        Ok(Deal::sorted(dealt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_deck() {
+        let deal = Deal::from_deck(&Deck::new()).unwrap();
+        assert_eq!(
+            deal.to_string(),
+            "N:AK95.J73.T62.Q84 T62.Q84.J73.AK95 J73.AK95.Q84.T62 Q84.T62.AK95.J73"
+        );
+        let north: Vec<String> = deal.hand(Seat::North).iter().map(Card::to_string).collect();
+        assert_eq!(
+            &north[..5],
+            ["AS", "KS", "9S", "5S", "JH"].map(String::from)
+        );
+
+        assert!(matches!(
+            Deal::from_deck(&"AS 2S".parse().unwrap()),
+            Err(errors::BridgeError::Deck(errors::DeckError::Incomplete(_)))
+        ));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn pbn_round_trip() {
+        let deal = Deal::from_deck(&Deck::new().shuffle()).unwrap();
+        for seat in Seat::all() {
+            assert_eq!(deal.to_pbn(seat).parse::<Deal>().unwrap(), deal);
+        }
+    }
+
+    #[test]
+    fn pbn() {
+        let pbn = "E:AK95.J73.T62.Q84 T62.Q84.J73.AK95 J73.AK95.Q84.T62 Q84.T62.AK95.J73";
+        let deal = pbn.parse::<Deal>().unwrap();
+        assert_eq!(deal.hand(Seat::East)[0], "AS".parse().unwrap());
+        assert_eq!(deal.to_pbn(Seat::East), pbn);
+        assert_eq!(deal.hand(Seat::North)[0], "QS".parse().unwrap());
+        // Voids are written as empty holdings.
+        let voids = "S:AKQJT98765432... .AKQJT98765432.. ..AKQJT98765432. ...AKQJT98765432";
+        assert_eq!(voids.parse::<Deal>().unwrap().to_pbn(Seat::South), voids);
+
+        let invalid = [
+            "X:AK95.J73.T62.Q84 T62.Q84.J73.AK95 J73.AK95.Q84.T62 Q84.T62.AK95.J73",
+            "N:AK95.J73.T62.Q84 T62.Q84.J73.AK95 J73.AK95.Q84.T62",
+            "N:AK95.J73.T62.Q84 T62.Q84.J73.AK95 J73.AK95.Q84.T62 Q84.T62.AK95.J7",
+            "N:AK95.J73.T62.Q84 T62.Q84.J73.AK95 J73.AK95.Q84.T62 Q84.T62.AK95.J77",
+            "N:AK95.J73.T62.Q8X T62.Q84.J73.AK95 J73.AK95.Q84.T62 Q84.T62.AK95.J73",
+        ];
+        for pbn in invalid {
+            assert!(pbn.parse::<Deal>().is_err(), "{pbn}");
+        }
+        assert_eq!(
+            invalid[2].parse::<Deal>(),
+            Err(errors::BridgeError::HandSize {
+                seat: Seat::West,
+                len: 12
+            })
+        );
+    }
+}
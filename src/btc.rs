@@ -0,0 +1,382 @@
+use crate::deck::Deck;
+use bitcoin::bip32::{ChildNumber, Fingerprint, Xpriv, Xpub};
+use bitcoin::hashes::Hash;
+use bitcoin::secp256k1::{Secp256k1, SecretKey};
+use bitcoin::{Address, CompressedPublicKey, Network, PrivateKey};
+use zeroize::Zeroizing;
+
+/// The characters which may appear in an output descriptor, in the order used by
+/// the descriptor checksum.
+const DESCRIPTOR_CHARSET: &str =
+    "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+
+/// The characters of an output descriptor checksum.
+const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// The purpose label passed to `Deck::derive_indexed` for Bitcoin keys.
+const BITCOIN_PURPOSE: &str = "bitcoin";
+
+/// [SLIP-132](https://github.com/satoshilabs/slips/blob/master/slip-0132.md) version
+/// bytes of BIP84 native segwit extended keys, as `(private, public)` pairs.
+const ZPRV_ZPUB: ([u8; 4], [u8; 4]) = ([0x04, 0xb2, 0x43, 0x0c], [0x04, 0xb2, 0x47, 0x46]);
+const VPRV_VPUB: ([u8; 4], [u8; 4]) = ([0x04, 0x5f, 0x18, 0xbc], [0x04, 0x5f, 0x1c, 0xf6]);
+
+/// A secp256k1 key derived from a `Deck`, for use with Bitcoin wallets which
+/// import WIF keys or output descriptors.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BitcoinKey {
+    /// The derived private key, which is always compressed.
+    pub private_key: PrivateKey,
+    /// The network the key is intended for.
+    pub network: Network,
+}
+
+impl BitcoinKey {
+    /// Returns the compressed public key.
+    pub fn public_key(&self) -> CompressedPublicKey {
+        CompressedPublicKey::from_private_key(&Secp256k1::signing_only(), &self.private_key)
+            .expect("derived private keys are always compressed")
+    }
+
+    /// Returns the private key in Wallet Import Format.
+    pub fn wif(&self) -> String {
+        self.private_key.to_wif()
+    }
+
+    /// Returns the key's fingerprint: the first 4 bytes of the HASH160 of the public key,
+    /// as used in the key origin of a descriptor.
+    pub fn fingerprint(&self) -> Fingerprint {
+        let hash = self.public_key().pubkey_hash().to_byte_array();
+        Fingerprint::from([hash[0], hash[1], hash[2], hash[3]])
+    }
+
+    /// Returns the native segwit (P2WPKH) address of the key.
+    pub fn wpkh_address(&self) -> Address {
+        Address::p2wpkh(&self.public_key(), self.network)
+    }
+
+    /// Returns the taproot (P2TR) address of the key, using it as the internal key
+    /// with no script tree.
+    pub fn tr_address(&self) -> Address {
+        Address::p2tr(
+            &Secp256k1::verification_only(),
+            self.public_key().into(),
+            None,
+            self.network,
+        )
+    }
+
+    /// Returns a watch-only P2WPKH output descriptor with a checksum, such as
+    /// `wpkh([fingerprint]pubkey)#checksum`.
+    pub fn wpkh_descriptor(&self) -> String {
+        self.descriptor("wpkh", &self.public_key().to_string())
+    }
+
+    /// Returns a P2WPKH output descriptor containing the WIF private key.
+    pub fn wpkh_private_descriptor(&self) -> String {
+        self.descriptor("wpkh", &self.wif())
+    }
+
+    /// Returns a watch-only P2TR output descriptor with a checksum, such as
+    /// `tr([fingerprint]pubkey)#checksum`.
+    pub fn tr_descriptor(&self) -> String {
+        let x_only: bitcoin::key::XOnlyPublicKey = self.public_key().into();
+        self.descriptor("tr", &x_only.to_string())
+    }
+
+    /// Returns a P2TR output descriptor containing the WIF private key.
+    pub fn tr_private_descriptor(&self) -> String {
+        self.descriptor("tr", &self.wif())
+    }
+
+    /// Formats a single-key descriptor with the key's origin and a checksum.
+    fn descriptor(&self, function: &str, key: &str) -> String {
+        let descriptor = format!("{function}([{}]{key})", self.fingerprint());
+        let checksum = descriptor_checksum(&descriptor)
+            .expect("descriptors of hex and base58 keys use only valid characters");
+        format!("{descriptor}#{checksum}")
+    }
+}
+
+/// A BIP32 master key derived from a `Deck`, for exporting extended keys with the
+/// version prefixes expected on the chosen network: `xprv`/`xpub` and `zprv`/`zpub`
+/// on mainnet, or `tprv`/`tpub` and `vprv`/`vpub` on test networks.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ExtendedKeys {
+    /// The BIP32 master private key.
+    pub master: Xpriv,
+    /// The network the keys are intended for.
+    pub network: Network,
+}
+
+impl ExtendedKeys {
+    /// Creates the master key from a BIP32 seed.
+    fn from_seed(seed: &[u8], network: Network) -> ExtendedKeys {
+        match Xpriv::new_master(network, seed) {
+            Ok(master) => ExtendedKeys { master, network },
+            Err(e) => panic!("failed to derive a master key from the seed: {}", e),
+        }
+    }
+
+    /// Returns the serialized master private key, beginning `xprv` or `tprv`.
+    pub fn xprv(&self) -> String {
+        self.master.to_string()
+    }
+
+    /// Returns the serialized master public key, beginning `xpub` or `tpub`.
+    pub fn xpub(&self) -> String {
+        Xpub::from_priv(&Secp256k1::signing_only(), &self.master).to_string()
+    }
+
+    /// Returns the private key of the first BIP84 native segwit account, at the
+    /// path `m/84'/0'/0'` on mainnet or `m/84'/1'/0'` on test networks.
+    pub fn segwit_account(&self) -> Xpriv {
+        let coin = if self.network == Network::Bitcoin {
+            0
+        } else {
+            1
+        };
+        let path = [84, coin, 0].map(|i| ChildNumber::from_hardened_idx(i).unwrap());
+        self.master
+            .derive_priv(&Secp256k1::signing_only(), &path)
+            .expect("hardened derivation from a valid key cannot fail")
+    }
+
+    /// Returns the serialized private key of the first BIP84 account, beginning
+    /// `zprv` on mainnet or `vprv` on test networks.
+    pub fn zprv(&self) -> String {
+        let mut bytes = self.segwit_account().encode();
+        bytes[..4].copy_from_slice(&self.slip132_versions().0);
+        bitcoin::base58::encode_check(&bytes)
+    }
+
+    /// Returns the serialized public key of the first BIP84 account, beginning
+    /// `zpub` on mainnet or `vpub` on test networks.
+    pub fn zpub(&self) -> String {
+        let xpub = Xpub::from_priv(&Secp256k1::signing_only(), &self.segwit_account());
+        let mut bytes = xpub.encode();
+        bytes[..4].copy_from_slice(&self.slip132_versions().1);
+        bitcoin::base58::encode_check(&bytes)
+    }
+
+    fn slip132_versions(&self) -> ([u8; 4], [u8; 4]) {
+        if self.network == Network::Bitcoin {
+            ZPRV_ZPUB
+        } else {
+            VPRV_VPUB
+        }
+    }
+}
+
+impl Deck {
+    /// Derives a BIP32 master key for the given Bitcoin network from the `Deck` and
+    /// an optional password, using the BIP39 seed of `Deck::mnemonic` with an empty
+    /// passphrase as the BIP32 seed. The keys therefore match those of a wallet
+    /// restored from the mnemonic.
+    ///
+    /// ```
+    /// use cardseed::{bitcoin::Network, Deck};
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let mainnet = deck.bitcoin_extended_keys(None, Network::Bitcoin);
+    /// assert!(mainnet.xpub().starts_with("xpub"));
+    /// assert!(mainnet.zpub().starts_with("zpub"));
+    ///
+    /// let testnet = deck.bitcoin_extended_keys(None, Network::Testnet);
+    /// assert!(testnet.xprv().starts_with("tprv"));
+    /// assert!(testnet.zpub().starts_with("vpub"));
+    /// ```
+    pub fn bitcoin_extended_keys(&self, password: Option<&str>, network: Network) -> ExtendedKeys {
+        let seed = Zeroizing::new(self.mnemonic(password).to_seed(""));
+        ExtendedKeys::from_seed(seed.as_slice(), network)
+    }
+
+    /// Derives a secp256k1 key for the given Bitcoin network from the `Deck` and an
+    /// optional password, using `Deck::derive_indexed` with the purpose `"bitcoin"`
+    /// and index 0 as the private key.
+    ///
+    /// The output of `Deck::hash` is not used directly, since it is also the entropy
+    /// of `Deck::mnemonic`, and a WIF key would then reveal the mnemonic.
+    ///
+    /// Panics in the astronomically unlikely case that the hash is not a valid
+    /// secp256k1 private key.
+    ///
+    /// ```
+    /// use cardseed::{bitcoin::Network, Deck};
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let key = deck.bitcoin_key(Some("bestpasswordever"), Network::Bitcoin);
+    /// assert!(key.wpkh_address().to_string().starts_with("bc1q"));
+    /// assert!(key.tr_descriptor().starts_with("tr(["));
+    /// ```
+    pub fn bitcoin_key(&self, password: Option<&str>, network: Network) -> BitcoinKey {
+        let secret = Zeroizing::new(self.derive_indexed(password, BITCOIN_PURPOSE, 0));
+        match SecretKey::from_slice(secret.as_slice()) {
+            Ok(secret) => BitcoinKey {
+                private_key: PrivateKey::new(secret, network),
+                network,
+            },
+            Err(e) => panic!("failed to use 32 bytes of entropy as a private key: {}", e),
+        }
+    }
+}
+
+/// Computes the 8-character checksum of an output descriptor, as specified by
+/// [BIP380](https://github.com/bitcoin/bips/blob/master/bip-0380.mediawiki). Returns
+/// `None` if the descriptor contains a character which descriptors may not use.
+fn descriptor_checksum(descriptor: &str) -> Option<String> {
+    fn polymod(c: u64, value: u64) -> u64 {
+        let top = c >> 35;
+        let mut c = ((c & 0x7ffffffff) << 5) ^ value;
+        for (bit, generator) in [
+            0xf5dee51989,
+            0xa9fdca3312,
+            0x1bab10e32d,
+            0x3706b1677a,
+            0x644d626ffd,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if top >> bit & 1 == 1 {
+                c ^= generator;
+            }
+        }
+        c
+    }
+
+    let mut c = 1;
+    let mut class = 0;
+    let mut class_count = 0;
+    for ch in descriptor.chars() {
+        let position = DESCRIPTOR_CHARSET.find(ch)? as u64;
+        c = polymod(c, position & 31);
+        class = class * 3 + (position >> 5);
+        class_count += 1;
+        if class_count == 3 {
+            c = polymod(c, class);
+            class = 0;
+            class_count = 0;
+        }
+    }
+    if class_count > 0 {
+        c = polymod(c, class);
+    }
+    for _ in 0..8 {
+        c = polymod(c, 0);
+    }
+    c ^= 1;
+
+    Some(
+        (0..8)
+            .map(|j| CHECKSUM_CHARSET[(c >> (5 * (7 - j)) & 31) as usize] as char)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum() {
+        // Test vector from BIP380.
+        assert_eq!(descriptor_checksum("raw(deadbeef)").unwrap(), "89f8spxm");
+        assert_eq!(descriptor_checksum("raw(deadbeef)\u{e9}"), None);
+    }
+
+    #[test]
+    fn bitcoin_key() {
+        let deck = Deck::new();
+        let key = deck.bitcoin_key(Some("slick"), Network::Bitcoin);
+        assert_eq!(
+            key.private_key.inner.secret_bytes(),
+            deck.derive_indexed(Some("slick"), BITCOIN_PURPOSE, 0)
+        );
+        assert_eq!(PrivateKey::from_wif(&key.wif()), Ok(key.private_key));
+        assert!(key.wif().starts_with('K') || key.wif().starts_with('L'));
+
+        let testnet = deck.bitcoin_key(Some("slick"), Network::Testnet);
+        assert!(testnet.wif().starts_with('c'));
+        assert!(testnet.wpkh_address().to_string().starts_with("tb1q"));
+        assert!(testnet.tr_address().to_string().starts_with("tb1p"));
+        assert_eq!(testnet.fingerprint(), key.fingerprint());
+    }
+
+    #[test]
+    fn bitcoin_key_is_not_mnemonic_entropy() {
+        let deck = Deck::new();
+        let key = deck.bitcoin_key(None, Network::Bitcoin);
+        assert_ne!(
+            key.private_key.inner.secret_bytes().to_vec(),
+            deck.mnemonic(None).to_entropy()
+        );
+    }
+
+    #[test]
+    fn extended_keys() {
+        // Test vector 1 from BIP32.
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let keys = ExtendedKeys::from_seed(&seed, Network::Bitcoin);
+        assert_eq!(
+            keys.xprv(),
+            "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi"
+        );
+        assert_eq!(
+            keys.xpub(),
+            "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"
+        );
+
+        // The seed of the mnemonic "abandon abandon ... about", from the BIP84 test vectors.
+        let seed = hex::decode(
+            "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc1\
+             9a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4",
+        )
+        .unwrap();
+        let keys = ExtendedKeys::from_seed(&seed, Network::Bitcoin);
+        assert_eq!(
+            keys.zpub(),
+            "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs"
+        );
+
+        let testnet = ExtendedKeys::from_seed(&seed, Network::Testnet);
+        assert!(testnet.xprv().starts_with("tprv"));
+        assert!(testnet.xpub().starts_with("tpub"));
+        assert!(testnet.zprv().starts_with("vprv"));
+        assert!(testnet.zpub().starts_with("vpub"));
+        assert_ne!(testnet.segwit_account(), keys.segwit_account());
+    }
+
+    #[test]
+    fn extended_keys_match_mnemonic() {
+        let deck = Deck::new();
+        let words = deck.mnemonic(Some("slick")).to_string();
+        let restored = bip39::Mnemonic::parse(&words).unwrap();
+        assert_eq!(
+            deck.bitcoin_extended_keys(Some("slick"), Network::Bitcoin),
+            ExtendedKeys::from_seed(&restored.to_seed(""), Network::Bitcoin)
+        );
+    }
+
+    #[test]
+    fn descriptors() {
+        let key = Deck::new().bitcoin_key(None, Network::Bitcoin);
+        let fingerprint = key.fingerprint().to_string();
+
+        for descriptor in [
+            key.wpkh_descriptor(),
+            key.wpkh_private_descriptor(),
+            key.tr_descriptor(),
+            key.tr_private_descriptor(),
+        ] {
+            let (body, checksum) = descriptor.split_once('#').unwrap();
+            assert_eq!(descriptor_checksum(body).unwrap(), checksum);
+            assert!(body.contains(&format!("([{fingerprint}]")));
+        }
+
+        assert!(key
+            .wpkh_descriptor()
+            .contains(&key.public_key().to_string()));
+        assert!(key.tr_private_descriptor().contains(&key.wif()));
+    }
+}
@@ -1,38 +1,278 @@
-use crate::suit::Suit;
+use crate::rank::{Rank, ALL_RANKS};
+use crate::suit::{Suit, ALL_SUITS};
 use crate::{errors, DECK_SIZE, SUIT_SIZE};
 use std::{self, fmt};
 
-/// Represents a single playing card. The `suit` field is the card's suit, and the `value`
-/// field is the card's face value index from 0 to 12, where ace is zero and king is 12.
+/// Marks bytes in the parsing lookup tables which aren't a valid face value or suit.
+const INVALID: u8 = u8::MAX;
+
+/// Maps each byte to the face value it represents, or `INVALID`.
+const FACE_VALUES: [u8; 256] = {
+    let mut table = [INVALID; 256];
+    let mut value = 1;
+    while value < 9 {
+        table[(b'1' + value) as usize] = value;
+        value += 1;
+    }
+    table[b'A' as usize] = 0;
+    table[b'T' as usize] = 9;
+    table[b'J' as usize] = 10;
+    table[b'Q' as usize] = 11;
+    table[b'K' as usize] = 12;
+    table
+};
+
+/// Maps each byte to the index of the suit it represents, or `INVALID`.
+const SUITS: [u8; 256] = {
+    let mut table = [INVALID; 256];
+    table[b'S' as usize] = 0;
+    table[b'C' as usize] = 1;
+    table[b'H' as usize] = 2;
+    table[b'D' as usize] = 3;
+    table
+};
+
+/// Represents a single playing card, made up of a suit and a face value index
+/// from 0 to 12, where ace is zero and king is 12.
+///
+/// A `Card` can only be constructed with a valid face value, so converting or
+/// formatting a `Card` never fails.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Card {
-    pub suit: Suit,
-    pub value: u32,
+    pub(crate) suit: Suit,
+    pub(crate) value: u32,
 }
 
 impl Card {
+    /// Creates a `Card` from a face value index and a suit. Returns an `Err`
+    /// if `value` is greater than or equal to `SUIT_SIZE`.
+    ///
+    /// ```
+    /// use cardseed::{Card, Suit};
+    ///
+    /// let card = Card::new(9, Suit::Hearts).unwrap();
+    /// assert_eq!(card.to_string(), "TH");
+    /// assert!(Card::new(13, Suit::Hearts).is_err());
+    ///
+    /// // `Card::new` can also be used in `const` contexts.
+    /// const TEN_OF_HEARTS: Card = match Card::new(9, Suit::Hearts) {
+    ///     Ok(card) => card,
+    ///     Err(_) => panic!("invalid card"),
+    /// };
+    /// assert_eq!(TEN_OF_HEARTS, card);
+    /// ```
+    pub const fn new(value: u32, suit: Suit) -> Result<Card, errors::CardError> {
+        if value >= SUIT_SIZE as u32 {
+            return Err(errors::CardError::BadValue(value));
+        }
+        Ok(Card { suit, value })
+    }
+
+    /// Creates a `Card` from its index in the range `[0..52]`. Returns an `Err`
+    /// if `index` is outside this range.
+    ///
+    /// ```
+    /// use cardseed::{Card, Suit};
+    ///
+    /// let card = Card::from_index(17).unwrap();
+    /// assert_eq!(card.suit(), Suit::Clubs);
+    /// assert_eq!(card.value(), 4);
+    /// ```
+    pub const fn from_index(index: u8) -> Result<Card, errors::CardError> {
+        if index as usize >= DECK_SIZE {
+            return Err(errors::CardError::BadIndex(index as u32));
+        }
+
+        Ok(Card {
+            suit: ALL_SUITS[index as usize / SUIT_SIZE],
+            value: index as u32 % SUIT_SIZE as u32,
+        })
+    }
+
     /// Creates an empty `Card`, which represents the ace of spades.
     /// Think of this as the zero card.
-    pub fn ace_of_spades() -> Card {
+    pub const fn ace_of_spades() -> Card {
         Card {
             value: 0,
             suit: Suit::Spades,
         }
     }
+
+    /// Returns the suit of the `Card`.
+    pub const fn suit(&self) -> Suit {
+        self.suit
+    }
+
+    /// Returns the face value index of the `Card` from 0 to 12, where ace is zero
+    /// and king is 12.
+    pub const fn value(&self) -> u32 {
+        self.value
+    }
+
+    /// Returns the rank of the `Card`, the face value as a `Rank`.
+    ///
+    /// ```
+    /// use cardseed::{Card, Rank};
+    ///
+    /// assert_eq!("QH".parse::<Card>().unwrap().rank(), Rank::Queen);
+    /// ```
+    pub const fn rank(&self) -> Rank {
+        ALL_RANKS[self.value as usize]
+    }
+
+    /// Creates the `Card` of a given rank and suit. Unlike `Card::new`, this can't fail.
+    pub const fn from_rank(rank: Rank, suit: Suit) -> Card {
+        Card {
+            value: rank.index(),
+            suit,
+        }
+    }
+
+    /// Returns the index of the `Card` from 0 to 51.
+    ///
+    /// ```
+    /// use cardseed::{Card, Suit};
+    ///
+    /// let card = Card::new(4, Suit::Clubs).unwrap();
+    /// assert_eq!(card.index(), 17);
+    /// ```
+    pub const fn index(&self) -> u32 {
+        self.suit.index() * SUIT_SIZE as u32 + self.value
+    }
+
+    /// Parses a `Card` from its 2-byte ASCII form, such as `b"TH"`, using lookup
+    /// tables instead of decoding characters. This is faster than parsing with
+    /// `FromStr` when parsing many cards, and returns the same errors for ASCII input.
+    ///
+    /// ```
+    /// use cardseed::{Card, Suit};
+    ///
+    /// assert_eq!(Card::parse_ascii(b"TH"), Ok(Card::new(9, Suit::Hearts).unwrap()));
+    /// assert!(Card::parse_ascii(b"TX").is_err());
+    /// ```
+    pub fn parse_ascii(bytes: &[u8; 2]) -> Result<Card, errors::ParseError> {
+        let value = FACE_VALUES[bytes[0] as usize];
+        let suit = SUITS[bytes[1] as usize];
+        if value == INVALID {
+            return Err(errors::ParseError::BadValue {
+                token: String::from_utf8_lossy(bytes).into_owned(),
+                value: char::from(bytes[0]),
+            });
+        }
+        if suit == INVALID {
+            return Err(errors::ParseError::BadSuit {
+                token: String::from_utf8_lossy(bytes).into_owned(),
+                suit: char::from(bytes[1]),
+            });
+        }
+
+        Ok(Card {
+            suit: ALL_SUITS[suit as usize],
+            value: value as u32,
+        })
+    }
+
+    /// Parses a `Card` leniently, as dictated or typed by hand: ignoring case, and
+    /// accepting long-form names like `"queen of hearts"` or `"10 of clubs"`, as well
+    /// as short forms like `"10h"` or `"qh"`. Returns `ParseError::Malformed` if the
+    /// string isn't any recognized form of a card.
+    ///
+    /// ```
+    /// use cardseed::Card;
+    ///
+    /// let queen = "QH".parse::<Card>().unwrap();
+    /// assert_eq!(Card::parse_lenient("Queen of Hearts"), Ok(queen));
+    /// assert_eq!(Card::parse_lenient("queen hearts"), Ok(queen));
+    /// assert_eq!(Card::parse_lenient("qh"), Ok(queen));
+    /// assert_eq!(Card::parse_lenient("10 of clubs"), "TC".parse());
+    /// assert!(Card::parse_lenient("queen of cups").is_err());
+    /// ```
+    pub fn parse_lenient(s: &str) -> Result<Card, errors::ParseError> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match Card::parse_words(&words) {
+            Some((card, used)) if used == words.len() => Ok(card),
+            _ => Err(errors::ParseError::Malformed(String::from(s))),
+        }
+    }
+
+    /// Parses a card from the start of a sequence of words, leniently as described by
+    /// `Card::parse_lenient`. Returns the card and the number of words it spans, or
+    /// `None` if the words don't start with a card.
+    pub(crate) fn parse_words(words: &[&str]) -> Option<(Card, usize)> {
+        let first = *words.first()?;
+        if let Some(rank) = Rank::from_word(first) {
+            let (suit, used) = match words.get(1) {
+                Some(w) if w.eq_ignore_ascii_case("of") => (words.get(2), 3),
+                w => (w, 2),
+            };
+            if let Some(suit) = suit.and_then(|w| Suit::from_word(w)) {
+                return Some((Card::from_rank(rank, suit), used));
+            }
+        }
+
+        // A single word, such as "10h" or "qh".
+        let split = first.len().checked_sub(1)?;
+        let (rank, suit) = (first.get(..split)?, first.get(split..)?);
+        let rank = Rank::from_word(rank)?;
+        let suit = Suit::from_word(suit)?;
+        Some((Card::from_rank(rank, suit), 1))
+    }
+
+    /// Parses a single-word card in any notation `Deck::normalize` accepts: a rank
+    /// followed by a suit, in any case, where the rank may be `10` or a German or
+    /// French face letter, and the suit may be a Unicode symbol. Returns `None` if the
+    /// token isn't a card in any of these notations.
+    pub(crate) fn parse_notation(token: &str) -> Option<Card> {
+        let last = token.chars().next_back()?;
+        let rank = &token[..token.len() - last.len_utf8()];
+        let suit = Suit::from_symbol(last).or(Suit::try_from(last.to_ascii_uppercase()).ok())?;
+        let rank = Rank::from_word(rank).or_else(|| match rank.to_ascii_uppercase().as_str() {
+            // Bube and valet for the jack, dame for the queen, and roi for the king.
+            "B" | "V" => Some(Rank::Jack),
+            "D" => Some(Rank::Queen),
+            "R" => Some(Rank::King),
+            _ => None,
+        })?;
+        Some(Card::from_rank(rank, suit))
+    }
+
+    /// Parses a card token, taking the `parse_ascii` fast path for 2-byte ASCII
+    /// tokens and falling back to `FromStr` for anything else.
+    pub(crate) fn parse_token(token: &str) -> Result<Card, errors::ParseError> {
+        match <&[u8; 2]>::try_from(token.as_bytes()) {
+            Ok(bytes) if bytes.is_ascii() => Card::parse_ascii(bytes),
+            _ => token.parse(),
+        }
+    }
 }
 
+/// Every card of a standard deck in canonical order: ace to king of spades, then
+/// clubs, hearts, and diamonds. This is the order of `Deck::new` and `FullDeck::new`,
+/// available at compile time for building lookup tables and fixed decks.
+///
+/// ```
+/// use cardseed::{Card, STANDARD_DECK};
+///
+/// const LAST: Card = STANDARD_DECK[51];
+/// assert_eq!(LAST.to_string(), "KD");
+/// ```
+pub const STANDARD_DECK: [Card; DECK_SIZE] = {
+    let mut cards = [Card::ace_of_spades(); DECK_SIZE];
+    let mut i = 0;
+    while i < DECK_SIZE {
+        cards[i] = Card {
+            value: (i % SUIT_SIZE) as u32,
+            suit: ALL_SUITS[i / SUIT_SIZE],
+        };
+        i += 1;
+    }
+    cards
+};
+
 impl From<Card> for u32 {
-    /// Convert a `Card` into a `u32` from 0 to 51. Panics if the
-    /// card's value is greater than or equal to `SUIT_SIZE`.
+    /// Convert a `Card` into a `u32` from 0 to 51.
     fn from(card: Card) -> u32 {
-        if card.value >= SUIT_SIZE as u32 {
-            panic!(
-                "attempted to convert card with invalid value {} to u32",
-                card.value
-            );
-        }
-
-        u32::from(card.suit) * SUIT_SIZE as u32 + card.value
+        card.index()
     }
 }
 
@@ -45,13 +285,7 @@ impl TryFrom<u32> for Card {
     /// ```
     /// use cardseed::{Card, Suit};
     ///
-    /// assert_eq!(
-    ///     Card::try_from(17),
-    ///     Ok(Card {
-    ///         suit: Suit::Clubs,
-    ///         value: 4,
-    ///     })
-    /// );
+    /// assert_eq!(Card::try_from(17), Ok(Card::new(4, Suit::Clubs).unwrap()));
     /// ```
     fn try_from(x: u32) -> Result<Card, errors::ParseError> {
         if x >= DECK_SIZE as u32 {
@@ -69,18 +303,27 @@ impl fmt::Display for Card {
     /// Formats a `Card` as a 2-character string. The first character is the `Card`'s
     /// face `value`, and the other is its `suit`.
     ///
-    /// Returns an error if the `Card`'s value is greater than or equal to `SUIT_SIZE`
+    /// The alternate flag formats the card's full name instead, for screen readers
+    /// and for reading a deck back unambiguously.
+    ///
+    /// ```
+    /// use cardseed::Card;
+    ///
+    /// let card = "TH".parse::<Card>().unwrap();
+    /// assert_eq!(format!("{card}"), "TH");
+    /// assert_eq!(format!("{card:#}"), "Ten of Hearts");
+    /// ```
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "{} of {}", self.rank().name(), self.suit.name());
+        }
         let c = match self.value {
             0 => 'A',
             9 => 'T',
             10 => 'J',
             11 => 'Q',
             12 => 'K',
-            v => match char::from_digit(v + 1, 10) {
-                Some(c) => c,
-                None => return Err(fmt::Error {}),
-            },
+            v => char::from(b'1' + v as u8),
         };
         write!(f, "{}{}", c, self.suit)
     }
@@ -97,37 +340,37 @@ impl std::str::FromStr for Card {
     /// use cardseed::{Card, Suit};
     ///
     /// let card = "TH".parse::<Card>().unwrap(); // ten of hearts
-    /// assert_eq!(card, Card {
-    ///     suit: Suit::Hearts,
-    ///     value: 9,
-    /// });
+    /// assert_eq!(card, Card::new(9, Suit::Hearts).unwrap());
     /// ```
     fn from_str(s: &str) -> Result<Card, errors::ParseError> {
         let mut chars = s.chars();
 
         let value = match chars.next() {
-            None => return Err(errors::ParseError::BadString(String::from(s))),
-            Some(c) => match c.to_digit(10) {
-                Some(v) => v - 1,
-                None => match c {
-                    'A' => 0,
-                    'T' => 9,
-                    'J' => 10,
-                    'Q' => 11,
-                    'K' => 12,
-                    _ => return Err(errors::ParseError::BadString(String::from(s))),
-                },
+            None => return Err(errors::ParseError::BadLength(String::from(s))),
+            Some(c) => match c {
+                'A' => 0,
+                '2'..='9' => c as u32 - '1' as u32,
+                'T' => 9,
+                'J' => 10,
+                'Q' => 11,
+                'K' => 12,
+                _ => {
+                    return Err(errors::ParseError::BadValue {
+                        token: String::from(s),
+                        value: c,
+                    })
+                }
             },
         };
         let suit = match chars.next() {
-            Some(c) => Suit::try_from(c)?,
-            None => return Err(errors::ParseError::BadString(String::from(s))),
+            Some(c) => Suit::try_from(c).map_err(|_| errors::ParseError::BadSuit {
+                token: String::from(s),
+                suit: c,
+            })?,
+            None => return Err(errors::ParseError::BadLength(String::from(s))),
         };
 
-        Ok(Card {
-            suit: suit,
-            value: value,
-        })
+        Ok(Card { suit, value })
     }
 }
 
@@ -173,30 +416,110 @@ mod tests {
     }
 
     #[test]
-    fn to_u32() {
-        assert_eq!(
-            u32::from(Card {
-                value: 8,
-                suit: Suit::Spades,
-            }),
-            8
-        );
+    fn index() {
+        assert_eq!(Card::new(8, Suit::Spades).unwrap().index(), 8);
+        assert_eq!(u32::from(Card::new(3, Suit::Clubs).unwrap()), 16);
+        assert_eq!(Card::new(0, Suit::Diamonds).unwrap().index(), 39);
+    }
 
+    #[test]
+    fn constructors() {
         assert_eq!(
-            u32::from(Card {
-                value: 3,
-                suit: Suit::Clubs,
-            }),
-            16
+            Card::new(99, Suit::Hearts),
+            Err(errors::CardError::BadValue(99))
         );
-
         assert_eq!(
-            u32::from(Card {
-                value: 0,
-                suit: Suit::Diamonds,
-            }),
-            39
+            Card::new(13, Suit::Hearts),
+            Err(errors::CardError::BadValue(13))
         );
+        assert_eq!(Card::from_index(52), Err(errors::CardError::BadIndex(52)));
+
+        for i in 0..DECK_SIZE as u8 {
+            let card = Card::from_index(i).unwrap();
+            assert_eq!(card.index(), i as u32);
+            assert_eq!(Card::new(card.value(), card.suit()), Ok(card));
+            assert_eq!(card.to_string().parse::<Card>(), Ok(card));
+        }
+    }
+
+    #[test]
+    fn parse_notation() {
+        for (s, expected) in [
+            ("QH", "QH"),
+            ("qh", "QH"),
+            ("10♥", "TH"),
+            ("t♠", "TS"),
+            ("A♧", "AC"),
+            ("B♦", "JD"),
+            ("vs", "JS"),
+            ("DC", "QC"),
+            ("R♡", "KH"),
+        ] {
+            assert_eq!(Card::parse_notation(s), expected.parse().ok(), "{s}");
+        }
+        for s in ["", "♠", "1H", "XS", "QX", "Q♠♠"] {
+            assert_eq!(Card::parse_notation(s), None, "{s}");
+        }
+    }
+
+    #[test]
+    fn parse_lenient() {
+        for (s, expected) in [
+            ("ace of spades", "AS"),
+            ("ACE OF SPADES", "AS"),
+            ("Two of Diamonds", "2D"),
+            ("10 of clubs", "TC"),
+            ("ten clubs", "TC"),
+            ("jack of heart", "JH"),
+            ("K of D", "KD"),
+            ("10h", "TH"),
+            ("7c", "7C"),
+            ("  Th ", "TH"),
+        ] {
+            assert_eq!(Card::parse_lenient(s), expected.parse(), "{s}");
+        }
+        for s in [
+            "",
+            "of spades",
+            "ace of",
+            "ace of spades of",
+            "one of hearts",
+            "11h",
+            "ace spades hearts",
+        ] {
+            assert_eq!(
+                Card::parse_lenient(s),
+                Err(errors::ParseError::Malformed(String::from(s))),
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_ascii() {
+        for card in STANDARD_DECK {
+            let s = card.to_string();
+            let bytes = <[u8; 2]>::try_from(s.as_bytes()).unwrap();
+            assert_eq!(Card::parse_ascii(&bytes), Ok(card));
+        }
+
+        // Every ASCII token is parsed the same as with `FromStr`, errors included.
+        for a in 0..128u8 {
+            for b in 0..128u8 {
+                let s = String::from_utf8(vec![a, b]).unwrap();
+                assert_eq!(Card::parse_ascii(&[a, b]), s.parse::<Card>());
+                assert_eq!(Card::parse_token(&s), s.parse::<Card>());
+            }
+        }
+        assert_eq!(Card::parse_token("7HX"), "7HX".parse::<Card>());
+        assert_eq!(Card::parse_token("é"), "é".parse::<Card>());
+    }
+
+    #[test]
+    fn standard_deck() {
+        for (i, card) in STANDARD_DECK.iter().enumerate() {
+            assert_eq!(card.index(), i as u32);
+        }
     }
 
     #[test]
@@ -208,6 +531,11 @@ mod tests {
         assert_eq!(Card::try_from(12)?.to_string(), "KS");
         assert_eq!(Card::try_from(50)?.to_string(), "QD");
 
+        assert_eq!(format!("{:#}", Card::try_from(0)?), "Ace of Spades");
+        assert_eq!(format!("{:#}", Card::try_from(32)?), "Seven of Hearts");
+        assert_eq!(format!("{:#}", Card::try_from(23)?), "Jack of Clubs");
+        assert_eq!(format!("{:#}", Card::try_from(51)?), "King of Diamonds");
+
         Ok(())
     }
 
@@ -263,4 +591,37 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn from_string_errors() {
+        assert_eq!(
+            "".parse::<Card>(),
+            Err(errors::ParseError::BadLength(String::from("")))
+        );
+        assert_eq!(
+            "Q".parse::<Card>(),
+            Err(errors::ParseError::BadLength(String::from("Q")))
+        );
+        assert_eq!(
+            "0S".parse::<Card>(),
+            Err(errors::ParseError::BadValue {
+                token: String::from("0S"),
+                value: '0'
+            })
+        );
+        assert_eq!(
+            "1S".parse::<Card>(),
+            Err(errors::ParseError::BadValue {
+                token: String::from("1S"),
+                value: '1'
+            })
+        );
+        assert_eq!(
+            "QX".parse::<Card>(),
+            Err(errors::ParseError::BadSuit {
+                token: String::from("QX"),
+                suit: 'X'
+            })
+        );
+    }
 }
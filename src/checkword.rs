@@ -0,0 +1,79 @@
+use crate::deck::Deck;
+use zeroize::Zeroizing;
+
+/// The `Deck::derive_indexed` purpose under which checkwords are derived, so that a
+/// checkword reveals nothing about any other secret derived from the deck.
+const CHECKWORD_PURPOSE: &str = "checkword";
+
+/// The 256 words a checkword is chosen from. Each word has a distinct four-letter
+/// prefix, so a checkword can be recognized from its first four letters.
+pub const CHECKWORDS: [&str; 256] = [
+    "acid", "acorn", "actor", "adult", "agent", "alarm", "album", "alley", "amber", "angel",
+    "ankle", "apple", "apron", "arrow", "atlas", "attic", "autumn", "award", "bacon", "badge",
+    "bagel", "baker", "ballet", "bamboo", "banana", "banjo", "barn", "basil", "basket", "beach",
+    "bean", "beard", "beaver", "bell", "bench", "berry", "bison", "blade", "blanket", "blossom",
+    "boat", "bonus", "border", "bottle", "bounce", "bracket", "branch", "bread", "brick", "bridge",
+    "broom", "bubble", "bucket", "bugle", "bunny", "button", "cabin", "cactus", "camel", "candle",
+    "canoe", "canyon", "carpet", "carrot", "castle", "cattle", "cave", "cedar", "cello", "chalk",
+    "cherry", "chess", "circle", "cliff", "clock", "cloud", "clover", "coast", "cobra", "coconut",
+    "coffee", "comet", "copper", "coral", "cotton", "cougar", "crane", "crater", "crayon",
+    "cricket", "crystal", "curtain", "cushion", "daisy", "dancer", "desert", "diamond", "dinner",
+    "dolphin", "donkey", "dragon", "drum", "eagle", "echo", "elbow", "ember", "engine", "falcon",
+    "fence", "fern", "ferry", "fiddle", "finch", "flame", "flute", "forest", "fossil", "fox",
+    "frost", "galaxy", "garden", "garlic", "geyser", "ginger", "giraffe", "glacier", "globe",
+    "goose", "grape", "gravel", "guitar", "hammer", "harbor", "harvest", "hazel", "helmet",
+    "hermit", "honey", "horizon", "hornet", "iceberg", "igloo", "island", "ivory", "jacket",
+    "jaguar", "jelly", "jigsaw", "jungle", "kettle", "kitten", "koala", "ladder", "lagoon",
+    "lantern", "lemon", "lettuce", "lily", "lizard", "lobster", "locket", "magnet", "mango",
+    "maple", "marble", "meadow", "melon", "meteor", "mirror", "mitten", "monkey", "moose",
+    "mosaic", "muffin", "napkin", "nectar", "needle", "nickel", "noodle", "nutmeg", "oasis",
+    "ocean", "olive", "onion", "orange", "orbit", "orchid", "otter", "oyster", "paddle", "palace",
+    "panda", "parrot", "peach", "pebble", "pelican", "pencil", "pepper", "piano", "pickle",
+    "pigeon", "pillow", "pirate", "planet", "plum", "pocket", "pony", "poppy", "potato", "pumpkin",
+    "puzzle", "quartz", "quilt", "rabbit", "radish", "raven", "ribbon", "river", "robin", "rocket",
+    "saddle", "salmon", "sandal", "scarf", "shadow", "shovel", "silver", "sketch", "sparrow",
+    "spider", "spoon", "statue", "summit", "sunset", "swan", "tablet", "teapot", "tiger", "timber",
+    "tomato", "trumpet", "tulip", "turtle", "valley", "velvet", "violin", "volcano", "wagon",
+    "walnut", "walrus", "whistle", "willow", "window", "wizard", "yogurt", "zebra",
+];
+
+impl Deck {
+    /// Derives a checkword for the `Deck` and an optional password: a single word
+    /// chosen by 8 bits of a secret derived from them. Write the checkword down
+    /// alongside the physical deck. When the deck is entered again, a different
+    /// checkword reveals a transcription mistake, or a mistyped password, before
+    /// the derived secret is used.
+    ///
+    /// The checkword is derived with `Deck::derive_indexed`, so it reveals nothing
+    /// about `Deck::hash` or any other derived secret. A mistake goes unnoticed with
+    /// probability 1 in 256.
+    ///
+    /// ```
+    /// use cardseed::{Deck, CHECKWORDS};
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let word = deck.checkword(Some("bestpasswordever"));
+    /// assert!(CHECKWORDS.contains(&word));
+    /// ```
+    pub fn checkword(&self, password: Option<&str>) -> &'static str {
+        let secret = Zeroizing::new(self.derive_indexed(password, CHECKWORD_PURPOSE, 0));
+        CHECKWORDS[secret[0] as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkword() {
+        let mut prefixes: Vec<&str> = CHECKWORDS.iter().map(|w| &w[..w.len().min(4)]).collect();
+        prefixes.dedup();
+        assert_eq!(prefixes.len(), CHECKWORDS.len());
+
+        let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+        let secret = deck.derive_indexed(None, "checkword", 0);
+        assert_eq!(deck.checkword(None), CHECKWORDS[secret[0] as usize]);
+        assert_eq!(deck.checkword(None), "geyser");
+    }
+}
@@ -0,0 +1,598 @@
+//! Storing arbitrary bytes in the order of a deck, for instance to keep a short
+//! secret in a deck of cards rather than deriving one from it.
+//!
+//! `encode` uses the deck's full capacity of `CAPACITY` bytes, but a single
+//! misplaced card garbles the whole payload. `encode_ecc` instead spends part of
+//! the capacity on Reed–Solomon parity, so that a deck with a few swapped or
+//! misread cards still decodes to the original payload. `encode_base52` stores
+//! data of any length as a sequence of cards drawn with reshuffling in between.
+//!
+//! ```
+//! use cardseed::codec;
+//!
+//! let deck = codec::encode_ecc(b"hello!", 8).unwrap();
+//!
+//! let mut copy = deck.cards.clone();
+//! copy.swap(3, 4);
+//! copy[10] = copy[11];
+//! let copy = cardseed::Deck { cards: copy };
+//! assert_eq!(codec::decode_ecc(&copy, 6, 8).unwrap(), b"hello!");
+//! ```
+
+use crate::card::{Card, STANDARD_DECK};
+use crate::deck::{CardSet, Deck};
+use crate::errors;
+use crate::secret::{gf_div, gf_mul};
+use crate::DECK_SIZE;
+use zeroize::Zeroizing;
+
+/// The most bytes `encode` can store in a 52-card deck. There are more than
+/// `2^225` orderings of a deck, so any 28-byte number identifies a unique one.
+pub const CAPACITY: usize = 28;
+
+/// The most bytes, payload and parity together, which `encode_ecc` can store.
+pub const ECC_CAPACITY: usize = 18;
+
+/// The most misread cards `decode_ecc` tries to repair, bounding the number of
+/// candidate decks it decodes.
+const MAX_MISREADS: usize = 3;
+
+/// A generator of the multiplicative group of GF(256) with the AES polynomial.
+const GENERATOR: u8 = 3;
+
+/// Encodes up to `CAPACITY` bytes as the order of a complete deck. The same number
+/// of bytes must be passed to `decode` to recover them.
+///
+/// The bytes are read as a big-endian number, which is written in the factorial
+/// number system as the [Lehmer code](https://en.wikipedia.org/wiki/Lehmer_code) of
+/// the deck: each card's rank among the cards not yet placed, ordered by index.
+///
+/// ```
+/// use cardseed::{codec, Deck};
+///
+/// assert_eq!(codec::encode(&[]).unwrap(), Deck::new());
+/// let deck = codec::encode(b"a secret").unwrap();
+/// assert_eq!(codec::decode(&deck, 8).unwrap(), b"a secret");
+/// ```
+pub fn encode(data: &[u8]) -> Result<Deck, errors::CodecError> {
+    if data.len() > CAPACITY {
+        return Err(errors::CodecError::TooLong {
+            len: data.len(),
+            capacity: CAPACITY,
+        });
+    }
+
+    let mut number = Zeroizing::new(data.to_vec());
+    let mut digits = Zeroizing::new([0u8; DECK_SIZE]);
+    for radix in 1..=DECK_SIZE {
+        digits[DECK_SIZE - radix] = div_rem(&mut number, radix as u32) as u8;
+    }
+    Ok(from_lehmer_code(&digits))
+}
+
+/// Decodes `len` bytes stored in a deck by `encode`. Returns an `Err` if the deck
+/// is not a complete deck without duplicates, or if it does not hold a number
+/// which fits in `len` bytes, as is the case for most decks when `len` is short.
+pub fn decode(deck: &Deck, len: usize) -> Result<Vec<u8>, errors::CodecError> {
+    deck.validate()?;
+    let digits = lehmer_code(&deck.cards);
+
+    let mut number = Zeroizing::new(vec![0u8; len]);
+    for (i, &digit) in digits.iter().enumerate() {
+        if !mul_add(&mut number, (DECK_SIZE - i) as u32, digit as u32) {
+            return Err(errors::CodecError::Corrupt);
+        }
+    }
+    Ok(number.to_vec())
+}
+
+/// Encodes `data` with `parity` bytes of Reed–Solomon parity as the order of a
+/// complete deck. Up to `parity / 2` bytes of the encoded payload can be corrupted
+/// and still corrected by `decode_ecc`. An adjacent pair of swapped cards corrupts
+/// at most two bytes.
+///
+/// Each byte of the payload and parity is stored in the Lehmer code digits of two
+/// neighboring cards, so that a mistake in one part of the deck only corrupts the
+/// bytes stored there. The cards after the last byte are left in ascending order.
+/// Returns an `Err` if `data.len() + parity` exceeds `ECC_CAPACITY`.
+pub fn encode_ecc(data: &[u8], parity: usize) -> Result<Deck, errors::CodecError> {
+    if data.len() + parity > ECC_CAPACITY {
+        return Err(errors::CodecError::TooLong {
+            len: data.len() + parity,
+            capacity: ECC_CAPACITY,
+        });
+    }
+
+    let mut codeword = Zeroizing::new(data.to_vec());
+    codeword.extend(rs_parity(data, parity));
+
+    let mut digits = Zeroizing::new([0u8; DECK_SIZE]);
+    for (i, &byte) in codeword.iter().enumerate() {
+        let low_radix = (DECK_SIZE - 2 * i - 1) as u8;
+        digits[2 * i] = byte / low_radix;
+        digits[2 * i + 1] = byte % low_radix;
+    }
+    Ok(from_lehmer_code(&digits))
+}
+
+/// Decodes `len` bytes stored in a deck by `encode_ecc` with `parity` bytes of
+/// parity, correcting errors in the deck where possible.
+///
+/// A deck in which a few cards were misread, so that some card appears twice and
+/// another is missing, is repaired by trying each way of replacing a duplicate with
+/// a missing card. Returns an `Err` if the deck isn't 52 cards long, or if it has
+/// too many errors to correct.
+pub fn decode_ecc(deck: &Deck, len: usize, parity: usize) -> Result<Vec<u8>, errors::CodecError> {
+    if len + parity > ECC_CAPACITY {
+        return Err(errors::CodecError::TooLong {
+            len: len + parity,
+            capacity: ECC_CAPACITY,
+        });
+    }
+    if deck.cards.len() != DECK_SIZE {
+        return Err(errors::DeckError::WrongLength {
+            expected: DECK_SIZE,
+            found: deck.cards.len(),
+        }
+        .into());
+    }
+
+    let mut best: Option<(usize, Zeroizing<Vec<u8>>)> = None;
+    for cards in repairs(&deck.cards)? {
+        let digits = lehmer_code(&cards);
+        let mut codeword = Zeroizing::new(Vec::with_capacity(len + parity));
+        for i in 0..len + parity {
+            let low_radix = (DECK_SIZE - 2 * i - 1) as u32;
+            let byte = digits[2 * i] as u32 * low_radix + digits[2 * i + 1] as u32;
+            // Bytes corrupted beyond 255 are left for the decoder to correct.
+            codeword.push(byte as u8);
+        }
+
+        if let Some(corrected) = rs_correct(&mut codeword, parity) {
+            if best.as_ref().is_none_or(|(fewest, _)| corrected < *fewest) {
+                best = Some((corrected, codeword));
+            }
+        }
+    }
+
+    let (_, codeword) = best.ok_or(errors::CodecError::Corrupt)?;
+    Ok(codeword[..len].to_vec())
+}
+
+/// Returns the complete decks which could have been misread as `cards`, by
+/// replacing one occurrence of each duplicated card with a missing card.
+fn repairs(cards: &[Card]) -> Result<Vec<Vec<Card>>, errors::CodecError> {
+    let mut positions = vec![Vec::new(); DECK_SIZE];
+    for (position, card) in cards.iter().enumerate() {
+        positions[card.index() as usize].push(position);
+    }
+
+    let mut duplicates = Vec::new();
+    let mut missing = Vec::new();
+    for (card, found) in STANDARD_DECK.iter().zip(&positions) {
+        match found.len() {
+            0 => missing.push(*card),
+            1 => {}
+            _ => duplicates.extend_from_slice(&found[..]),
+        }
+    }
+    if missing.len() > MAX_MISREADS {
+        return Err(errors::CodecError::Corrupt);
+    }
+
+    // Choose which of the duplicated positions were misread, then which missing
+    // card belongs at each of them.
+    let mut candidates = Vec::new();
+    for chosen in subsets(&duplicates, missing.len()) {
+        let mut repaired = cards.to_vec();
+        for order in permutations(&missing) {
+            for (&position, &card) in chosen.iter().zip(&order) {
+                repaired[position] = card;
+            }
+            let mut seen = CardSet::default();
+            if repaired.iter().all(|&card| seen.insert(card)) {
+                candidates.push(repaired.clone());
+            }
+        }
+    }
+    Ok(candidates)
+}
+
+/// Returns every subset of `k` items.
+fn subsets(items: &[usize], k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    let mut result = Vec::new();
+    for (i, &item) in items.iter().enumerate() {
+        for mut rest in subsets(&items[i + 1..], k - 1) {
+            rest.insert(0, item);
+            result.push(rest);
+        }
+    }
+    result
+}
+
+/// Returns every ordering of `items`.
+fn permutations(items: &[Card]) -> Vec<Vec<Card>> {
+    if items.is_empty() {
+        return vec![vec![]];
+    }
+    let mut result = Vec::new();
+    for i in 0..items.len() {
+        let mut rest = items.to_vec();
+        let item = rest.remove(i);
+        for mut order in permutations(&rest) {
+            order.insert(0, item);
+            result.push(order);
+        }
+    }
+    result
+}
+
+/// Returns the Lehmer code of a complete deck: the rank of each card among the
+/// cards after it, ordered by index.
+pub(crate) fn lehmer_code(cards: &[Card]) -> [u8; DECK_SIZE] {
+    let mut digits = [0u8; DECK_SIZE];
+    let mut unplaced: u64 = (1 << DECK_SIZE) - 1;
+    for (digit, card) in digits.iter_mut().zip(cards) {
+        let bit = 1u64 << card.index();
+        *digit = (unplaced & (bit - 1)).count_ones() as u8;
+        unplaced &= !bit;
+    }
+    digits
+}
+
+/// Builds the deck with the given Lehmer code. Each digit must be less than the
+/// number of cards remaining at its position.
+pub(crate) fn from_lehmer_code(digits: &[u8; DECK_SIZE]) -> Deck {
+    let mut remaining = STANDARD_DECK.to_vec();
+    Deck {
+        cards: digits
+            .iter()
+            .map(|&digit| remaining.remove(digit as usize))
+            .collect(),
+    }
+}
+
+/// Encodes bytes of any length as a sequence of cards, each a base-52 digit given by
+/// its index. The cards are meant to be drawn one at a time with the deck reshuffled
+/// between draws, so a card may repeat, and unlike `encode` there is no limit on the
+/// length of the data.
+///
+/// The bytes are read as a big-endian number and written in base 52 with the most
+/// significant digit first. Each leading zero byte is written as one ace of spades,
+/// the zero digit, so that `decode_base52` recovers it, as in Base58.
+///
+/// ```
+/// use cardseed::codec;
+///
+/// let cards = codec::encode_base52(b"a longer secret than a deck can hold");
+/// assert_eq!(codec::decode_base52(&cards), b"a longer secret than a deck can hold");
+/// assert_eq!(codec::encode_base52(&[0, 0, 53]).to_string(), "AS AS 2S 2S");
+/// ```
+pub fn encode_base52(data: &[u8]) -> Deck {
+    let zeros = data.iter().take_while(|&&byte| byte == 0).count();
+    let mut number = Zeroizing::new(data[zeros..].to_vec());
+    let mut digits = Zeroizing::new(Vec::new());
+    let mut start = 0;
+    while start < number.len() {
+        digits.push(div_rem(&mut number[start..], DECK_SIZE as u32) as u8);
+        while start < number.len() && number[start] == 0 {
+            start += 1;
+        }
+    }
+
+    let cards = std::iter::repeat_n(0, zeros)
+        .chain(digits.iter().rev().copied())
+        .map(|digit| STANDARD_DECK[digit as usize])
+        .collect();
+    Deck { cards }
+}
+
+/// Decodes bytes stored in a sequence of cards by `encode_base52`. Every sequence
+/// of cards decodes to some bytes, so mistakes in transcription go undetected.
+pub fn decode_base52(deck: &Deck) -> Vec<u8> {
+    let zeros = deck
+        .cards
+        .iter()
+        .take_while(|&&card| card.index() == 0)
+        .count();
+    let digits = &deck.cards[zeros..];
+
+    // Each base-52 digit holds less than six bits, so this never overflows.
+    let mut number = Zeroizing::new(vec![0u8; digits.len() * 6 / 8 + 1]);
+    for card in digits {
+        let fits = mul_add(&mut number, DECK_SIZE as u32, card.index());
+        debug_assert!(fits);
+    }
+
+    let significant = number.iter().position(|&byte| byte != 0);
+    let mut data = vec![0u8; zeros];
+    data.extend_from_slice(&number[significant.unwrap_or(number.len())..]);
+    data
+}
+
+/// Divides a big-endian number in place, returning the remainder.
+fn div_rem(number: &mut [u8], divisor: u32) -> u32 {
+    let mut remainder = 0;
+    for byte in number.iter_mut() {
+        let current = remainder << 8 | *byte as u32;
+        *byte = (current / divisor) as u8;
+        remainder = current % divisor;
+    }
+    remainder
+}
+
+/// Multiplies a big-endian number in place and adds to it, returning false if
+/// the result overflows.
+fn mul_add(number: &mut [u8], factor: u32, addend: u32) -> bool {
+    let mut carry = addend;
+    for byte in number.iter_mut().rev() {
+        let current = *byte as u32 * factor + carry;
+        *byte = current as u8;
+        carry = current >> 8;
+    }
+    carry == 0
+}
+
+/// Returns `GENERATOR` raised to a power.
+fn gf_pow(exponent: usize) -> u8 {
+    (0..exponent % 255).fold(1, |acc, _| gf_mul(acc, GENERATOR))
+}
+
+/// Evaluates a polynomial, with coefficients ordered from the highest degree, at `x`.
+fn poly_eval(poly: &[u8], x: u8) -> u8 {
+    poly.iter().fold(0, |acc, &c| gf_mul(acc, x) ^ c)
+}
+
+/// Computes the Reed–Solomon parity of `data`: the remainder of `data * x^parity`
+/// divided by the generator polynomial with roots `GENERATOR^0..GENERATOR^parity`.
+fn rs_parity(data: &[u8], parity: usize) -> Vec<u8> {
+    // The generator polynomial, ordered from the highest degree.
+    let mut generator = vec![1u8];
+    for k in 0..parity {
+        let root = gf_pow(k);
+        let mut next = generator.clone();
+        next.push(0);
+        for (i, &c) in generator.iter().enumerate() {
+            next[i + 1] ^= gf_mul(c, root);
+        }
+        generator = next;
+    }
+
+    let mut remainder = vec![0u8; parity];
+    for &byte in data {
+        let factor = byte ^ remainder.first().copied().unwrap_or(0);
+        remainder.rotate_left(1);
+        if let Some(last) = remainder.last_mut() {
+            *last = 0;
+        }
+        for (r, &g) in remainder.iter_mut().zip(&generator[1..]) {
+            *r ^= gf_mul(g, factor);
+        }
+    }
+    remainder
+}
+
+/// Corrects up to `parity / 2` corrupted bytes of a Reed–Solomon codeword in place,
+/// using the Berlekamp–Massey algorithm and Forney's formula. Returns the number
+/// of bytes corrected, or `None` if the codeword has too many errors to correct.
+fn rs_correct(codeword: &mut [u8], parity: usize) -> Option<usize> {
+    let syndromes: Vec<u8> = (0..parity)
+        .map(|k| poly_eval(codeword, gf_pow(k)))
+        .collect();
+    if syndromes.iter().all(|&s| s == 0) {
+        return Some(0);
+    }
+
+    // Berlekamp–Massey, with polynomials ordered from the lowest degree.
+    let mut locator = vec![1u8];
+    let mut previous = vec![1u8];
+    let mut errors = 0;
+    let mut shift = 1;
+    let mut last_discrepancy = 1u8;
+    for n in 0..parity {
+        let discrepancy = (0..=errors.min(locator.len() - 1))
+            .fold(0, |acc, i| acc ^ gf_mul(locator[i], syndromes[n - i]));
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+
+        let scale = gf_div(discrepancy, last_discrepancy);
+        let mut next = locator.clone();
+        next.resize(next.len().max(previous.len() + shift), 0);
+        for (i, &p) in previous.iter().enumerate() {
+            next[i + shift] ^= gf_mul(scale, p);
+        }
+        if 2 * errors <= n {
+            previous = locator;
+            errors = n + 1 - errors;
+            last_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+        locator = next;
+    }
+    while locator.last() == Some(&0) {
+        locator.pop();
+    }
+    if locator.len() - 1 != errors || 2 * errors > parity {
+        return None;
+    }
+
+    // The error evaluator, the product of the syndrome and locator polynomials
+    // truncated to `parity` terms.
+    let mut evaluator = vec![0u8; parity];
+    for (i, &l) in locator.iter().enumerate() {
+        for (j, &s) in syndromes.iter().enumerate() {
+            if i + j < parity {
+                evaluator[i + j] ^= gf_mul(l, s);
+            }
+        }
+    }
+
+    // Chien search for the roots of the locator, each the inverse of an error's
+    // position, followed by Forney's formula for the error's value.
+    let eval_low = |poly: &[u8], x: u8| poly.iter().rev().fold(0, |acc, &c| gf_mul(acc, x) ^ c);
+    let derivative: Vec<u8> = locator
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, &c)| if i % 2 == 1 { c } else { 0 })
+        .collect();
+    let mut found = 0;
+    for degree in 0..codeword.len() {
+        let position = gf_pow(degree);
+        let inverse = gf_div(1, position);
+        if eval_low(&locator, inverse) != 0 {
+            continue;
+        }
+        let denominator = eval_low(&derivative, inverse);
+        if denominator == 0 {
+            return None;
+        }
+        let magnitude = gf_mul(position, gf_div(eval_low(&evaluator, inverse), denominator));
+        let index = codeword.len() - 1 - degree;
+        codeword[index] ^= magnitude;
+        found += 1;
+    }
+    if found != errors {
+        return None;
+    }
+
+    let corrected = (0..parity).all(|k| poly_eval(codeword, gf_pow(k)) == 0);
+    corrected.then_some(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn lehmer_code() {
+        let deck = Deck::new().shuffle();
+        let digits = super::lehmer_code(&deck.cards);
+        for (i, &digit) in digits.iter().enumerate() {
+            assert!((digit as usize) < DECK_SIZE - i);
+        }
+        assert_eq!(from_lehmer_code(&digits), deck);
+        assert_eq!(super::lehmer_code(&Deck::new().cards), [0; DECK_SIZE]);
+    }
+
+    #[test]
+    fn encode_decode() {
+        let data = [0xffu8; CAPACITY];
+        let deck = encode(&data).unwrap();
+        assert!(deck.validate().is_ok());
+        assert_eq!(decode(&deck, CAPACITY).unwrap(), data);
+
+        // Leading zeros are preserved by the length.
+        let deck = encode(&[0, 0, 1]).unwrap();
+        assert_eq!(decode(&deck, 3).unwrap(), [0, 0, 1]);
+        assert_eq!(decode(&deck, 1).unwrap(), [1]);
+
+        // The least significant digit orders the last two cards.
+        let deck = encode(&[1]).unwrap();
+        assert_eq!(deck.cards[..50], Deck::new().cards[..50]);
+        assert_eq!(deck.cards[50], Deck::new().cards[51]);
+
+        assert_eq!(
+            encode(&[0; CAPACITY + 1]),
+            Err(errors::CodecError::TooLong {
+                len: CAPACITY + 1,
+                capacity: CAPACITY
+            })
+        );
+        let reversed = Deck {
+            cards: Deck::new().cards.into_iter().rev().collect(),
+        };
+        assert_eq!(decode(&reversed, 4), Err(errors::CodecError::Corrupt));
+        assert!(matches!(
+            decode(&"AS".parse().unwrap(), 1),
+            Err(errors::CodecError::Deck(_))
+        ));
+    }
+
+    #[test]
+    fn base52() {
+        assert!(encode_base52(&[]).is_empty());
+        assert_eq!(decode_base52(&Deck { cards: vec![] }), b"");
+        assert_eq!(encode_base52(&[0]).to_string(), "AS");
+        assert_eq!(encode_base52(&[51]).to_string(), "KD");
+        assert_eq!(encode_base52(&[52]).to_string(), "2S AS");
+        assert_eq!(encode_base52(&[1, 0]).to_string(), "5S TD");
+
+        let data: Vec<u8> = (0..200).map(|i| (i * 37 % 256) as u8).collect();
+        for len in [1, 2, 27, 28, 29, 100, 200] {
+            let mut data = data[..len].to_vec();
+            assert_eq!(decode_base52(&encode_base52(&data)), data);
+            data[0] = 0;
+            assert_eq!(decode_base52(&encode_base52(&data)), data);
+        }
+        assert_eq!(encode_base52(&[0xff; 100]).len(), 141);
+    }
+
+    #[test]
+    fn reed_solomon() {
+        let data = b"cardseed".to_vec();
+        let mut codeword = data.clone();
+        codeword.extend(rs_parity(&data, 6));
+        assert_eq!(rs_correct(&mut codeword.clone(), 6), Some(0));
+
+        for errors in 1..=3 {
+            let mut corrupted = codeword.clone();
+            for i in 0..errors {
+                corrupted[i * 4] ^= 0x5a + i as u8;
+            }
+            assert_eq!(rs_correct(&mut corrupted, 6), Some(errors));
+            assert_eq!(corrupted, codeword);
+        }
+
+        let mut corrupted = codeword.clone();
+        for byte in corrupted.iter_mut().take(4) {
+            *byte ^= 0xff;
+        }
+        let result = rs_correct(&mut corrupted, 6);
+        assert!(result.is_none() || corrupted != codeword);
+    }
+
+    #[test]
+    fn ecc() {
+        let data = b"0123456789";
+        let deck = encode_ecc(data, 8).unwrap();
+        assert!(deck.validate().is_ok());
+        assert_eq!(decode_ecc(&deck, 10, 8).unwrap(), data);
+        assert!(deck.cards[36..]
+            .windows(2)
+            .all(|w| w[0].index() < w[1].index()));
+
+        // Two adjacent swaps anywhere in the encoded cards are corrected.
+        for i in 0..35 {
+            let mut cards = deck.cards.clone();
+            cards.swap(i, i + 1);
+            cards.swap(35 - i / 2, 34 - i / 2);
+            let swapped = Deck { cards };
+            assert_eq!(decode_ecc(&swapped, 10, 8).unwrap(), data, "swap at {i}");
+        }
+
+        // So are misread cards, which duplicate another card.
+        let mut cards = deck.cards.clone();
+        cards[5] = cards[30];
+        cards[20] = cards[0];
+        assert_eq!(decode_ecc(&Deck { cards }, 10, 8).unwrap(), data);
+
+        assert!(matches!(
+            decode_ecc(&Deck::new(), 19, 0),
+            Err(errors::CodecError::TooLong { .. })
+        ));
+        assert!(encode_ecc(data, 9).is_err());
+        assert!(matches!(
+            decode_ecc(&"AS".parse().unwrap(), 1, 2),
+            Err(errors::CodecError::Deck(_))
+        ));
+    }
+}
@@ -0,0 +1,121 @@
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::suit::Suit;
+use std::fmt;
+
+/// The ANSI escape sequence which switches the terminal's foreground color to red.
+const RED: &str = "\x1b[31m";
+
+/// The ANSI escape sequence which resets the terminal's colors.
+const RESET: &str = "\x1b[0m";
+
+/// Formats a `Card` or `Deck` for a terminal which supports ANSI colors, with suit
+/// symbols in place of suit letters, and hearts and diamonds in red. Spades and clubs
+/// are left in the terminal's default color. Returned by `Card::colored` and
+/// `Deck::colored`.
+#[derive(Clone, Copy, Debug)]
+pub struct Colored<'a, T>(&'a T);
+
+impl Suit {
+    /// Returns the Unicode symbol of the `Suit`, such as `♠` for spades.
+    pub const fn symbol(self) -> char {
+        match self {
+            Suit::Spades => '♠',
+            Suit::Clubs => '♣',
+            Suit::Hearts => '♥',
+            Suit::Diamonds => '♦',
+        }
+    }
+
+    /// Returns the `Suit` of a Unicode suit symbol, either filled like `♠` or outlined
+    /// like `♤`, or `None` if the character isn't a suit symbol.
+    ///
+    /// ```
+    /// use cardseed::Suit;
+    ///
+    /// assert_eq!(Suit::from_symbol('♥'), Some(Suit::Hearts));
+    /// assert_eq!(Suit::from_symbol('♢'), Some(Suit::Diamonds));
+    /// assert_eq!(Suit::from_symbol('H'), None);
+    /// ```
+    pub const fn from_symbol(c: char) -> Option<Suit> {
+        match c {
+            '♠' | '♤' => Some(Suit::Spades),
+            '♣' | '♧' => Some(Suit::Clubs),
+            '♥' | '♡' => Some(Suit::Hearts),
+            '♦' | '♢' => Some(Suit::Diamonds),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the `Suit` is red: hearts or diamonds.
+    pub const fn is_red(self) -> bool {
+        matches!(self, Suit::Hearts | Suit::Diamonds)
+    }
+}
+
+impl Card {
+    /// Returns a wrapper which displays the `Card` with its suit symbol, colored red for
+    /// hearts and diamonds with ANSI escape codes.
+    ///
+    /// ```
+    /// use cardseed::Card;
+    ///
+    /// let card = "TH".parse::<Card>().unwrap();
+    /// assert_eq!(card.colored().to_string(), "\x1b[31mT♥\x1b[0m");
+    /// assert_eq!("KS".parse::<Card>().unwrap().colored().to_string(), "K♠");
+    /// ```
+    pub fn colored(&self) -> Colored<'_, Card> {
+        Colored(self)
+    }
+}
+
+impl Deck {
+    /// Returns a wrapper which displays the `Deck` as space-delimited cards, each
+    /// formatted like `Card::colored`, which makes long listings easier to proofread.
+    pub fn colored(&self) -> Colored<'_, Deck> {
+        Colored(self)
+    }
+}
+
+impl fmt::Display for Colored<'_, Card> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let card = self.0;
+        let symbol = card.suit().symbol();
+        if card.suit().is_red() {
+            write!(f, "{RED}{}{symbol}{RESET}", card.rank())
+        } else {
+            write!(f, "{}{symbol}", card.rank())
+        }
+    }
+}
+
+impl fmt::Display for Colored<'_, Deck> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, card) in self.0.cards.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", card.colored())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colored() {
+        let deck = "AS 2C 3H 4D".parse::<Deck>().unwrap();
+        assert_eq!(
+            deck.colored().to_string(),
+            "A♠ 2♣ \x1b[31m3♥\x1b[0m \x1b[31m4♦\x1b[0m"
+        );
+        assert_eq!(Deck { cards: vec![] }.colored().to_string(), "");
+        assert!(Suit::all()
+            .filter(|s| s.is_red())
+            .eq([Suit::Hearts, Suit::Diamonds]));
+        assert!(Suit::all().all(|s| Suit::from_symbol(s.symbol()) == Some(s)));
+    }
+}
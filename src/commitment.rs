@@ -0,0 +1,139 @@
+//! A commit-reveal protocol for shuffled decks. One party commits to a deck by
+//! publishing its `Commitment` before play begins, and reveals the deck and nonce
+//! afterwards, so that others can check the deck wasn't changed in between. Online
+//! games and audits can use this to show a shuffle was fixed in advance.
+//!
+//! ```
+//! use cardseed::commitment::{self, Commitment};
+//! use cardseed::Deck;
+//!
+//! # #[cfg(feature = "rand")] {
+//! let deck = Deck::new().shuffle();
+//! let nonce = commitment::random_nonce();
+//! let published = commitment::commit(&deck, &nonce).to_string();
+//!
+//! // Later, once the deck and nonce are revealed:
+//! let commitment = published.parse::<Commitment>().unwrap();
+//! assert!(commitment::verify_reveal(&commitment, &deck, &nonce));
+//! # }
+//! ```
+
+use crate::deck::Deck;
+use crate::errors;
+#[cfg(feature = "rand")]
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// Domain separation tag which begins every committed preimage.
+const COMMIT_TAG: &[u8] = b"cardseed/commit";
+
+/// The number of bytes in a commitment nonce.
+pub const NONCE_SIZE: usize = 32;
+
+/// A binding, hiding commitment to a deck: a SHA256 hash which reveals nothing
+/// about the deck until its nonce is revealed, and which no other deck and nonce
+/// can be found to match. Formats and parses as 64 hex digits.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Commitment(pub [u8; 32]);
+
+impl fmt::Display for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+impl std::str::FromStr for Commitment {
+    type Err = errors::ParseError;
+
+    fn from_str(s: &str) -> Result<Commitment, errors::ParseError> {
+        let mut hash = [0u8; 32];
+        hex::decode_to_slice(s, &mut hash)
+            .map_err(|_| errors::ParseError::Malformed(String::from(s)))?;
+        Ok(Commitment(hash))
+    }
+}
+
+/// Returns a fresh nonce from the operating system's secure random number
+/// generator. A nonce must never be reused, since the same deck and nonce always
+/// produce the same commitment.
+#[cfg(feature = "rand")]
+pub fn random_nonce() -> [u8; NONCE_SIZE] {
+    random_nonce_with_rng(&mut rand::rngs::OsRng)
+}
+
+/// Returns a fresh nonce from the given secure random number generator, for
+/// platforms without an operating system RNG.
+#[cfg(feature = "rand")]
+pub fn random_nonce_with_rng<R: CryptoRng + RngCore + ?Sized>(rng: &mut R) -> [u8; NONCE_SIZE] {
+    let mut nonce = [0u8; NONCE_SIZE];
+    rng.fill_bytes(&mut nonce);
+    nonce
+}
+
+/// Commits to a deck with a secret random nonce. The commitment is the SHA256 hash
+/// of:
+///
+/// ```text
+/// "cardseed/commit" || u32be(len(deck)) || deck || nonce
+/// ```
+///
+/// where `deck` is the packed binary deck, one card index per byte.
+pub fn commit(deck: &Deck, nonce: &[u8; NONCE_SIZE]) -> Commitment {
+    let mut hasher = Sha256::new();
+    hasher.update(COMMIT_TAG);
+    hasher.update((deck.cards.len() as u32).to_be_bytes());
+    for card in &deck.cards {
+        hasher.update([card.index() as u8]);
+    }
+    hasher.update(nonce);
+    Commitment(hasher.finalize().into())
+}
+
+/// Checks that a revealed deck and nonce match a commitment published earlier.
+pub fn verify_reveal(commitment: &Commitment, deck: &Deck, nonce: &[u8; NONCE_SIZE]) -> bool {
+    commit(deck, nonce) == *commitment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn commit_reveal() {
+        let deck = "AS 3H KC".parse::<Deck>().unwrap();
+        let nonce = [7u8; NONCE_SIZE];
+        let commitment = commit(&deck, &nonce);
+
+        let mut preimage = b"cardseed/commit\x00\x00\x00\x03\x00\x1c\x19".to_vec();
+        preimage.extend_from_slice(&nonce);
+        assert_eq!(commitment.0, <[u8; 32]>::from(Sha256::digest(&preimage)));
+
+        assert!(verify_reveal(&commitment, &deck, &nonce));
+        assert!(!verify_reveal(&commitment, &deck, &[8u8; NONCE_SIZE]));
+        assert!(!verify_reveal(
+            &commitment,
+            &"AS KC 3H".parse().unwrap(),
+            &nonce
+        ));
+        assert_ne!(random_nonce(), random_nonce());
+
+        use rand::SeedableRng;
+        let seeded = || rand_chacha::ChaCha20Rng::seed_from_u64(1);
+        assert_eq!(
+            random_nonce_with_rng(&mut seeded()),
+            random_nonce_with_rng(&mut seeded())
+        );
+    }
+
+    #[test]
+    fn parse() {
+        let commitment = commit(&Deck::new(), &[0u8; NONCE_SIZE]);
+        let s = commitment.to_string();
+        assert_eq!(s.len(), 64);
+        assert_eq!(s.parse::<Commitment>(), Ok(commitment));
+        assert!(s[1..].parse::<Commitment>().is_err());
+        assert!("zz".repeat(32).parse::<Commitment>().is_err());
+    }
+}
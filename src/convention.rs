@@ -0,0 +1,183 @@
+use crate::card::{Card, STANDARD_DECK};
+use crate::deck::Deck;
+use crate::errors;
+use crate::rank::Rank;
+use crate::suit::Suit;
+use crate::DECK_SIZE;
+
+/// A convention for ordering the cards of a standard deck, which determines the
+/// canonical deck order and the numbering of cards from 0 to 51.
+///
+/// The crate's own numbering, used by `Card::index`, `Deck::new`, and everything
+/// derived from them, is always `Convention::Standard`. Other conventions are
+/// applied explicitly, and the order of each one is fixed: a new ordering will
+/// always be added as a new variant rather than changing an existing one.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Convention {
+    /// Spades, clubs, hearts, then diamonds, each from ace to king. This is the
+    /// order of `Deck::new` and `Card::index`.
+    #[default]
+    Standard,
+    /// Bridge order: clubs, diamonds, hearts, then spades, each from deuce to ace,
+    /// so that cards are ordered by their rank in bridge.
+    Bridge,
+    /// The order of a newly opened deck from most US manufacturers, alternating
+    /// colors: spades and diamonds from ace to king, then clubs and hearts from king
+    /// to ace.
+    NewDeck,
+}
+
+impl Convention {
+    /// Returns the suits in the order of the convention, each with whether its
+    /// ranks run from ace to king, as opposed to the reverse.
+    fn suits(self) -> [(Suit, bool); 4] {
+        match self {
+            Convention::Standard => [
+                (Suit::Spades, true),
+                (Suit::Clubs, true),
+                (Suit::Hearts, true),
+                (Suit::Diamonds, true),
+            ],
+            Convention::Bridge => [
+                (Suit::Clubs, true),
+                (Suit::Diamonds, true),
+                (Suit::Hearts, true),
+                (Suit::Spades, true),
+            ],
+            Convention::NewDeck => [
+                (Suit::Spades, true),
+                (Suit::Diamonds, true),
+                (Suit::Clubs, false),
+                (Suit::Hearts, false),
+            ],
+        }
+    }
+
+    /// Returns the ranks of a suit in the order of the convention.
+    fn ranks(self, ascending: bool) -> Vec<Rank> {
+        let mut ranks: Vec<Rank> = Rank::all().collect();
+        if self == Convention::Bridge {
+            ranks.rotate_left(1);
+        }
+        if !ascending {
+            ranks.reverse();
+        }
+        ranks
+    }
+
+    /// Returns every card of a standard deck in the order of the convention.
+    ///
+    /// ```
+    /// use cardseed::Convention;
+    ///
+    /// let order = Convention::Bridge.order();
+    /// assert_eq!(order[0].to_string(), "2C");
+    /// assert_eq!(order[51].to_string(), "AS");
+    /// ```
+    pub fn order(self) -> [Card; DECK_SIZE] {
+        let mut order = STANDARD_DECK;
+        let cards = self.suits().into_iter().flat_map(|(suit, ascending)| {
+            self.ranks(ascending)
+                .into_iter()
+                .map(move |rank| Card::from_rank(rank, suit))
+        });
+        for (slot, card) in order.iter_mut().zip(cards) {
+            *slot = card;
+        }
+        order
+    }
+
+    /// Returns the position of a card in the order of the convention, from 0 to 51.
+    pub fn index(self, card: Card) -> u32 {
+        let (suit_position, ascending) = self
+            .suits()
+            .iter()
+            .enumerate()
+            .find(|(_, (suit, _))| *suit == card.suit())
+            .map(|(i, &(_, ascending))| (i, ascending))
+            .expect("every suit is ordered");
+        let rank_position = self
+            .ranks(ascending)
+            .iter()
+            .position(|&rank| rank == card.rank())
+            .expect("every rank is ordered");
+        (suit_position * Rank::all().len() + rank_position) as u32
+    }
+
+    /// Returns the card at a position from 0 to 51 in the order of the convention.
+    /// Returns an `Err` if the index is out of range.
+    pub fn card(self, index: u32) -> Result<Card, errors::CardError> {
+        self.order()
+            .get(index as usize)
+            .copied()
+            .ok_or(errors::CardError::BadIndex(index))
+    }
+}
+
+impl Deck {
+    /// Creates a new full `Deck` in the canonical order of a `Convention`.
+    /// `Deck::new_in(Convention::Standard)` is the same as `Deck::new()`.
+    pub fn new_in(convention: Convention) -> Deck {
+        Deck {
+            cards: convention.order().to_vec(),
+        }
+    }
+
+    /// Sorts the cards of the `Deck` into the order of a `Convention`.
+    ///
+    /// ```
+    /// use cardseed::{Convention, Deck};
+    ///
+    /// let mut deck = "AS KH 2C 3D".parse::<Deck>().unwrap();
+    /// deck.sort(Convention::Bridge);
+    /// assert_eq!(deck.to_string(), "2C 3D KH AS");
+    /// deck.sort(Convention::NewDeck);
+    /// assert_eq!(deck.to_string(), "AS 3D 2C KH");
+    /// ```
+    pub fn sort(&mut self, convention: Convention) {
+        self.cards
+            .sort_by_cached_key(|&card| convention.index(card));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard() {
+        assert_eq!(Convention::default().order(), STANDARD_DECK);
+        assert_eq!(Deck::new_in(Convention::Standard), Deck::new());
+        for card in STANDARD_DECK {
+            assert_eq!(Convention::Standard.index(card), card.index());
+        }
+    }
+
+    #[test]
+    fn orders() {
+        let new_deck = Deck::new_in(Convention::NewDeck).to_string();
+        assert!(new_deck.starts_with("AS 2S"));
+        assert!(new_deck.contains("KD KC QC"));
+        assert!(new_deck.ends_with("2H AH"));
+
+        for convention in [
+            Convention::Standard,
+            Convention::Bridge,
+            Convention::NewDeck,
+        ] {
+            let order = convention.order();
+            assert!(Deck::new_in(convention).validate().is_ok());
+            for (i, &card) in order.iter().enumerate() {
+                assert_eq!(convention.index(card), i as u32);
+                assert_eq!(convention.card(i as u32), Ok(card));
+            }
+            assert_eq!(convention.card(52), Err(errors::CardError::BadIndex(52)));
+            assert_eq!(convention.card(300), Err(errors::CardError::BadIndex(300)));
+
+            let mut shuffled = Deck::new_in(Convention::Bridge);
+            shuffled.cards.reverse();
+            shuffled.sort(convention);
+            assert_eq!(shuffled.cards, order);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::deck::Deck;
+use crate::errors;
+use bitcoin::bech32::{self, Bech32, Hrp};
+use bitcoin::bip32::{ChildNumber, Xpriv};
+use bitcoin::hashes::{hash160, Hash};
+use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+use bitcoin::NetworkKind;
+use zeroize::Zeroizing;
+
+/// The bech32 prefix of Cosmos Hub account addresses.
+const COSMOS_HRP: &str = "cosmos";
+
+/// The SLIP-44 coin type of Cosmos Hub, shared by most Cosmos SDK chains.
+const COSMOS_COIN_TYPE: u32 = 118;
+
+/// A secp256k1 key derived from a `Deck`, for use as a Cosmos SDK account.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CosmosKey {
+    /// The derived private key.
+    pub private_key: SecretKey,
+}
+
+impl CosmosKey {
+    /// Derives the key at `m/44'/118'/0'/0/index` from a BIP32 seed.
+    fn from_seed(seed: &[u8], index: ChildNumber) -> CosmosKey {
+        let master = match Xpriv::new_master(NetworkKind::Main, seed) {
+            Ok(master) => master,
+            Err(e) => panic!("failed to derive a master key from the seed: {}", e),
+        };
+        let path = [
+            ChildNumber::from_hardened_idx(44),
+            ChildNumber::from_hardened_idx(COSMOS_COIN_TYPE),
+            ChildNumber::from_hardened_idx(0),
+            ChildNumber::from_normal_idx(0),
+            Ok(index),
+        ]
+        .map(|child| child.expect("derivation path indexes are in range"));
+
+        let key = master
+            .derive_priv(&Secp256k1::signing_only(), &path)
+            .expect("derivation from a valid key cannot fail");
+        CosmosKey {
+            private_key: key.private_key,
+        }
+    }
+
+    /// Returns the compressed public key.
+    pub fn public_key(&self) -> PublicKey {
+        self.private_key.public_key(&Secp256k1::signing_only())
+    }
+
+    /// Returns the Cosmos Hub account address, beginning `cosmos1`.
+    pub fn address(&self) -> String {
+        self.address_with_prefix(COSMOS_HRP)
+            .expect("the Cosmos prefix is a valid bech32 prefix")
+    }
+
+    /// Returns the account address on another Cosmos SDK chain, given its bech32
+    /// prefix such as `"osmo"`. Returns `None` if the prefix is not valid bech32.
+    pub fn address_with_prefix(&self, prefix: &str) -> Option<String> {
+        let hrp = Hrp::parse(prefix).ok()?;
+        let hash = hash160::Hash::hash(&self.public_key().serialize());
+        bech32::encode::<Bech32>(hrp, hash.as_byte_array()).ok()
+    }
+}
+
+impl Deck {
+    /// Derives a Cosmos account key from the `Deck` and an optional password. The BIP39
+    /// seed of `Deck::mnemonic` with an empty passphrase is used as a BIP32 seed, from
+    /// which the key is derived along the standard path `m/44'/118'/0'/0/index`. The
+    /// account therefore matches the one Keplr and other Cosmos wallets derive when
+    /// the mnemonic is imported.
+    ///
+    /// Returns an `Err` if `index` is 2^31 or more.
+    ///
+    /// ```
+    /// use cardseed::Deck;
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let key = deck.cosmos_key(Some("bestpasswordever"), 0).unwrap();
+    /// assert!(key.address().starts_with("cosmos1"));
+    /// ```
+    pub fn cosmos_key(
+        &self,
+        password: Option<&str>,
+        index: u32,
+    ) -> Result<CosmosKey, errors::DerivationError> {
+        let index = ChildNumber::from_normal_idx(index)
+            .map_err(|_| errors::DerivationError::IndexTooLarge(index))?;
+        let seed = Zeroizing::new(self.mnemonic(password).to_seed(""));
+        Ok(CosmosKey::from_seed(seed.as_slice(), index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cosmos_key() {
+        let deck = Deck::new();
+        let key = deck.cosmos_key(None, 0).unwrap();
+        let address = key.address();
+        assert!(address.starts_with("cosmos1"));
+        assert_eq!(address.len(), 45);
+
+        let (hrp, data) = bech32::decode(&address).unwrap();
+        assert_eq!(hrp.as_str(), "cosmos");
+        assert_eq!(
+            data,
+            hash160::Hash::hash(&key.public_key().serialize()).to_byte_array()
+        );
+
+        assert!(key
+            .address_with_prefix("osmo")
+            .unwrap()
+            .starts_with("osmo1"));
+        assert_eq!(key.address_with_prefix("not valid"), None);
+        assert_ne!(deck.cosmos_key(None, 1), Ok(key));
+    }
+
+    #[test]
+    fn mnemonic_vector() {
+        // The first Cosmos Hub account of the mnemonic "abandon abandon ... about", as
+        // derived by Cosmos wallets.
+        let words = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let seed = bip39::Mnemonic::parse(words).unwrap().to_seed("");
+        let key = CosmosKey::from_seed(&seed, ChildNumber::from_normal_idx(0).unwrap());
+        assert_eq!(
+            key.address(),
+            "cosmos19rl4cm2hmr8afy4kldpxz3fka4jguq0auqdal4"
+        );
+    }
+
+    #[test]
+    fn index_too_large() {
+        let deck = Deck::new();
+        for index in [1 << 31, u32::MAX] {
+            assert_eq!(
+                deck.cosmos_key(None, index),
+                Err(errors::DerivationError::IndexTooLarge(index))
+            );
+        }
+    }
+}
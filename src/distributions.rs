@@ -0,0 +1,61 @@
+//! Implementations of `rand`'s `Standard` distribution, so that cards, ranks, and
+//! suits can be sampled uniformly with `Rng::gen`.
+
+use crate::card::{Card, STANDARD_DECK};
+use crate::rank::{Rank, ALL_RANKS};
+use crate::suit::{Suit, ALL_SUITS};
+use rand::distributions::{Distribution, Standard};
+use rand::Rng;
+
+/// Samples each of the 52 cards of a standard deck with equal probability.
+///
+/// ```
+/// use cardseed::Card;
+/// use rand::Rng;
+///
+/// let card: Card = rand::thread_rng().gen();
+/// assert!(card.index() < 52);
+/// ```
+impl Distribution<Card> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Card {
+        STANDARD_DECK[rng.gen_range(0..STANDARD_DECK.len())]
+    }
+}
+
+/// Samples each of the four suits with equal probability.
+impl Distribution<Suit> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Suit {
+        ALL_SUITS[rng.gen_range(0..ALL_SUITS.len())]
+    }
+}
+
+/// Samples each of the 13 ranks with equal probability.
+impl Distribution<Rank> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Rank {
+        ALL_RANKS[rng.gen_range(0..ALL_RANKS.len())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn uniform() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(7);
+        let mut counts = [0u32; 52];
+        for _ in 0..52_000 {
+            counts[rng.gen::<Card>().index() as usize] += 1;
+        }
+        assert!(
+            counts.iter().all(|&n| (800..1200).contains(&n)),
+            "{counts:?}"
+        );
+
+        let suits: Vec<Suit> = (0..100).map(|_| rng.gen()).collect();
+        assert!(ALL_SUITS.iter().all(|s| suits.contains(s)));
+        let ranks: Vec<Rank> = (0..200).map(|_| rng.gen()).collect();
+        assert!(ALL_RANKS.iter().all(|r| ranks.contains(r)));
+    }
+}
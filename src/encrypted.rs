@@ -0,0 +1,326 @@
+use crate::deck::Deck;
+use crate::errors;
+use crate::file::DeckFile;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::{CryptoRng, RngCore};
+use std::fs;
+use std::path::Path;
+use unicode_normalization::UnicodeNormalization;
+use zeroize::Zeroizing;
+
+/// The bytes which begin every encrypted `.deck` file.
+pub const ENCRYPTED_FILE_MAGIC: [u8; 4] = *b"CSDE";
+
+/// The version of the encrypted format written by `DeckFile::encrypt`.
+const ENCRYPTED_FILE_VERSION: u8 = 1;
+
+/// The length of the random Argon2id salt.
+const SALT_LEN: usize = 16;
+
+/// The length of the random XChaCha20-Poly1305 nonce.
+const NONCE_LEN: usize = 24;
+
+/// The length of the header which precedes the ciphertext.
+const HEADER_LEN: usize = ENCRYPTED_FILE_MAGIC.len() + 13 + SALT_LEN + NONCE_LEN;
+
+/// The most memory, in KiB, which `DeckFile::decrypt` lets a file ask for, so
+/// that opening an untrusted file can't exhaust memory.
+const MAX_MEMORY_KIB: u32 = 1 << 20;
+
+/// The most passes over memory which `DeckFile::decrypt` lets a file ask for, so
+/// that opening an untrusted file can't hang the caller.
+const MAX_ITERATIONS: u32 = 32;
+
+/// The most lanes which `DeckFile::decrypt` lets a file ask for.
+const MAX_PARALLELISM: u32 = 16;
+
+/// Argon2id parameters for deriving the key of an encrypted deck file. They are
+/// stored in the file, so files written with different options can all be read.
+/// The default is the minimum recommended by
+/// [OWASP](https://cheatsheetseries.owasp.org/cheatsheets/Password_Storage_Cheat_Sheet.html):
+/// 19 MiB of memory and 2 iterations.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EncryptionOptions {
+    /// The memory cost in KiB, at most 1 GiB.
+    pub memory_kib: u32,
+    /// The number of passes over the memory, at most 32.
+    pub iterations: u32,
+    /// The degree of parallelism, at most 16.
+    pub parallelism: u32,
+}
+
+impl Default for EncryptionOptions {
+    fn default() -> EncryptionOptions {
+        EncryptionOptions {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl DeckFile {
+    /// Encrypts the file under `passphrase` with XChaCha20-Poly1305, using a key
+    /// derived from the passphrase by Argon2id with a random salt. The passphrase is
+    /// converted to NFKD form first, as with `Normalization::Nfkd`, so that it can be
+    /// typed on any platform. Returns `EncryptionError::TooCostly` if the options
+    /// exceed the limits `DeckFile::decrypt` enforces, since the file couldn't be
+    /// read back.
+    ///
+    /// The result is the header below followed by the encrypted `.deck` encoding of
+    /// the file, label included. The header is authenticated too, so tampering with
+    /// the parameters is detected.
+    ///
+    /// ```text
+    /// "CSDE" || u8(version) || u32be(memory_kib) || u32be(iterations) || u32be(parallelism) || salt[16] || nonce[24]
+    /// ```
+    pub fn encrypt(
+        &self,
+        passphrase: &str,
+        options: &EncryptionOptions,
+    ) -> Result<Vec<u8>, errors::EncryptionError> {
+        self.encrypt_with_rng(passphrase, options, &mut rand::rngs::OsRng)
+    }
+
+    /// Encrypts the file like `DeckFile::encrypt`, drawing the salt and nonce from the
+    /// given secure RNG.
+    pub fn encrypt_with_rng<R: CryptoRng + RngCore + ?Sized>(
+        &self,
+        passphrase: &str,
+        options: &EncryptionOptions,
+        rng: &mut R,
+    ) -> Result<Vec<u8>, errors::EncryptionError> {
+        let plaintext = Zeroizing::new(self.encode()?);
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + plaintext.len() + 16);
+        bytes.extend_from_slice(&ENCRYPTED_FILE_MAGIC);
+        bytes.push(ENCRYPTED_FILE_VERSION);
+        bytes.extend_from_slice(&options.memory_kib.to_be_bytes());
+        bytes.extend_from_slice(&options.iterations.to_be_bytes());
+        bytes.extend_from_slice(&options.parallelism.to_be_bytes());
+        bytes.resize(HEADER_LEN, 0);
+        rng.try_fill_bytes(&mut bytes[HEADER_LEN - SALT_LEN - NONCE_LEN..])
+            .map_err(errors::RngError::from)?;
+
+        let (salt, nonce) = bytes[HEADER_LEN - SALT_LEN - NONCE_LEN..].split_at(SALT_LEN);
+        let cipher = cipher(passphrase, options, salt)?;
+        let payload = Payload {
+            msg: &plaintext,
+            aad: &bytes,
+        };
+        let ciphertext = cipher
+            .encrypt(XNonce::from_slice(nonce), payload)
+            .expect("deck files are far shorter than the XChaCha20 limit");
+        bytes.extend_from_slice(&ciphertext);
+        Ok(bytes)
+    }
+
+    /// Decrypts a file written by `DeckFile::encrypt`. Returns
+    /// `EncryptionError::Decryption` if the passphrase is wrong or any byte of the
+    /// file was changed, and `EncryptionError::TooCostly` if the parameters in its
+    /// header exceed the limits on memory, iterations and parallelism, before doing
+    /// any work.
+    pub fn decrypt(bytes: &[u8], passphrase: &str) -> Result<DeckFile, errors::EncryptionError> {
+        if !bytes.starts_with(&ENCRYPTED_FILE_MAGIC) {
+            return Err(errors::DeckFileError::Magic.into());
+        }
+        if bytes.len() < HEADER_LEN {
+            return Err(errors::DeckFileError::Truncated.into());
+        }
+        let version = bytes[ENCRYPTED_FILE_MAGIC.len()];
+        if version != ENCRYPTED_FILE_VERSION {
+            return Err(errors::DeckFileError::Version(version).into());
+        }
+
+        let (header, ciphertext) = bytes.split_at(HEADER_LEN);
+        let word = |i: usize| {
+            let start = ENCRYPTED_FILE_MAGIC.len() + 1 + 4 * i;
+            u32::from_be_bytes(header[start..start + 4].try_into().unwrap())
+        };
+        let options = EncryptionOptions {
+            memory_kib: word(0),
+            iterations: word(1),
+            parallelism: word(2),
+        };
+        let (salt, nonce) = header[HEADER_LEN - SALT_LEN - NONCE_LEN..].split_at(SALT_LEN);
+        let payload = Payload {
+            msg: ciphertext,
+            aad: header,
+        };
+        let plaintext = cipher(passphrase, &options, salt)?
+            .decrypt(XNonce::from_slice(nonce), payload)
+            .map(Zeroizing::new)
+            .map_err(|_| errors::EncryptionError::Decryption)?;
+        Ok(DeckFile::decode(&plaintext)?)
+    }
+}
+
+impl Deck {
+    /// Encrypts the `Deck` under `passphrase` with the default `EncryptionOptions`
+    /// and writes it to `path`, replacing any existing file. See `DeckFile::encrypt`
+    /// for the format, and to include a label.
+    ///
+    /// Prefer keeping the ordering only on paper or metal. This is for those who must
+    /// store it digitally, and is only as strong as the passphrase.
+    ///
+    /// ```no_run
+    /// use cardseed::Deck;
+    ///
+    /// let deck = Deck::new().shuffle();
+    /// deck.save_encrypted("wallet.deck", "correct horse battery staple").unwrap();
+    /// let loaded = Deck::load_encrypted("wallet.deck", "correct horse battery staple");
+    /// assert_eq!(loaded.unwrap(), deck);
+    /// ```
+    pub fn save_encrypted(
+        &self,
+        path: impl AsRef<Path>,
+        passphrase: &str,
+    ) -> Result<(), errors::EncryptionError> {
+        let file = DeckFile {
+            deck: self.clone(),
+            label: None,
+        };
+        Ok(fs::write(
+            path,
+            file.encrypt(passphrase, &EncryptionOptions::default())?,
+        )?)
+    }
+
+    /// Reads and decrypts a `Deck` written by `Deck::save_encrypted` or
+    /// `DeckFile::encrypt`, ignoring any label.
+    pub fn load_encrypted(
+        path: impl AsRef<Path>,
+        passphrase: &str,
+    ) -> Result<Deck, errors::EncryptionError> {
+        Ok(DeckFile::decrypt(&fs::read(path)?, passphrase)?.deck)
+    }
+}
+
+/// Derives the key for `passphrase` and `salt` with Argon2id, and returns a cipher
+/// keyed with it.
+fn cipher(
+    passphrase: &str,
+    options: &EncryptionOptions,
+    salt: &[u8],
+) -> Result<XChaCha20Poly1305, errors::EncryptionError> {
+    if options.memory_kib > MAX_MEMORY_KIB
+        || options.iterations > MAX_ITERATIONS
+        || options.parallelism > MAX_PARALLELISM
+    {
+        return Err(errors::EncryptionError::TooCostly);
+    }
+    let params = Params::new(
+        options.memory_kib,
+        options.iterations,
+        options.parallelism,
+        Some(32),
+    )?;
+    let normalized = Zeroizing::new(passphrase.nfkd().collect::<String>());
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params).hash_password_into(
+        normalized.as_bytes(),
+        salt,
+        key.as_mut_slice(),
+    )?;
+    Ok(XChaCha20Poly1305::new(key.as_slice().into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHEAP: EncryptionOptions = EncryptionOptions {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    #[test]
+    fn decrypt() {
+        let file = DeckFile {
+            deck: Deck::new().shuffle(),
+            label: Some("savings".into()),
+        };
+        let bytes = file.encrypt("pässword", &CHEAP).unwrap();
+        assert_eq!(bytes.len(), HEADER_LEN + 13 + 52 + 7 + 16);
+        assert_eq!(DeckFile::decrypt(&bytes, "pässword").unwrap(), file);
+        // The passphrase is normalized, so a decomposed "ä" works too.
+        assert_eq!(DeckFile::decrypt(&bytes, "pa\u{308}ssword").unwrap(), file);
+        assert_ne!(file.encrypt("pässword", &CHEAP).unwrap(), bytes);
+
+        assert!(matches!(
+            DeckFile::decrypt(&bytes, "password"),
+            Err(errors::EncryptionError::Decryption)
+        ));
+        for i in [8, 17, HEADER_LEN - 1, bytes.len() - 1] {
+            let mut tampered = bytes.clone();
+            tampered[i] ^= 1;
+            assert!(matches!(
+                DeckFile::decrypt(&tampered, "pässword"),
+                Err(errors::EncryptionError::Decryption)
+            ));
+        }
+
+        assert!(matches!(
+            DeckFile::decrypt(&file.encode().unwrap(), "pässword"),
+            Err(errors::EncryptionError::File(errors::DeckFileError::Magic))
+        ));
+        assert!(matches!(
+            DeckFile::decrypt(&bytes[..HEADER_LEN - 1], "pässword"),
+            Err(errors::EncryptionError::File(
+                errors::DeckFileError::Truncated
+            ))
+        ));
+        // Costly parameters are refused before deriving a key, so these return at once.
+        for (offset, limit) in [
+            (5, MAX_MEMORY_KIB),
+            (9, MAX_ITERATIONS),
+            (13, MAX_PARALLELISM),
+        ] {
+            let mut greedy = bytes.clone();
+            greedy[offset..offset + 4].copy_from_slice(&(limit + 1).to_be_bytes());
+            assert!(matches!(
+                DeckFile::decrypt(&greedy, "pässword"),
+                Err(errors::EncryptionError::TooCostly)
+            ));
+            greedy[offset..offset + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+            assert!(matches!(
+                DeckFile::decrypt(&greedy, "pässword"),
+                Err(errors::EncryptionError::TooCostly)
+            ));
+        }
+        let mut broken = bytes.clone();
+        broken[13..17].copy_from_slice(&0u32.to_be_bytes());
+        assert!(matches!(
+            DeckFile::decrypt(&broken, "pässword"),
+            Err(errors::EncryptionError::Params(_))
+        ));
+        let costly = EncryptionOptions {
+            iterations: MAX_ITERATIONS + 1,
+            ..CHEAP
+        };
+        assert!(matches!(
+            file.encrypt("pässword", &costly),
+            Err(errors::EncryptionError::TooCostly)
+        ));
+    }
+
+    #[test]
+    fn load_encrypted() {
+        let path = std::env::temp_dir().join(format!("cardseed-{}.enc.deck", std::process::id()));
+        let deck = "AS 3H KD".parse::<Deck>().unwrap();
+        let file = DeckFile {
+            deck: deck.clone(),
+            label: None,
+        };
+        fs::write(&path, file.encrypt("pw", &CHEAP).unwrap()).unwrap();
+        assert_eq!(Deck::load_encrypted(&path, "pw").unwrap(), deck);
+        assert!(matches!(
+            Deck::load_encrypted(&path, "wrong"),
+            Err(errors::EncryptionError::Decryption)
+        ));
+        fs::remove_file(&path).unwrap();
+    }
+}
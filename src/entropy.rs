@@ -0,0 +1,495 @@
+//! Converting between card draws and other physical sources of randomness, such as
+//! dice and coins, so that whatever randomness is at hand can produce a deck, and
+//! a shuffled deck can stand in for dice.
+//!
+//! Conversions are unbiased: each output is uniformly distributed if the inputs
+//! are, using rejection sampling which keeps the leftover randomness of accepted
+//! draws. Every conversion reports how many bits of entropy went in and came out.
+//!
+//! ```
+//! use cardseed::entropy::{self, Source};
+//!
+//! let rolls = [3, 6, 1, 2, 5, 4, 4, 2, 6, 1, 3, 3];
+//! let draws = entropy::deck_from_rolls(Source::D6, &rolls).unwrap();
+//! assert_eq!(draws.output.cards.len(), 5);
+//! assert!(draws.bits_out <= draws.bits_in);
+//! ```
+
+use crate::card::{Card, STANDARD_DECK};
+use crate::codec::lehmer_code;
+use crate::deck::{CardSet, Deck};
+use crate::errors;
+use crate::hash::pbkdf2_sha256;
+use crate::{DECK_SIZE, PBKDF2_ITERATIONS};
+use zeroize::Zeroizing;
+
+/// Domain separation tag which begins every preimage stretched by `DrawStream::seed`.
+const DRAW_STREAM_TAG: &[u8] = b"cardseed/draw-stream";
+
+/// The byte which marks a reshuffle in a `DrawStream` transcript. Drawn cards are
+/// recorded by their index, which is always lower.
+const RESHUFFLE: u8 = DECK_SIZE as u8;
+
+/// Domain separation tag which begins every preimage stretched by
+/// `EntropyAccumulator::seed`.
+const ACCUMULATOR_TAG: &[u8] = b"cardseed/accumulator";
+
+/// A source of uniformly random outcomes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Source {
+    /// A fair coin, with outcomes 1 for heads and 2 for tails.
+    Coin,
+    /// A fair six-sided die, with outcomes 1 to 6.
+    D6,
+    /// A fair twenty-sided die, with outcomes 1 to 20.
+    D20,
+}
+
+impl Source {
+    /// Returns the number of equally likely outcomes.
+    pub fn sides(&self) -> u32 {
+        match self {
+            Source::Coin => 2,
+            Source::D6 => 6,
+            Source::D20 => 20,
+        }
+    }
+
+    /// Returns the bits of entropy in one outcome.
+    pub fn bits(&self) -> f64 {
+        (self.sides() as f64).log2()
+    }
+}
+
+/// The result of a conversion, with its accounting of entropy.
+#[derive(Debug, PartialEq)]
+pub struct Conversion<T> {
+    /// The converted outcomes.
+    pub output: T,
+    /// The number of inputs used. Inputs after these were not needed.
+    pub consumed: usize,
+    /// The bits of entropy in the inputs used.
+    pub bits_in: f64,
+    /// The bits of entropy in the output. The difference from `bits_in` was
+    /// discarded by rejection sampling, or is left over in the last inputs.
+    pub bits_out: f64,
+}
+
+/// Draws unbiased outcomes from a stream of uniform mixed-radix digits.
+struct Extractor<I> {
+    digits: I,
+    value: u64,
+    range: u64,
+    consumed: usize,
+    bits_in: f64,
+}
+
+impl<I: Iterator<Item = (u64, u64)>> Extractor<I> {
+    fn new(digits: I) -> Extractor<I> {
+        Extractor {
+            digits,
+            value: 0,
+            range: 1,
+            consumed: 0,
+            bits_in: 0.0,
+        }
+    }
+
+    /// Draws a uniform outcome below `n`, or returns `None` if the digits run out.
+    fn draw(&mut self, n: u64) -> Option<u64> {
+        loop {
+            while self.range < n {
+                let (digit, radix) = self.digits.next()?;
+                self.value = self.value * radix + digit;
+                self.range *= radix;
+                self.consumed += 1;
+                self.bits_in += (radix as f64).log2();
+            }
+
+            // Accept if the value lies in the largest multiple of `n` below the
+            // range, keeping the quotient as leftover randomness. Otherwise keep
+            // the excess, which is itself uniform, and try again.
+            let accepted = self.range - self.range % n;
+            if self.value < accepted {
+                let outcome = self.value % n;
+                self.value /= n;
+                self.range = accepted / n;
+                return Some(outcome);
+            }
+            self.value -= accepted;
+            self.range -= accepted;
+        }
+    }
+}
+
+/// Converts outcomes of a `Source` into card draws without replacement, drawing
+/// as many cards as the outcomes allow, up to a full deck. A full deck needs at
+/// least 226 bits of entropy, such as 88 rolls of a d6, and usually a few more to
+/// make up for rejected draws. Returns an `Err` naming the first outcome outside
+/// the source's range.
+pub fn deck_from_rolls(
+    source: Source,
+    rolls: &[u32],
+) -> Result<Conversion<Deck>, errors::EntropyError> {
+    let sides = source.sides();
+    if let Some(position) = rolls.iter().position(|&r| r == 0 || r > sides) {
+        return Err(errors::EntropyError::BadOutcome {
+            position,
+            outcome: rolls[position],
+            sides,
+        });
+    }
+
+    let digits = rolls.iter().map(|&r| (r as u64 - 1, sides as u64));
+    let mut extractor = Extractor::new(digits);
+    let mut remaining = STANDARD_DECK.to_vec();
+    let mut deck = Deck { cards: Vec::new() };
+    let mut bits_out = 0.0;
+    while let Some(i) = extractor.draw(remaining.len() as u64) {
+        bits_out += (remaining.len() as f64).log2();
+        deck.cards.push(remaining.remove(i as usize));
+        if remaining.is_empty() {
+            break;
+        }
+    }
+
+    Ok(Conversion {
+        output: deck,
+        consumed: extractor.consumed,
+        bits_in: extractor.bits_in,
+        bits_out,
+    })
+}
+
+/// Converts a randomly shuffled deck, or any prefix of one, into outcomes of a
+/// `Source`, producing as many as the deck's entropy allows. Returns an `Err` if
+/// the deck contains duplicates.
+///
+/// ```
+/// use cardseed::entropy::{self, Source};
+/// use cardseed::Deck;
+///
+/// # #[cfg(feature = "rand")] {
+/// let rolls = entropy::rolls_from_deck(&Deck::new().shuffle(), Source::D20).unwrap();
+/// assert!(rolls.output.iter().all(|&r| (1..=20).contains(&r)));
+/// assert!(rolls.output.len() >= 40);
+/// # }
+/// ```
+pub fn rolls_from_deck(
+    deck: &Deck,
+    source: Source,
+) -> Result<Conversion<Vec<u32>>, errors::EntropyError> {
+    if let Some(card) = deck.first_duplicate() {
+        return Err(errors::DeckError::Duplicate(card).into());
+    }
+
+    let code = lehmer_code(&deck.cards);
+    let digits = code[..deck.cards.len()]
+        .iter()
+        .enumerate()
+        .map(|(i, &digit)| (digit as u64, (DECK_SIZE - i) as u64));
+    let mut extractor = Extractor::new(digits);
+    let mut rolls = Vec::new();
+    while let Some(outcome) = extractor.draw(source.sides() as u64) {
+        rolls.push(outcome as u32 + 1);
+    }
+
+    Ok(Conversion {
+        bits_out: rolls.len() as f64 * source.bits(),
+        output: rolls,
+        consumed: extractor.consumed,
+        bits_in: extractor.bits_in,
+    })
+}
+
+/// Accumulates entropy from cards drawn one at a time, for when transcribing a
+/// whole shuffled deck is too burdensome. The user draws cards from a shuffled deck
+/// and reports each one, optionally reshuffling the drawn cards back in between
+/// draws, until enough entropy has been gathered to produce a seed.
+///
+/// Each draw contributes the entropy of a uniform choice among the cards left
+/// undrawn since the last reshuffle, so draws are only as random as the shuffle.
+///
+/// ```
+/// use cardseed::entropy::DrawStream;
+///
+/// let mut stream = DrawStream::new(20.0);
+/// for card in ["AS", "7D", "QH"] {
+///     stream.draw(card.parse().unwrap()).unwrap();
+/// }
+/// assert!(stream.seed(None).is_none());
+/// stream.reshuffle();
+/// stream.draw("AS".parse().unwrap()).unwrap();
+/// assert!(stream.is_complete());
+/// assert!(stream.seed(Some("pw")).is_some());
+/// ```
+pub struct DrawStream {
+    target_bits: f64,
+    bits: f64,
+    drawn: CardSet,
+    round: usize,
+    transcript: Zeroizing<Vec<u8>>,
+}
+
+impl DrawStream {
+    /// Creates an empty stream which completes once `target_bits` of entropy have
+    /// been drawn.
+    pub fn new(target_bits: f64) -> DrawStream {
+        DrawStream {
+            target_bits,
+            bits: 0.0,
+            drawn: CardSet::default(),
+            round: 0,
+            transcript: Zeroizing::new(Vec::new()),
+        }
+    }
+
+    /// Records a card drawn from the deck. Returns an `Err` if the card was already
+    /// drawn since the last reshuffle, since it can't have been drawn again.
+    pub fn draw(&mut self, card: Card) -> Result<(), errors::EntropyError> {
+        if !self.drawn.insert(card) {
+            return Err(errors::DeckError::Duplicate(card).into());
+        }
+        self.bits += ((DECK_SIZE - self.round) as f64).log2();
+        self.round += 1;
+        self.transcript.push(card.index() as u8);
+        Ok(())
+    }
+
+    /// Records that the drawn cards were returned to the deck and it was shuffled
+    /// again, so every card can be drawn once more. Reshuffling before any card was
+    /// drawn has no effect.
+    pub fn reshuffle(&mut self) {
+        if self.round > 0 {
+            self.drawn = CardSet::default();
+            self.round = 0;
+            self.transcript.push(RESHUFFLE);
+        }
+    }
+
+    /// Returns the bits of entropy drawn so far.
+    pub fn bits(&self) -> f64 {
+        self.bits
+    }
+
+    /// Returns the number of cards drawn since the last reshuffle.
+    pub fn drawn(&self) -> usize {
+        self.round
+    }
+
+    /// Returns true once the entropy drawn has reached the target.
+    pub fn is_complete(&self) -> bool {
+        self.bits >= self.target_bits
+    }
+
+    /// Derives a seed from the draws and an optional password with PBKDF2-SHA256, or
+    /// returns `None` if the stream is not yet complete. The preimage is:
+    ///
+    /// ```text
+    /// "cardseed/draw-stream" || u32be(len(draws)) || draws || u8(has_password) || u32be(len(password)) || password
+    /// ```
+    ///
+    /// where `draws` holds the index of each drawn card in order, and a byte of 52
+    /// for each reshuffle.
+    pub fn seed(&self, password: Option<&str>) -> Option<[u8; 32]> {
+        if !self.is_complete() {
+            return None;
+        }
+        Some(stretch(DRAW_STREAM_TAG, &self.transcript, password))
+    }
+}
+
+/// Absorbs several independently shuffled decks, or fragments of them, and derives
+/// a single seed from all of them, for more entropy than one deck holds or to split
+/// entry across several sessions.
+///
+/// Each deck is assumed to be drawn from the top of its own randomly shuffled full
+/// deck, so a fragment of `n` cards contributes the entropy of `n` draws without
+/// replacement from 52 cards, and a full deck contributes `Deck::entropy_bits`.
+/// The decks must be absorbed in the same order to derive the same seed.
+///
+/// ```
+/// use cardseed::entropy::EntropyAccumulator;
+/// use cardseed::Deck;
+///
+/// let mut accumulator = EntropyAccumulator::new();
+/// accumulator.absorb(&Deck::new()).unwrap();
+/// accumulator.absorb(&"7D 2C QH".parse().unwrap()).unwrap();
+/// assert!(accumulator.bits() > 225.0);
+/// let seed = accumulator.seed(Some("pw"));
+/// assert_ne!(seed, accumulator.seed(None));
+/// ```
+#[derive(Default)]
+pub struct EntropyAccumulator {
+    decks: u32,
+    bits: f64,
+    transcript: Zeroizing<Vec<u8>>,
+}
+
+impl EntropyAccumulator {
+    /// Creates an accumulator which has absorbed nothing.
+    pub fn new() -> EntropyAccumulator {
+        EntropyAccumulator::default()
+    }
+
+    /// Absorbs a shuffled deck or a fragment of one. Returns an `Err` if the deck
+    /// contains duplicates, since it can't have been drawn from a single shuffled deck.
+    pub fn absorb(&mut self, deck: &Deck) -> Result<(), errors::EntropyError> {
+        if let Some(card) = deck.first_duplicate() {
+            return Err(errors::DeckError::Duplicate(card).into());
+        }
+        self.bits += Deck::entropy_bits_for_prefix(deck.cards.len());
+        self.decks += 1;
+        self.transcript.push(deck.cards.len() as u8);
+        self.transcript
+            .extend(deck.cards.iter().map(|card| card.index() as u8));
+        Ok(())
+    }
+
+    /// Returns the number of decks absorbed.
+    pub fn decks(&self) -> usize {
+        self.decks as usize
+    }
+
+    /// Returns the total bits of entropy absorbed.
+    pub fn bits(&self) -> f64 {
+        self.bits
+    }
+
+    /// Derives a seed from the absorbed decks and an optional password with
+    /// PBKDF2-SHA256. The preimage is:
+    ///
+    /// ```text
+    /// "cardseed/accumulator" || u32be(len(decks)) || decks || u8(has_password) || u32be(len(password)) || password
+    /// ```
+    ///
+    /// where `decks` holds each deck in order as the byte length of the deck followed
+    /// by the index of each card.
+    pub fn seed(&self, password: Option<&str>) -> [u8; 32] {
+        stretch(ACCUMULATOR_TAG, &self.transcript, password)
+    }
+}
+
+/// Stretches a domain-separated preimage with PBKDF2-SHA256:
+///
+/// ```text
+/// tag || u32be(len(body)) || body || u8(has_password) || u32be(len(password)) || password
+/// ```
+fn stretch(tag: &[u8], body: &[u8], password: Option<&str>) -> [u8; 32] {
+    let has_password = password.is_some();
+    let password = password.unwrap_or_default().as_bytes();
+    let mut preimage = Zeroizing::new(Vec::with_capacity(
+        tag.len() + 9 + body.len() + password.len(),
+    ));
+    preimage.extend_from_slice(tag);
+    preimage.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    preimage.extend_from_slice(body);
+    preimage.push(has_password as u8);
+    preimage.extend_from_slice(&(password.len() as u32).to_be_bytes());
+    preimage.extend_from_slice(password);
+    pbkdf2_sha256(&preimage, &[], PBKDF2_ITERATIONS, &mut |_, _| {})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extractor() {
+        // Every combination of two d6 rolls, drawing outcomes below 4: the 32
+        // accepted combinations must produce each outcome equally often.
+        let mut counts = [0; 4];
+        for a in 0..6 {
+            for b in 0..6 {
+                let mut extractor = Extractor::new([(a, 6), (b, 6)].into_iter());
+                if let Some(outcome) = extractor.draw(4) {
+                    counts[outcome as usize] += 1;
+                }
+            }
+        }
+        assert_eq!(counts, [9, 9, 9, 9]);
+    }
+
+    #[test]
+    fn deck_from_rolls() {
+        let flips: Vec<u32> = (0..1000).map(|i| 1 + (i * 7 % 11 % 2)).collect();
+        let draws = super::deck_from_rolls(Source::Coin, &flips).unwrap();
+        assert!(draws.output.validate().is_ok());
+        assert!(draws.consumed < flips.len());
+        assert!((draws.bits_out - Deck::new().entropy_bits()).abs() < 1e-9);
+        assert_eq!(draws.bits_in, draws.consumed as f64);
+
+        let draws = super::deck_from_rolls(Source::D20, &[20]).unwrap();
+        assert!(draws.output.cards.is_empty());
+        assert_eq!(draws.bits_out, 0.0);
+
+        assert_eq!(
+            super::deck_from_rolls(Source::D6, &[1, 7]),
+            Err(errors::EntropyError::BadOutcome {
+                position: 1,
+                outcome: 7,
+                sides: 6
+            })
+        );
+    }
+
+    #[test]
+    fn rolls_from_deck() {
+        let rolls = super::rolls_from_deck(&Deck::new(), Source::Coin).unwrap();
+        assert_eq!(rolls.consumed, DECK_SIZE);
+        assert!(rolls.bits_out <= rolls.bits_in);
+        assert!(rolls.output.iter().all(|&r| r == 1));
+
+        assert!(super::rolls_from_deck(&"AS AS".parse().unwrap(), Source::D6).is_err());
+    }
+
+    #[test]
+    fn draw_stream() {
+        let card = |s: &str| s.parse::<Card>().unwrap();
+        let mut stream = DrawStream::new(40.0);
+        for c in ["AS", "2S", "3S"] {
+            stream.draw(card(c)).unwrap();
+        }
+        assert!((stream.bits() - (52.0f64 * 51.0 * 50.0).log2()).abs() < 1e-9);
+        assert_eq!(
+            stream.draw(card("2S")),
+            Err(errors::DeckError::Duplicate(card("2S")).into())
+        );
+        assert_eq!(stream.drawn(), 3);
+
+        stream.reshuffle();
+        stream.reshuffle();
+        assert_eq!(stream.drawn(), 0);
+        assert_eq!(*stream.transcript, [0, 1, 2, RESHUFFLE]);
+        stream.draw(card("2S")).unwrap();
+        assert!(!stream.is_complete());
+        assert_eq!(stream.seed(None), None);
+
+        for c in ["AS", "3S", "4S", "5S"] {
+            stream.draw(card(c)).unwrap();
+        }
+        assert!(stream.is_complete());
+        assert_ne!(stream.seed(None), stream.seed(Some("")));
+    }
+
+    #[test]
+    fn entropy_accumulator() {
+        let mut accumulator = EntropyAccumulator::new();
+        accumulator.absorb(&Deck::new()).unwrap();
+        assert!((accumulator.bits() - Deck::new().entropy_bits()).abs() < 1e-9);
+        accumulator.absorb(&"AS 2S".parse().unwrap()).unwrap();
+        assert!((accumulator.bits() - 225.581 - (52.0f64 * 51.0).log2()).abs() < 0.001);
+        assert!(accumulator.absorb(&"AS AS".parse().unwrap()).is_err());
+        assert_eq!(accumulator.decks(), 2);
+        assert_eq!(accumulator.transcript[0], 52);
+        assert_eq!(accumulator.transcript[53..], [2, 0, 1]);
+
+        // The split between decks is part of the seed.
+        let mut joined = EntropyAccumulator::new();
+        joined.absorb(&"AS 2S".parse().unwrap()).unwrap();
+        let mut split = EntropyAccumulator::new();
+        split.absorb(&"AS".parse().unwrap()).unwrap();
+        split.absorb(&"2S".parse().unwrap()).unwrap();
+        assert_ne!(joined.seed(None), split.seed(None));
+    }
+}
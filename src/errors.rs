@@ -1,10 +1,21 @@
-use std::{self, fmt};
+use crate::card::Card;
+use std::{self, fmt, io};
 
+/// Returned when parsing a `Card` or `Suit` fails.
 #[derive(Debug, PartialEq)]
 pub enum ParseError {
+    /// An integer was outside the range of valid card or suit indexes.
     BadInt(u32),
+    /// A character does not represent any suit.
     BadChar(char),
-    BadString(String),
+    /// The first character of a card token is not a valid face value.
+    BadValue { token: String, value: char },
+    /// The second character of a card token is not a valid suit.
+    BadSuit { token: String, suit: char },
+    /// A card token is too short or too long to be a card.
+    BadLength(String),
+    /// A serialized value does not match its expected format.
+    Malformed(String),
 }
 
 impl std::error::Error for ParseError {}
@@ -14,7 +25,816 @@ impl fmt::Display for ParseError {
         match self {
             ParseError::BadInt(i) => write!(f, "failed to parse from unexpected integer {i}"),
             ParseError::BadChar(c) => write!(f, "failed to parse from unexpected character {c}"),
-            ParseError::BadString(s) => write!(f, "failed to parse from unexpected string \"{s}\""),
+            ParseError::BadValue { token, value } => {
+                write!(f, "unknown face value '{value}' in card \"{token}\"")
+            }
+            ParseError::BadSuit { token, suit } => {
+                write!(f, "unknown suit '{suit}' in card \"{token}\"")
+            }
+            ParseError::BadLength(s) => write!(f, "card string \"{s}\" has an invalid length"),
+            ParseError::Malformed(s) => write!(f, "malformed input \"{s}\""),
+        }
+    }
+}
+
+/// Returned when constructing a `Card` from invalid parts.
+#[derive(Debug, PartialEq)]
+pub enum CardError {
+    /// The card's face value is not in the range `[0..13]`.
+    BadValue(u32),
+    /// The card's index is not in the range `[0..52]`.
+    BadIndex(u32),
+}
+
+impl std::error::Error for CardError {}
+
+impl fmt::Display for CardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CardError::BadValue(v) => write!(f, "invalid card face value {v}"),
+            CardError::BadIndex(i) => write!(f, "invalid card index {i}"),
+        }
+    }
+}
+
+/// Returned when a `Deck` is malformed or fails validation.
+#[derive(Debug, PartialEq)]
+pub enum DeckError {
+    /// The given card appears in the deck more than once.
+    Duplicate(Card),
+    /// The deck has no duplicates, but is missing the given cards.
+    Incomplete(Vec<Card>),
+    /// The deck has the wrong number of cards.
+    WrongLength { expected: usize, found: usize },
+    /// The token at the given position in the input could not be parsed as a card.
+    UnknownCard { position: usize, source: ParseError },
+}
+
+impl std::error::Error for DeckError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DeckError::UnknownCard { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DeckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeckError::Duplicate(card) => write!(f, "duplicate card {card} in deck"),
+            DeckError::Incomplete(missing) => {
+                write!(f, "deck is missing {} cards:", missing.len())?;
+                for card in missing.iter() {
+                    write!(f, " {card}")?;
+                }
+                Ok(())
+            }
+            DeckError::WrongLength { expected, found } => {
+                write!(f, "expected a deck of {expected} cards, found {found}")
+            }
+            DeckError::UnknownCard { position, .. } => {
+                write!(f, "failed to parse card at position {position}")
+            }
+        }
+    }
+}
+
+/// Returned by `Deck::derive_checked` when a `Deck` fails to meet a `Policy`.
+#[derive(Debug, PartialEq)]
+pub enum PolicyError {
+    /// The deck is malformed, incomplete, or contains duplicates.
+    Deck(DeckError),
+    /// The deck contains fewer bits of entropy than the policy requires.
+    InsufficientEntropy { bits: f64, required: f64 },
+    /// The deck is in canonical order, and so was probably never shuffled.
+    CanonicalOrder,
+}
+
+impl std::error::Error for PolicyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PolicyError::Deck(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolicyError::Deck(_) => write!(f, "deck does not meet policy"),
+            PolicyError::InsufficientEntropy { bits, required } => write!(
+                f,
+                "deck contains {bits:.1} bits of entropy, but at least {required:.1} are required"
+            ),
+            PolicyError::CanonicalOrder => {
+                write!(
+                    f,
+                    "deck is in canonical order and was probably not shuffled"
+                )
+            }
+        }
+    }
+}
+
+impl From<DeckError> for PolicyError {
+    fn from(e: DeckError) -> PolicyError {
+        PolicyError::Deck(e)
+    }
+}
+
+/// Returned when splitting a secret into shares or combining shares fails.
+#[derive(Debug, PartialEq)]
+pub enum ShareError {
+    /// The threshold is zero or greater than the number of shares.
+    BadThreshold { threshold: u8, shares: u8 },
+    /// Fewer shares were given than are needed to recover the secret.
+    NotEnoughShares { found: usize, required: usize },
+    /// Two shares have the same index.
+    DuplicateShare(u8),
+    /// The shares have different thresholds or lengths, so are not from the same secret.
+    Mismatched,
+    /// A share or the recovered secret does not match its checksum.
+    BadChecksum,
+}
+
+impl std::error::Error for ShareError {}
+
+impl fmt::Display for ShareError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShareError::BadThreshold { threshold, shares } => {
+                write!(
+                    f,
+                    "cannot split a secret into {shares} shares with threshold {threshold}"
+                )
+            }
+            ShareError::NotEnoughShares { found, required } => {
+                write!(f, "found {found} shares, but {required} are required")
+            }
+            ShareError::DuplicateShare(index) => write!(f, "share {index} was given twice"),
+            ShareError::Mismatched => write!(f, "shares are not from the same secret"),
+            ShareError::BadChecksum => write!(f, "share checksum does not match"),
+        }
+    }
+}
+
+/// Returned when reading a `Deck` incrementally from a reader fails.
+#[derive(Debug)]
+pub enum ReadError {
+    /// The reader failed.
+    Io(io::Error),
+    /// A card could not be parsed, or appears more than once.
+    Deck(DeckError),
+    /// The reader holds more cards than the `max_cards` limit passed to
+    /// `Deck::read_from`. The value is that limit, not the number of cards read.
+    TooManyCards(usize),
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            ReadError::Deck(e) => Some(e),
+            ReadError::TooManyCards(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::Io(_) => write!(f, "failed to read deck"),
+            ReadError::Deck(_) => write!(f, "failed to read deck"),
+            ReadError::TooManyCards(n) => write!(f, "deck exceeds the limit of {n} cards"),
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> ReadError {
+        ReadError::Io(e)
+    }
+}
+
+impl From<DeckError> for ReadError {
+    fn from(e: DeckError) -> ReadError {
+        ReadError::Deck(e)
+    }
+}
+
+/// Returned when a recovery search for a partially known deck cannot run.
+#[derive(Debug, PartialEq)]
+pub enum RecoveryError {
+    /// A known card appears more than once in the partial deck.
+    Duplicate(Card),
+    /// The search has more candidates than its limit allows.
+    TooManyCandidates { limit: u64 },
+    /// A pile has a known card which isn't among its cards, or at a position
+    /// outside the pile.
+    InvalidPile { pile: usize },
+}
+
+impl std::error::Error for RecoveryError {}
+
+impl fmt::Display for RecoveryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecoveryError::Duplicate(card) => write!(f, "card {card} is known at two positions"),
+            RecoveryError::TooManyCandidates { limit } => {
+                write!(f, "search has more than {limit} candidates")
+            }
+            RecoveryError::InvalidPile { pile } => {
+                write!(f, "pile {pile} has a known card which doesn't belong there")
+            }
+        }
+    }
+}
+
+/// Returned when storing bytes in a `Deck` or reading them back fails.
+#[derive(Debug, PartialEq)]
+pub enum CodecError {
+    /// The payload is longer than the deck can store.
+    TooLong { len: usize, capacity: usize },
+    /// The deck is not a complete deck of 52 cards.
+    Deck(DeckError),
+    /// The deck does not hold a payload of the given length, or has too many
+    /// errors to correct.
+    Corrupt,
+}
+
+impl std::error::Error for CodecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CodecError::Deck(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CodecError::TooLong { len, capacity } => {
+                write!(
+                    f,
+                    "cannot store {len} bytes in a deck which holds {capacity}"
+                )
+            }
+            CodecError::Deck(_) => write!(f, "deck cannot hold a payload"),
+            CodecError::Corrupt => write!(f, "deck does not hold a readable payload"),
+        }
+    }
+}
+
+impl From<DeckError> for CodecError {
+    fn from(e: DeckError) -> CodecError {
+        CodecError::Deck(e)
+    }
+}
+
+/// Returned when converting between card draws and other random outcomes fails.
+#[derive(Debug, PartialEq)]
+pub enum EntropyError {
+    /// An outcome is outside the range of its source, from 1 to `sides`.
+    BadOutcome {
+        position: usize,
+        outcome: u32,
+        sides: u32,
+    },
+    /// The deck contains duplicates, so was not randomly shuffled.
+    Deck(DeckError),
+}
+
+impl std::error::Error for EntropyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EntropyError::Deck(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for EntropyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EntropyError::BadOutcome {
+                position,
+                outcome,
+                sides,
+            } => write!(
+                f,
+                "outcome {outcome} at position {position} is not between 1 and {sides}"
+            ),
+            EntropyError::Deck(_) => write!(f, "deck cannot be converted"),
+        }
+    }
+}
+
+impl From<DeckError> for EntropyError {
+    fn from(e: DeckError) -> EntropyError {
+        EntropyError::Deck(e)
+    }
+}
+
+/// Returned when a random number generator fails, as `OsRng` may on platforms
+/// without a source of entropy, or fails a health test.
+#[cfg(feature = "rand")]
+#[derive(Debug)]
+pub enum RngError {
+    /// The generator failed to produce random bytes.
+    Failed(rand::Error),
+    /// The generator produced the same byte `run` times in a row, failing the
+    /// repetition count test of `health::self_test`.
+    RepetitionCount { run: usize },
+    /// The generator produced the first byte of a window `count` times within the
+    /// window, failing the adaptive proportion test of `health::self_test`.
+    AdaptiveProportion { count: usize },
+}
+
+#[cfg(feature = "rand")]
+impl std::error::Error for RngError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RngError::Failed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "rand")]
+impl fmt::Display for RngError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RngError::Failed(_) => write!(f, "random number generator failed"),
+            RngError::RepetitionCount { run } => {
+                write!(
+                    f,
+                    "random number generator repeated a byte {run} times in a row"
+                )
+            }
+            RngError::AdaptiveProportion { count } => write!(
+                f,
+                "random number generator produced a byte {count} times in {} samples",
+                crate::health::PROPORTION_WINDOW
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "rand")]
+impl From<rand::Error> for RngError {
+    fn from(e: rand::Error) -> RngError {
+        RngError::Failed(e)
+    }
+}
+
+/// Returned when a metal backup cannot be read.
+#[derive(Debug, PartialEq)]
+pub enum MetalBackupError {
+    /// A card index is not between 0 and 51.
+    Deck(DeckError),
+    /// The checksum of the given row, numbered from one, doesn't match its cards.
+    Checksum { row: u32 },
+    /// A line is not a correctly numbered row of card indexes and a checksum.
+    Malformed(String),
+}
+
+impl std::error::Error for MetalBackupError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MetalBackupError::Deck(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for MetalBackupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MetalBackupError::Deck(_) => write!(f, "invalid card index"),
+            MetalBackupError::Checksum { row } => write!(f, "checksum of row {row} doesn't match"),
+            MetalBackupError::Malformed(line) => write!(f, "unrecognized row {line:?}"),
+        }
+    }
+}
+
+impl From<DeckError> for MetalBackupError {
+    fn from(e: DeckError) -> MetalBackupError {
+        MetalBackupError::Deck(e)
+    }
+}
+
+/// Returned when a `.deck` file cannot be read or written.
+#[derive(Debug)]
+pub enum DeckFileError {
+    /// The file couldn't be read or written.
+    Io(io::Error),
+    /// The file doesn't begin with the `.deck` magic bytes.
+    Magic,
+    /// The file was written in a format version this crate doesn't understand.
+    Version(u8),
+    /// The file ends before the fields it declares.
+    Truncated,
+    /// The CRC of the file doesn't match its contents.
+    Checksum,
+    /// The label is not valid UTF-8.
+    Label,
+    /// The deck or label has more than 65535 cards or bytes, and can't be written.
+    TooLong(usize),
+    /// A card index is not between 0 and 51.
+    Deck(DeckError),
+}
+
+impl std::error::Error for DeckFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DeckFileError::Io(e) => Some(e),
+            DeckFileError::Deck(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DeckFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeckFileError::Io(_) => write!(f, "failed to access deck file"),
+            DeckFileError::Magic => write!(f, "not a deck file"),
+            DeckFileError::Version(v) => write!(f, "unsupported deck file version {v}"),
+            DeckFileError::Truncated => write!(f, "deck file is truncated"),
+            DeckFileError::Checksum => write!(f, "deck file checksum doesn't match"),
+            DeckFileError::Label => write!(f, "deck file label is not valid UTF-8"),
+            DeckFileError::TooLong(n) => {
+                write!(f, "field of length {n} is too long for a deck file")
+            }
+            DeckFileError::Deck(_) => write!(f, "invalid card index"),
+        }
+    }
+}
+
+impl From<io::Error> for DeckFileError {
+    fn from(e: io::Error) -> DeckFileError {
+        DeckFileError::Io(e)
+    }
+}
+
+impl From<DeckError> for DeckFileError {
+    fn from(e: DeckError) -> DeckFileError {
+        DeckFileError::Deck(e)
+    }
+}
+
+/// Returned when an encrypted deck file cannot be written or read.
+#[cfg(feature = "encryption")]
+#[derive(Debug)]
+pub enum EncryptionError {
+    /// The file couldn't be accessed, or isn't an encrypted deck file.
+    File(DeckFileError),
+    /// The RNG failed to produce a salt or nonce.
+    Rng(RngError),
+    /// The key derivation parameters are invalid.
+    Params(argon2::Error),
+    /// The key derivation parameters ask for more memory, iterations or parallelism
+    /// than `DeckFile::decrypt` allows, so that an untrusted file can't exhaust the
+    /// memory or time of the caller.
+    TooCostly,
+    /// The passphrase is wrong, or the file was modified.
+    Decryption,
+}
+
+#[cfg(feature = "encryption")]
+impl std::error::Error for EncryptionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EncryptionError::File(e) => Some(e),
+            EncryptionError::Rng(e) => Some(e),
+            EncryptionError::Params(e) => Some(e),
+            EncryptionError::TooCostly | EncryptionError::Decryption => None,
+        }
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl fmt::Display for EncryptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncryptionError::File(_) => write!(f, "failed to access encrypted deck file"),
+            EncryptionError::Rng(_) => write!(f, "failed to generate a salt or nonce"),
+            EncryptionError::Params(_) => write!(f, "invalid key derivation parameters"),
+            EncryptionError::TooCostly => {
+                write!(f, "key derivation parameters exceed the limits for reading")
+            }
+            EncryptionError::Decryption => {
+                write!(f, "wrong passphrase, or the file was modified")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl From<DeckFileError> for EncryptionError {
+    fn from(e: DeckFileError) -> EncryptionError {
+        EncryptionError::File(e)
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl From<io::Error> for EncryptionError {
+    fn from(e: io::Error) -> EncryptionError {
+        EncryptionError::File(DeckFileError::Io(e))
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl From<RngError> for EncryptionError {
+    fn from(e: RngError) -> EncryptionError {
+        EncryptionError::Rng(e)
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl From<argon2::Error> for EncryptionError {
+    fn from(e: argon2::Error) -> EncryptionError {
+        EncryptionError::Params(e)
+    }
+}
+
+/// Returned when a `PaoTable` is unusable, or recalled words aren't in it.
+#[derive(Debug, PartialEq)]
+pub enum PaoError {
+    /// The named table doesn't have exactly one entry for each of the 52 cards.
+    TableSize { table: &'static str, len: usize },
+    /// The given entry appears twice in one table, so its card is ambiguous.
+    Duplicate(String),
+    /// The given word isn't an entry of the table for its place in a triad.
+    UnknownEntry(String),
+}
+
+impl std::error::Error for PaoError {}
+
+impl fmt::Display for PaoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PaoError::TableSize { table, len } => {
+                write!(f, "{table} table has {len} entries instead of 52")
+            }
+            PaoError::Duplicate(entry) => write!(f, "{entry:?} appears twice in a table"),
+            PaoError::UnknownEntry(entry) => write!(f, "{entry:?} is not in the table"),
+        }
+    }
+}
+
+/// Returned when a completed backup worksheet cannot be read.
+#[derive(Debug, PartialEq)]
+pub enum WorksheetError {
+    /// A card slot is blank or could not be parsed as a card.
+    Deck(DeckError),
+    /// A card slot is numbered out of order, so a slot was skipped or repeated.
+    Slot { expected: usize, found: usize },
+    /// The checkword box holds a word which is not a checkword.
+    Checkword(String),
+    /// A line is not a field or a row of card slots.
+    Malformed(String),
+}
+
+impl std::error::Error for WorksheetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WorksheetError::Deck(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for WorksheetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorksheetError::Deck(_) => write!(f, "invalid card slot"),
+            WorksheetError::Slot { expected, found } => {
+                write!(f, "expected card slot {expected}, found slot {found}")
+            }
+            WorksheetError::Checkword(word) => write!(f, "unknown checkword {word:?}"),
+            WorksheetError::Malformed(line) => write!(f, "unrecognized line {line:?}"),
+        }
+    }
+}
+
+impl From<DeckError> for WorksheetError {
+    fn from(e: DeckError) -> WorksheetError {
+        WorksheetError::Deck(e)
+    }
+}
+
+/// Returned when a `Hand` cannot be created or decoded from its index.
+#[derive(Debug, PartialEq)]
+pub enum HandError {
+    /// A card appears more than once, or a card string could not be parsed.
+    Deck(DeckError),
+    /// A hand of more than 52 cards was requested.
+    Size(usize),
+    /// The combination index is too large for a hand of the requested size.
+    BadIndex(u64),
+}
+
+impl std::error::Error for HandError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HandError::Deck(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for HandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HandError::Deck(_) => write!(f, "invalid hand"),
+            HandError::Size(k) => write!(f, "cannot draw a hand of {k} cards from 52"),
+            HandError::BadIndex(index) => {
+                write!(f, "combination index {index} is out of range")
+            }
+        }
+    }
+}
+
+impl From<DeckError> for HandError {
+    fn from(e: DeckError) -> HandError {
+        HandError::Deck(e)
+    }
+}
+
+/// Returned when a poker hand cannot be ranked.
+#[derive(Debug, PartialEq)]
+pub enum PokerError {
+    /// The hand has fewer than five or more than seven cards.
+    HandSize(usize),
+    /// A card appears more than once in the hand.
+    Duplicate(Card),
+}
+
+impl std::error::Error for PokerError {}
+
+impl fmt::Display for PokerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PokerError::HandSize(n) => write!(f, "expected 5 to 7 cards, got {n}"),
+            PokerError::Duplicate(card) => write!(f, "card {card} appears more than once"),
+        }
+    }
+}
+
+/// Returned by `mnemonic_from_decks` when a mnemonic cannot be restored from decks.
+#[cfg(feature = "bip39")]
+#[derive(Debug, PartialEq)]
+pub enum MnemonicError {
+    /// The word count is not 12, 15, 18, 21, or 24.
+    WordCount(usize),
+    /// The number of decks given is wrong for the word count.
+    DeckCount { expected: usize, found: usize },
+    /// A deck does not hold entropy of the expected length.
+    Codec(CodecError),
+}
+
+#[cfg(feature = "bip39")]
+impl std::error::Error for MnemonicError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MnemonicError::Codec(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "bip39")]
+impl fmt::Display for MnemonicError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MnemonicError::WordCount(n) => write!(f, "a mnemonic cannot have {n} words"),
+            MnemonicError::DeckCount { expected, found } => {
+                write!(
+                    f,
+                    "expected {expected} decks for the mnemonic, found {found}"
+                )
+            }
+            MnemonicError::Codec(_) => write!(f, "deck does not hold a mnemonic"),
+        }
+    }
+}
+
+#[cfg(feature = "bip39")]
+impl From<CodecError> for MnemonicError {
+    fn from(e: CodecError) -> MnemonicError {
+        MnemonicError::Codec(e)
+    }
+}
+
+/// Returned when a bridge deal cannot be dealt or parsed.
+#[cfg(feature = "bridge")]
+#[derive(Debug, PartialEq)]
+pub enum BridgeError {
+    /// The deck dealt from is not a complete deck of 52 cards, or a parsed deal
+    /// has a card more than once.
+    Deck(DeckError),
+    /// A parsed deal is not a valid PBN deal string.
+    Parse(ParseError),
+    /// A hand in a parsed deal doesn't have 13 cards.
+    HandSize {
+        seat: crate::bridge::Seat,
+        len: usize,
+    },
+}
+
+#[cfg(feature = "bridge")]
+impl std::error::Error for BridgeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BridgeError::Deck(e) => Some(e),
+            BridgeError::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "bridge")]
+impl fmt::Display for BridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BridgeError::Deck(_) => write!(f, "invalid deck"),
+            BridgeError::Parse(_) => write!(f, "invalid deal"),
+            BridgeError::HandSize { seat, len } => {
+                write!(f, "hand {seat} has {len} cards instead of 13")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "bridge")]
+impl From<DeckError> for BridgeError {
+    fn from(e: DeckError) -> BridgeError {
+        BridgeError::Deck(e)
+    }
+}
+
+#[cfg(feature = "bridge")]
+impl From<ParseError> for BridgeError {
+    fn from(e: ParseError) -> BridgeError {
+        BridgeError::Parse(e)
+    }
+}
+
+/// Returned when an aezeed mnemonic cannot be deciphered.
+#[cfg(feature = "aezeed")]
+#[derive(Debug, PartialEq)]
+pub enum AezeedError {
+    /// The mnemonic doesn't have 24 words.
+    WordCount(usize),
+    /// The word at the given position is not in the BIP39 English word list.
+    UnknownWord(usize),
+    /// The mnemonic's checksum is wrong, so a word was mistyped or misplaced.
+    Checksum,
+    /// The mnemonic is of a version of aezeed which isn't supported.
+    Version(u8),
+    /// The passphrase is wrong.
+    Passphrase,
+}
+
+#[cfg(feature = "aezeed")]
+impl std::error::Error for AezeedError {}
+
+#[cfg(feature = "aezeed")]
+impl fmt::Display for AezeedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AezeedError::WordCount(n) => write!(f, "an aezeed mnemonic cannot have {n} words"),
+            AezeedError::UnknownWord(i) => write!(f, "word {i} is not in the word list"),
+            AezeedError::Checksum => write!(f, "the mnemonic's checksum is wrong"),
+            AezeedError::Version(v) => write!(f, "unsupported aezeed version {v}"),
+            AezeedError::Passphrase => write!(f, "wrong passphrase for the mnemonic"),
+        }
+    }
+}
+
+/// Returned when an account or address index can't be used in a derivation path.
+#[cfg(any(feature = "cosmos", feature = "solana"))]
+#[derive(Debug, PartialEq)]
+pub enum DerivationError {
+    /// The index is 2^31 or more, which BIP32 and SLIP-10 reserve for hardened
+    /// children.
+    IndexTooLarge(u32),
+}
+
+#[cfg(any(feature = "cosmos", feature = "solana"))]
+impl std::error::Error for DerivationError {}
+
+#[cfg(any(feature = "cosmos", feature = "solana"))]
+impl fmt::Display for DerivationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DerivationError::IndexTooLarge(index) => {
+                write!(f, "index {index} is not below 2^31")
+            }
         }
     }
 }
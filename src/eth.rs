@@ -0,0 +1,151 @@
+use crate::deck::Deck;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::SecretKey;
+use sha3::{Digest, Keccak256};
+use zeroize::Zeroizing;
+
+/// The purpose label passed to `Deck::derive_indexed` for Ethereum keys.
+const ETHEREUM_PURPOSE: &str = "ethereum";
+
+/// A secp256k1 key derived from a `Deck`, for use as an Ethereum account.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EthereumKey {
+    /// The derived private key.
+    pub private_key: SecretKey,
+}
+
+impl EthereumKey {
+    /// Returns the 20-byte account address: the last 20 bytes of the Keccak-256
+    /// hash of the uncompressed public key.
+    pub fn address(&self) -> [u8; 20] {
+        let point = self.private_key.public_key().to_encoded_point(false);
+        let hash = Keccak256::digest(&point.as_bytes()[1..]);
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&hash[12..]);
+        address
+    }
+
+    /// Returns the account address as a `0x`-prefixed hex string with the mixed-case
+    /// checksum specified by [EIP-55](https://eips.ethereum.org/EIPS/eip-55).
+    pub fn checksum_address(&self) -> String {
+        checksum_address(&self.address())
+    }
+
+    /// Returns the private key as a `0x`-prefixed hex string, as accepted by most
+    /// Ethereum wallets.
+    pub fn private_key_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.private_key.to_bytes()))
+    }
+}
+
+/// Formats an address with the EIP-55 checksum: each hex letter is uppercased if
+/// the corresponding nibble of the Keccak-256 hash of the lowercase address is 8 or more.
+fn checksum_address(address: &[u8; 20]) -> String {
+    let lower = hex::encode(address);
+    let hash = Keccak256::digest(lower.as_bytes());
+
+    let mut s = String::from("0x");
+    for (i, c) in lower.chars().enumerate() {
+        let nibble = (hash[i / 2] >> (4 * (1 - i % 2))) & 0xf;
+        s.push(if nibble >= 8 {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        });
+    }
+    s
+}
+
+impl Deck {
+    /// Derives a secp256k1 key for an Ethereum account from the `Deck` and an optional
+    /// password, using `Deck::derive_indexed` with the purpose `"ethereum"` and index
+    /// 0 as the private key.
+    ///
+    /// The purpose keeps the key apart from the one `Deck::bitcoin_key` derives, so
+    /// that importing one key into the other chain's wallet would not hand over the
+    /// funds on both.
+    ///
+    /// Panics in the astronomically unlikely case that the hash is not a valid
+    /// secp256k1 private key.
+    ///
+    /// ```
+    /// use cardseed::Deck;
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let key = deck.ethereum_key(Some("bestpasswordever"));
+    /// assert_eq!(key.checksum_address().len(), 42);
+    /// ```
+    pub fn ethereum_key(&self, password: Option<&str>) -> EthereumKey {
+        let secret = Zeroizing::new(self.derive_indexed(password, ETHEREUM_PURPOSE, 0));
+        match SecretKey::from_slice(secret.as_slice()) {
+            Ok(private_key) => EthereumKey { private_key },
+            Err(e) => panic!("failed to use 32 bytes of entropy as a private key: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eip55() {
+        // Test vectors from EIP-55.
+        for expected in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            let mut address = [0u8; 20];
+            hex::decode_to_slice(expected[2..].to_lowercase(), &mut address).unwrap();
+            assert_eq!(checksum_address(&address), expected);
+        }
+    }
+
+    #[test]
+    fn address() {
+        let private_key = SecretKey::from_slice(
+            &hex::decode("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+                .unwrap(),
+        )
+        .unwrap();
+        let key = EthereumKey { private_key };
+        assert_eq!(
+            key.checksum_address(),
+            "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23"
+        );
+        assert_eq!(
+            key.private_key_hex(),
+            "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+        );
+    }
+
+    #[test]
+    fn ethereum_key() {
+        let deck = Deck::new();
+        let key = deck.ethereum_key(Some("slick"));
+        assert_eq!(
+            key.private_key.to_bytes().as_slice(),
+            deck.derive_indexed(Some("slick"), "ethereum", 0)
+        );
+        // The key must never be the raw hash, which other chains use as a key.
+        assert_ne!(
+            key.private_key.to_bytes().as_slice(),
+            deck.hash(Some("slick"))
+        );
+        assert_ne!(key.address(), deck.ethereum_key(None).address());
+    }
+
+    #[cfg(feature = "bitcoin")]
+    #[test]
+    fn differs_from_bitcoin_key() {
+        let deck = Deck::new();
+        let eth = deck.ethereum_key(Some("slick"));
+        let btc = deck.bitcoin_key(Some("slick"), bitcoin::Network::Bitcoin);
+        assert_ne!(
+            eth.private_key.to_bytes().as_slice(),
+            btc.private_key.inner.secret_bytes()
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! A C-compatible interface to `cardseed`, for linking from C, C++, or mobile apps.
+//!
+//! Decks are passed across the boundary as opaque `CardseedDeck` pointers, which must be
+//! released with `cardseed_deck_free`. Strings returned by this module must be released
+//! with `cardseed_string_free`. Every fallible function returns a `CardseedStatus`.
+//!
+//! The matching C header is generated by [cbindgen](https://github.com/mozilla/cbindgen)
+//! into `include/cardseed.h`. Regenerate it after changing this module with:
+//!
+//! ```text
+//! cbindgen --config cbindgen.toml --crate cardseed --output include/cardseed.h
+//! ```
+
+use crate::deck::Deck;
+use crate::errors;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use zeroize::Zeroize;
+
+/// An opaque handle to a `Deck`.
+pub struct CardseedDeck(Deck);
+
+/// Status codes returned by fallible FFI functions.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CardseedStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// A string argument was not valid UTF-8.
+    InvalidUtf8 = 2,
+    /// A card string could not be parsed.
+    UnknownCard = 3,
+    /// The deck contains a duplicate card.
+    Duplicate = 4,
+    /// The deck is missing cards.
+    Incomplete = 5,
+    /// The deck has the wrong number of cards.
+    WrongLength = 6,
+}
+
+impl From<errors::DeckError> for CardseedStatus {
+    fn from(e: errors::DeckError) -> CardseedStatus {
+        match e {
+            errors::DeckError::Duplicate(_) => CardseedStatus::Duplicate,
+            errors::DeckError::Incomplete(_) => CardseedStatus::Incomplete,
+            errors::DeckError::WrongLength { .. } => CardseedStatus::WrongLength,
+            errors::DeckError::UnknownCard { .. } => CardseedStatus::UnknownCard,
+        }
+    }
+}
+
+/// Reads a nullable C string as an optional `&str`.
+unsafe fn optional_str<'a>(s: *const c_char) -> Result<Option<&'a str>, CardseedStatus> {
+    if s.is_null() {
+        return Ok(None);
+    }
+    match CStr::from_ptr(s).to_str() {
+        Ok(s) => Ok(Some(s)),
+        Err(_) => Err(CardseedStatus::InvalidUtf8),
+    }
+}
+
+/// Parses a deck from a NUL-terminated string of whitespace-delimited card strings.
+/// On success, a new deck handle is written to `out`.
+///
+/// # Safety
+///
+/// `s` must be a valid NUL-terminated string, and `out` must be a valid pointer
+/// to write a `CardseedDeck` pointer to.
+#[no_mangle]
+pub unsafe extern "C" fn cardseed_deck_parse(
+    s: *const c_char,
+    out: *mut *mut CardseedDeck,
+) -> CardseedStatus {
+    if out.is_null() {
+        return CardseedStatus::NullPointer;
+    }
+    let s = match optional_str(s) {
+        Ok(Some(s)) => s,
+        Ok(None) => return CardseedStatus::NullPointer,
+        Err(status) => return status,
+    };
+
+    match s.parse::<Deck>() {
+        Ok(deck) => {
+            *out = Box::into_raw(Box::new(CardseedDeck(deck)));
+            CardseedStatus::Ok
+        }
+        Err(e) => e.into(),
+    }
+}
+
+/// Creates a new deck handle in canonical order.
+#[no_mangle]
+pub extern "C" fn cardseed_deck_new() -> *mut CardseedDeck {
+    Box::into_raw(Box::new(CardseedDeck(Deck::new())))
+}
+
+/// Creates a randomly shuffled copy of a deck, using a secure OS RNG.
+/// Returns null if `deck` is null.
+///
+/// # Safety
+///
+/// `deck` must be null or a live handle returned by this module.
+#[no_mangle]
+pub unsafe extern "C" fn cardseed_deck_shuffle(deck: *const CardseedDeck) -> *mut CardseedDeck {
+    match deck.as_ref() {
+        Some(deck) => Box::into_raw(Box::new(CardseedDeck(deck.0.shuffle()))),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Releases a deck handle. Passing null is a no-op.
+///
+/// # Safety
+///
+/// `deck` must be null or a live handle returned by this module, and must
+/// not be used again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn cardseed_deck_free(deck: *mut CardseedDeck) {
+    if !deck.is_null() {
+        drop(Box::from_raw(deck));
+    }
+}
+
+/// Returns the number of cards in a deck, or zero if `deck` is null.
+///
+/// # Safety
+///
+/// `deck` must be null or a live handle returned by this module.
+#[no_mangle]
+pub unsafe extern "C" fn cardseed_deck_len(deck: *const CardseedDeck) -> usize {
+    deck.as_ref().map_or(0, |deck| deck.0.cards.len())
+}
+
+/// Checks that a deck is a complete 52-card deck without duplicates.
+///
+/// # Safety
+///
+/// `deck` must be null or a live handle returned by this module.
+#[no_mangle]
+pub unsafe extern "C" fn cardseed_deck_validate(deck: *const CardseedDeck) -> CardseedStatus {
+    match deck.as_ref() {
+        Some(deck) => match deck.0.validate() {
+            Ok(()) => CardseedStatus::Ok,
+            Err(e) => e.into(),
+        },
+        None => CardseedStatus::NullPointer,
+    }
+}
+
+/// Derives a 32-byte secret from a deck, writing it to `out`. `password` may
+/// be null if no password is used.
+///
+/// # Safety
+///
+/// `deck` must be a live handle returned by this module, `password` must be null
+/// or a valid NUL-terminated string, and `out` must point to 32 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn cardseed_deck_hash(
+    deck: *const CardseedDeck,
+    password: *const c_char,
+    out: *mut u8,
+) -> CardseedStatus {
+    let deck = match deck.as_ref() {
+        Some(deck) => deck,
+        None => return CardseedStatus::NullPointer,
+    };
+    if out.is_null() {
+        return CardseedStatus::NullPointer;
+    }
+    let password = match optional_str(password) {
+        Ok(password) => password,
+        Err(status) => return status,
+    };
+
+    let mut hash = deck.0.hash(password);
+    ptr::copy_nonoverlapping(hash.as_ptr(), out, hash.len());
+    hash.zeroize();
+    CardseedStatus::Ok
+}
+
+/// Formats a deck as a newly allocated, NUL-terminated string of space-delimited
+/// cards. Returns null if `deck` is null. The string must be released with
+/// `cardseed_string_free`.
+///
+/// # Safety
+///
+/// `deck` must be null or a live handle returned by this module.
+#[no_mangle]
+pub unsafe extern "C" fn cardseed_deck_to_string(deck: *const CardseedDeck) -> *mut c_char {
+    match deck.as_ref() {
+        Some(deck) => match CString::new(deck.0.to_string()) {
+            Ok(s) => s.into_raw(),
+            Err(_) => ptr::null_mut(),
+        },
+        None => ptr::null_mut(),
+    }
+}
+
+/// Releases a string returned by this module. Passing null is a no-op.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by this module, and must not be
+/// used again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn cardseed_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hash_free() {
+        unsafe {
+            let mut deck = ptr::null_mut();
+            let s = CString::new(Deck::new().to_string()).unwrap();
+            assert_eq!(
+                cardseed_deck_parse(s.as_ptr(), &mut deck),
+                CardseedStatus::Ok
+            );
+            assert_eq!(cardseed_deck_len(deck), 52);
+            assert_eq!(cardseed_deck_validate(deck), CardseedStatus::Ok);
+
+            let password = CString::new("slick").unwrap();
+            let mut out = [0u8; 32];
+            assert_eq!(
+                cardseed_deck_hash(deck, password.as_ptr(), out.as_mut_ptr()),
+                CardseedStatus::Ok
+            );
+            assert_eq!(out, Deck::new().hash(Some("slick")));
+
+            assert_eq!(
+                cardseed_deck_hash(deck, ptr::null(), out.as_mut_ptr()),
+                CardseedStatus::Ok
+            );
+            assert_eq!(out, Deck::new().hash(None));
+
+            let formatted = cardseed_deck_to_string(deck);
+            assert_eq!(
+                CStr::from_ptr(formatted).to_str(),
+                Ok(Deck::new().to_string().as_str())
+            );
+            cardseed_string_free(formatted);
+            cardseed_deck_free(deck);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        unsafe {
+            let mut deck = ptr::null_mut();
+            let s = CString::new("AS XX").unwrap();
+            assert_eq!(
+                cardseed_deck_parse(s.as_ptr(), &mut deck),
+                CardseedStatus::UnknownCard
+            );
+            assert!(deck.is_null());
+            assert_eq!(
+                cardseed_deck_parse(ptr::null(), &mut deck),
+                CardseedStatus::NullPointer
+            );
+
+            let s = CString::new("AS 2S AS").unwrap();
+            assert_eq!(
+                cardseed_deck_parse(s.as_ptr(), &mut deck),
+                CardseedStatus::Ok
+            );
+            assert_eq!(cardseed_deck_validate(deck), CardseedStatus::Duplicate);
+            cardseed_deck_free(deck);
+
+            assert_eq!(
+                cardseed_deck_validate(ptr::null()),
+                CardseedStatus::NullPointer
+            );
+            assert!(cardseed_deck_shuffle(ptr::null()).is_null());
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use crate::deck::Deck;
+use crate::errors;
+use std::fs;
+use std::path::Path;
+
+/// The bytes which begin every `.deck` file.
+pub const DECK_FILE_MAGIC: [u8; 4] = *b"CSDK";
+
+/// The version of the `.deck` format written by `DeckFile::encode`.
+pub const DECK_FILE_VERSION: u8 = 1;
+
+/// The reflected CRC-32 polynomial, as used by zip and PNG.
+const CRC32_POLYNOMIAL: u32 = 0xEDB88320;
+
+/// A `Deck` with an optional label, as stored in a `.deck` file, so that
+/// applications persist decks in one consistent format. Every field is
+/// length-prefixed and the whole file is covered by a CRC-32:
+///
+/// ```text
+/// "CSDK" || u8(version) || u16be(len(deck)) || deck || u16be(len(label)) || label || u32be(crc32)
+/// ```
+///
+/// where `deck` is the packed binary deck, `label` is UTF-8, and the CRC covers
+/// every preceding byte. An empty label is read back as `None`.
+///
+/// ```
+/// use cardseed::{Deck, DeckFile};
+///
+/// let file = DeckFile {
+///     deck: "AS 3H KD".parse().unwrap(),
+///     label: Some("wallet".into()),
+/// };
+/// let bytes = file.encode().unwrap();
+/// assert_eq!(&bytes[..5], b"CSDK\x01");
+/// assert_eq!(DeckFile::decode(&bytes).unwrap(), file);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeckFile {
+    /// The cards, stored in packed binary form.
+    pub deck: Deck,
+    /// A name for the deck, such as what it secures. Never put secrets here.
+    pub label: Option<String>,
+}
+
+impl DeckFile {
+    /// Encodes the file in the current `.deck` format. Returns an `Err` if the
+    /// deck or label is longer than 65535 cards or bytes.
+    pub fn encode(&self) -> Result<Vec<u8>, errors::DeckFileError> {
+        let label = self.label.as_deref().unwrap_or_default();
+        let deck_len = field_len(self.deck.cards.len())?;
+        let label_len = field_len(label.len())?;
+
+        let mut bytes = Vec::with_capacity(13 + self.deck.cards.len() + label.len());
+        bytes.extend_from_slice(&DECK_FILE_MAGIC);
+        bytes.push(DECK_FILE_VERSION);
+        bytes.extend_from_slice(&deck_len.to_be_bytes());
+        bytes.extend(self.deck.cards.iter().map(|card| card.index() as u8));
+        bytes.extend_from_slice(&label_len.to_be_bytes());
+        bytes.extend_from_slice(label.as_bytes());
+        bytes.extend_from_slice(&crc32(&bytes).to_be_bytes());
+        Ok(bytes)
+    }
+
+    /// Decodes a file in the `.deck` format, checking its magic bytes, version and
+    /// CRC. Like `Deck::unpack`, this doesn't check the deck for duplicates.
+    pub fn decode(bytes: &[u8]) -> Result<DeckFile, errors::DeckFileError> {
+        if !bytes.starts_with(&DECK_FILE_MAGIC) {
+            return Err(errors::DeckFileError::Magic);
+        }
+        if bytes.len() < DECK_FILE_MAGIC.len() + 5 {
+            return Err(errors::DeckFileError::Truncated);
+        }
+        let version = bytes[DECK_FILE_MAGIC.len()];
+        if version != DECK_FILE_VERSION {
+            return Err(errors::DeckFileError::Version(version));
+        }
+        let (body, crc) = bytes.split_at(bytes.len() - 4);
+        if crc32(body).to_be_bytes() != crc {
+            return Err(errors::DeckFileError::Checksum);
+        }
+
+        let mut rest = &body[DECK_FILE_MAGIC.len() + 1..];
+        let deck_len = take_len(&mut rest)?;
+        let deck = Deck::unpack(take(&mut rest, deck_len)?)?;
+        let label_len = take_len(&mut rest)?;
+        let label = std::str::from_utf8(take(&mut rest, label_len)?)
+            .map_err(|_| errors::DeckFileError::Label)?;
+        if !rest.is_empty() {
+            return Err(errors::DeckFileError::Truncated);
+        }
+
+        Ok(DeckFile {
+            deck,
+            label: (!label.is_empty()).then(|| label.to_string()),
+        })
+    }
+
+    /// Writes the file to `path` in the `.deck` format, replacing any existing file.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), errors::DeckFileError> {
+        Ok(fs::write(path, self.encode()?)?)
+    }
+
+    /// Reads a `.deck` file from `path`.
+    pub fn read(path: impl AsRef<Path>) -> Result<DeckFile, errors::DeckFileError> {
+        DeckFile::decode(&fs::read(path)?)
+    }
+}
+
+impl Deck {
+    /// Writes the `Deck` without a label to a `.deck` file at `path`. Use `DeckFile`
+    /// to include a label.
+    ///
+    /// This is distinct from `Deck::read_from`, which parses cards written as text.
+    ///
+    /// ```no_run
+    /// use cardseed::Deck;
+    ///
+    /// let deck = "AS 3H KD".parse::<Deck>().unwrap();
+    /// deck.write_file("wallet.deck").unwrap();
+    /// assert_eq!(Deck::read_file("wallet.deck").unwrap(), deck);
+    /// ```
+    pub fn write_file(&self, path: impl AsRef<Path>) -> Result<(), errors::DeckFileError> {
+        DeckFile {
+            deck: self.clone(),
+            label: None,
+        }
+        .write(path)
+    }
+
+    /// Reads a `Deck` from a `.deck` file at `path`, ignoring any label.
+    pub fn read_file(path: impl AsRef<Path>) -> Result<Deck, errors::DeckFileError> {
+        Ok(DeckFile::read(path)?.deck)
+    }
+}
+
+/// Checks that a field fits in a `u16be` length prefix.
+fn field_len(len: usize) -> Result<u16, errors::DeckFileError> {
+    u16::try_from(len).map_err(|_| errors::DeckFileError::TooLong(len))
+}
+
+/// Splits `n` bytes off the front of `rest`.
+fn take<'a>(rest: &mut &'a [u8], n: usize) -> Result<&'a [u8], errors::DeckFileError> {
+    if rest.len() < n {
+        return Err(errors::DeckFileError::Truncated);
+    }
+    let (field, remaining) = rest.split_at(n);
+    *rest = remaining;
+    Ok(field)
+}
+
+/// Splits a `u16be` length prefix off the front of `rest`.
+fn take_len(rest: &mut &[u8]) -> Result<usize, errors::DeckFileError> {
+    let bytes = take(rest, 2)?;
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]) as usize)
+}
+
+/// Computes the CRC-32 of `bytes`, bit by bit, since files are tiny.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (CRC32_POLYNOMIAL & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc32() {
+        assert_eq!(super::crc32(b""), 0);
+        assert_eq!(super::crc32(b"123456789"), 0xCBF43926);
+    }
+
+    #[test]
+    fn decode() {
+        let file = DeckFile {
+            deck: Deck::new(),
+            label: Some("cold storage".into()),
+        };
+        let bytes = file.encode().unwrap();
+        assert_eq!(bytes.len(), 13 + 52 + 12);
+        assert_eq!(DeckFile::decode(&bytes).unwrap(), file);
+
+        let unlabeled = DeckFile {
+            deck: Deck { cards: vec![] },
+            label: Some(String::new()),
+        };
+        let decoded = DeckFile::decode(&unlabeled.encode().unwrap()).unwrap();
+        assert_eq!(decoded.label, None);
+
+        assert!(matches!(
+            DeckFile::decode(b"PK\x03\x04"),
+            Err(errors::DeckFileError::Magic)
+        ));
+        assert!(matches!(
+            DeckFile::decode(&bytes[..20]),
+            Err(errors::DeckFileError::Checksum)
+        ));
+        assert!(matches!(
+            DeckFile::decode(b"CSDK\x01"),
+            Err(errors::DeckFileError::Truncated)
+        ));
+        let mut corrupted = bytes.clone();
+        corrupted[10] ^= 1;
+        assert!(matches!(
+            DeckFile::decode(&corrupted),
+            Err(errors::DeckFileError::Checksum)
+        ));
+        let mut future = bytes.clone();
+        future[4] = 2;
+        assert!(matches!(
+            DeckFile::decode(&future),
+            Err(errors::DeckFileError::Version(2))
+        ));
+
+        // A card index out of range is caught even with a valid CRC.
+        let mut body = b"CSDK\x01\x00\x01\x34\x00\x00".to_vec();
+        body.extend_from_slice(&super::crc32(&body).to_be_bytes());
+        assert!(matches!(
+            DeckFile::decode(&body),
+            Err(errors::DeckFileError::Deck(_))
+        ));
+
+        let long = DeckFile {
+            deck: Deck::new(),
+            label: Some("x".repeat(70000)),
+        };
+        assert!(matches!(
+            long.encode(),
+            Err(errors::DeckFileError::TooLong(70000))
+        ));
+    }
+
+    #[test]
+    fn read_file() {
+        let path = std::env::temp_dir().join(format!("cardseed-{}.deck", std::process::id()));
+        let deck = "AS 3H KD".parse::<Deck>().unwrap();
+        deck.write_file(&path).unwrap();
+        assert_eq!(Deck::read_file(&path).unwrap(), deck);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            Deck::read_file(&path),
+            Err(errors::DeckFileError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::card::{Card, STANDARD_DECK};
+use crate::deck::Deck;
+use crate::errors;
+use crate::DECK_SIZE;
+#[cfg(feature = "rand")]
+use rand::{seq::SliceRandom, CryptoRng, RngCore};
+use std::fmt;
+
+/// A complete 52-card deck stored inline in a fixed-size array. A `FullDeck` always
+/// holds each card exactly once, so unlike `Deck` it never needs validating, and
+/// creating, shuffling, or copying one never allocates.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FullDeck([Card; DECK_SIZE]);
+
+impl FullDeck {
+    /// Creates a `FullDeck` in the same canonical order as `Deck::new`.
+    pub const fn new() -> FullDeck {
+        FullDeck(STANDARD_DECK)
+    }
+
+    /// Returns the cards in the deck, in order.
+    pub const fn cards(&self) -> &[Card; DECK_SIZE] {
+        &self.0
+    }
+
+    /// Randomly shuffles the `FullDeck` using a secure OS RNG.
+    ///
+    /// ```
+    /// use cardseed::FullDeck;
+    ///
+    /// let deck = FullDeck::new().shuffle();
+    /// assert!(deck.to_deck().validate().is_ok());
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn shuffle(&self) -> FullDeck {
+        self.shuffle_with_rng(&mut rand::rngs::OsRng)
+    }
+
+    /// Randomly shuffles the `FullDeck` using the given secure random number generator,
+    /// for platforms without an operating system RNG.
+    #[cfg(feature = "rand")]
+    pub fn shuffle_with_rng<R: CryptoRng + RngCore + ?Sized>(&self, rng: &mut R) -> FullDeck {
+        let mut shuffled = *self;
+        shuffled.0.shuffle(rng);
+        shuffled
+    }
+
+    /// Copies the cards into a heap-allocated `Deck`.
+    pub fn to_deck(&self) -> Deck {
+        Deck {
+            cards: self.0.to_vec(),
+        }
+    }
+}
+
+impl Default for FullDeck {
+    fn default() -> FullDeck {
+        FullDeck::new()
+    }
+}
+
+impl AsRef<[Card]> for FullDeck {
+    fn as_ref(&self) -> &[Card] {
+        &self.0
+    }
+}
+
+impl From<FullDeck> for Deck {
+    fn from(deck: FullDeck) -> Deck {
+        deck.to_deck()
+    }
+}
+
+impl TryFrom<&Deck> for FullDeck {
+    type Error = errors::DeckError;
+
+    /// Converts a `Deck` into a `FullDeck`. Returns an `Err` if the `Deck` is not a
+    /// complete 52-card deck without duplicates, as checked by `Deck::validate`.
+    ///
+    /// ```
+    /// use cardseed::{Deck, FullDeck};
+    ///
+    /// let deck = Deck::new();
+    /// let full = FullDeck::try_from(&deck).unwrap();
+    /// assert_eq!(full.to_deck(), deck);
+    /// assert!(FullDeck::try_from(&"AS 2S".parse::<Deck>().unwrap()).is_err());
+    /// ```
+    fn try_from(deck: &Deck) -> Result<FullDeck, errors::DeckError> {
+        deck.validate()?;
+        let mut cards = [Card::ace_of_spades(); DECK_SIZE];
+        cards.copy_from_slice(&deck.cards);
+        Ok(FullDeck(cards))
+    }
+}
+
+impl fmt::Display for FullDeck {
+    /// Formats the `FullDeck` as space-delimited cards, like `Deck`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, card) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{card}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn full_deck() {
+        let deck = FullDeck::new();
+        assert_eq!(deck, FullDeck::default());
+        assert_eq!(deck.to_deck(), Deck::new());
+        assert_eq!(deck.to_string(), Deck::new().to_string());
+
+        let shuffled = deck.shuffle();
+        assert_ne!(shuffled, deck);
+        assert_eq!(FullDeck::try_from(&shuffled.to_deck()), Ok(shuffled));
+        assert_eq!(Deck::from(shuffled).cards, shuffled.cards().to_vec());
+
+        use rand::SeedableRng;
+        let seeded = || rand_chacha::ChaCha20Rng::seed_from_u64(5);
+        let shuffled = deck.shuffle_with_rng(&mut seeded());
+        assert_ne!(shuffled, deck);
+        assert_eq!(shuffled, deck.shuffle_with_rng(&mut seeded()));
+    }
+
+    #[test]
+    fn try_from_errors() {
+        let mut deck = Deck::new();
+        deck.cards[5] = deck.cards[4];
+        assert_eq!(
+            FullDeck::try_from(&deck),
+            Err(errors::DeckError::Duplicate(deck.cards[4]))
+        );
+
+        deck.cards.truncate(4);
+        assert!(matches!(
+            FullDeck::try_from(&deck),
+            Err(errors::DeckError::Incomplete(_))
+        ));
+    }
+}
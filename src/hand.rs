@@ -0,0 +1,199 @@
+use crate::card::Card;
+use crate::deck::{CardSet, Deck};
+use crate::errors;
+use crate::DECK_SIZE;
+use std::fmt;
+
+/// An unordered hand of distinct cards, such as a hand drawn from a shuffled deck
+/// whose order wasn't recorded. The cards are kept sorted by index, so two hands
+/// with the same cards are equal however they were given.
+///
+/// A hand of `k` cards can be numbered by its index in the
+/// [combinatorial number system](https://en.wikipedia.org/wiki/Combinatorial_number_system),
+/// from zero up to `Hand::combinations(k)`, which stores it more compactly than a
+/// list of cards and allows encoding data as a choice of cards.
+///
+/// ```
+/// use cardseed::Hand;
+///
+/// let hand = "KD AS 3H".parse::<Hand>().unwrap();
+/// assert_eq!(hand.to_string(), "AS 3H KD");
+///
+/// let index = hand.to_combination_index();
+/// assert!(index < Hand::combinations(3));
+/// assert_eq!(Hand::from_combination_index(3, index), Ok(hand));
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Hand {
+    cards: Vec<Card>,
+}
+
+impl Hand {
+    /// Creates a `Hand` from the given cards in any order. Returns an `Err` if a card
+    /// appears more than once.
+    pub fn from_cards(cards: &[Card]) -> Result<Hand, errors::HandError> {
+        let mut seen = CardSet::default();
+        if let Some(&card) = cards.iter().find(|&&card| !seen.insert(card)) {
+            return Err(errors::DeckError::Duplicate(card).into());
+        }
+        let mut cards = cards.to_vec();
+        cards.sort_unstable_by_key(Card::index);
+        Ok(Hand { cards })
+    }
+
+    /// Returns the cards in the hand, sorted by index.
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Returns the number of cards in the hand.
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Returns true if the hand has no cards.
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Returns the number of distinct hands of `k` cards, `C(52, k)`, which is zero
+    /// if `k` is more than 52.
+    pub fn combinations(k: usize) -> u64 {
+        binomial(DECK_SIZE, k)
+    }
+
+    /// Returns the index of the hand in the combinatorial number system: the sum of
+    /// `C(c_i, i + 1)` over the card indexes `c_0 < c_1 < ...` of the hand. Hands of
+    /// `k` cards have indexes below `Hand::combinations(k)`.
+    pub fn to_combination_index(&self) -> u64 {
+        self.cards
+            .iter()
+            .enumerate()
+            .map(|(i, card)| binomial(card.index() as usize, i + 1))
+            .sum()
+    }
+
+    /// Returns the hand of `k` cards with the given index in the combinatorial
+    /// number system. Returns an `Err` if `k` is more than 52, or the index is not
+    /// below `Hand::combinations(k)`.
+    pub fn from_combination_index(k: usize, index: u64) -> Result<Hand, errors::HandError> {
+        if k > DECK_SIZE {
+            return Err(errors::HandError::Size(k));
+        }
+        if index >= Hand::combinations(k) {
+            return Err(errors::HandError::BadIndex(index));
+        }
+
+        // Choose each card greedily from the highest down: the largest c with
+        // C(c, i) no more than what remains of the index.
+        let mut remaining = index;
+        let mut cards = Vec::with_capacity(k);
+        let mut c = DECK_SIZE;
+        for i in (1..=k).rev() {
+            c -= 1;
+            while binomial(c, i) > remaining {
+                c -= 1;
+            }
+            remaining -= binomial(c, i);
+            cards.push(Card::from_index(c as u8).expect("c is below 52"));
+        }
+        cards.reverse();
+        Ok(Hand { cards })
+    }
+}
+
+/// Returns the binomial coefficient `C(n, k)`, which is exact in a `u64` for any
+/// `n` up to 52.
+fn binomial(n: usize, k: usize) -> u64 {
+    if k > n {
+        return 0;
+    }
+    let k = k.min(n - k);
+    (0..k).fold(1, |c, i| c * (n - i) as u64 / (i + 1) as u64)
+}
+
+impl fmt::Display for Hand {
+    /// Formats the `Hand` as a space-delimited string of its cards, sorted by index.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, card) in self.cards.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{card}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Hand {
+    type Err = errors::HandError;
+
+    /// Parses a `Hand` from a string of whitespace-delimited cards in any order.
+    fn from_str(s: &str) -> Result<Hand, errors::HandError> {
+        let deck = s.parse::<Deck>()?;
+        Hand::from_cards(&deck.cards)
+    }
+}
+
+impl From<Hand> for Deck {
+    /// Converts a `Hand` into a `Deck` of its cards, sorted by index.
+    fn from(hand: Hand) -> Deck {
+        Deck { cards: hand.cards }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binomial() {
+        assert_eq!(super::binomial(52, 5), 2598960);
+        assert_eq!(super::binomial(52, 26), 495918532948104);
+        assert_eq!(super::binomial(52, 0), 1);
+        assert_eq!(super::binomial(3, 4), 0);
+    }
+
+    #[test]
+    fn combination_index() {
+        assert_eq!(
+            Hand::from_combination_index(2, 0).unwrap().to_string(),
+            "AS 2S"
+        );
+        assert_eq!(
+            Hand::from_combination_index(2, Hand::combinations(2) - 1)
+                .unwrap()
+                .to_string(),
+            "QD KD"
+        );
+        for index in (0..Hand::combinations(3)).step_by(997) {
+            let hand = Hand::from_combination_index(3, index).unwrap();
+            assert_eq!(hand.len(), 3);
+            assert_eq!(hand.to_combination_index(), index);
+        }
+
+        let full = Hand::from_combination_index(DECK_SIZE, 0).unwrap();
+        assert_eq!(Deck::from(full), Deck::new());
+        assert_eq!(Hand::from_combination_index(0, 0).unwrap().len(), 0);
+        assert_eq!(
+            Hand::from_combination_index(2, Hand::combinations(2)),
+            Err(errors::HandError::BadIndex(1326))
+        );
+        assert_eq!(
+            Hand::from_combination_index(53, 0),
+            Err(errors::HandError::Size(53))
+        );
+    }
+
+    #[test]
+    fn from_cards() {
+        assert_eq!(
+            "7D 2C 7D".parse::<Hand>(),
+            Err(errors::DeckError::Duplicate("7D".parse().unwrap()).into())
+        );
+        assert_eq!(
+            "7D 2C".parse::<Hand>().unwrap(),
+            "2C 7D".parse::<Hand>().unwrap()
+        );
+    }
+}
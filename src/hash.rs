@@ -0,0 +1,1880 @@
+use crate::deck::Deck;
+use crate::errors;
+use hmac::{Hmac, Mac};
+#[cfg(feature = "rand")]
+use rand::{CryptoRng, RngCore};
+use sha2::Sha256;
+use std::fmt::{self, Write};
+use std::time::{Duration, Instant};
+use subtle::ConstantTimeEq;
+use unicode_normalization::UnicodeNormalization;
+use zeroize::{Zeroize, Zeroizing};
+
+/// The default number of PBKDF2 iterations used to derive secure entropy from a `Deck`.
+pub const PBKDF2_ITERATIONS: u32 = 1 << 16;
+
+/// Domain separation tag which begins every `Preimage::Framed` preimage.
+const FRAMED_TAG: &[u8] = b"cardseed/framed";
+
+/// Domain separation tag which begins every `Preimage::Binary` preimage.
+const BINARY_TAG: &[u8] = b"cardseed/binary";
+
+/// Domain separation tag which begins every `Preimage::Unordered` preimage.
+const UNORDERED_TAG: &[u8] = b"cardseed/unordered";
+
+/// The number of PBKDF2 iterations between calls to the progress callback given
+/// to `Deck::hash_with_progress`.
+pub const PROGRESS_INTERVAL: u32 = 1 << 12;
+
+/// Domain separation tag which begins every preimage hashed by `Deck::hash_mixed`.
+const MIXED_TAG: &[u8] = b"cardseed/mixed/v1";
+
+/// The number of random bytes in an `EntropySource::os_random` source.
+pub const OS_RANDOM_SIZE: usize = 32;
+
+/// The HKDF salt used by `Deck::derive_indexed`.
+const INDEXED_SALT: &[u8] = b"cardseed/indexed";
+
+/// The HKDF salt used by `Deck::hash_decoy`.
+const DECOY_SALT: &[u8] = b"cardseed/decoy";
+
+/// The HKDF salt used by `Deck::shuffle_hybrid`.
+#[cfg(feature = "rand")]
+const HYBRID_SALT: &[u8] = b"cardseed/hybrid-shuffle";
+
+/// The version field of a serialized `SaltedHash` whose options match no `HashVersion`.
+const CUSTOM_VERSION: &str = "custom";
+
+/// The most PBKDF2 iterations which a parsed `SaltedHash` may ask for, so that
+/// verifying against an untrusted record can't hang the caller.
+const MAX_SALTED_ITERATIONS: u32 = 1 << 24;
+
+/// The number of random bytes in the salt generated by `Deck::hash_salted`.
+pub const SALT_SIZE: usize = 16;
+
+/// The capacity of the stack buffer in which preimages are built: enough for a
+/// framed full deck string and a password of up to 64 bytes. Longer preimages are
+/// built on the heap instead.
+const PREIMAGE_BUFFER_SIZE: usize = 256;
+
+/// Selects how the secret preimage stretched by `Deck::hash_with` is built from
+/// a `Deck` and an optional password.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Preimage {
+    /// The original preimage: the formatted deck, followed by a colon and the
+    /// password if one is given. Kept so that existing secrets remain recoverable.
+    /// New applications should prefer `Preimage::Framed`.
+    #[default]
+    Legacy,
+
+    /// A domain-separated preimage in which the deck and password are each
+    /// prefixed by their length, so that no two distinct deck and password
+    /// combinations can produce the same preimage. The preimage is:
+    ///
+    /// ```text
+    /// "cardseed/framed" || u32be(len(deck)) || deck || u8(has_password) || u32be(len(password)) || password
+    /// ```
+    ///
+    /// where `deck` is the formatted deck string.
+    Framed,
+
+    /// A domain-separated preimage like `Preimage::Framed`, but which encodes the
+    /// deck in its packed binary form (see `Deck::pack`) rather than as a string,
+    /// so it does not depend on the textual card format:
+    ///
+    /// ```text
+    /// "cardseed/binary" || u32be(len(deck)) || deck || u8(has_password) || u32be(len(password)) || password
+    /// ```
+    Binary,
+
+    /// A domain-separated binary preimage like `Preimage::Binary`, but with the
+    /// cards sorted by index first, so that the order in which they were recorded
+    /// doesn't matter. This suits a hand of cards drawn from a shuffled deck whose
+    /// order can't be preserved, and which holds only as much entropy as
+    /// `Deck::entropy_bits_for_hand` reports:
+    ///
+    /// ```text
+    /// "cardseed/unordered" || u32be(len(deck)) || sorted(deck) || u8(has_password) || u32be(len(password)) || password
+    /// ```
+    Unordered,
+}
+
+/// Selects how `Deck::hash_with` normalizes a password before hashing it.
+///
+/// The same accented character can be encoded as different sequences of code
+/// points, which would otherwise produce different secrets on different platforms.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Normalization {
+    /// The password is hashed exactly as given. This is the original behavior,
+    /// kept so that existing secrets remain reproducible.
+    #[default]
+    None,
+    /// The password is converted to Unicode
+    /// [NFKD](https://unicode.org/reports/tr15/) form before hashing, as BIP39 does.
+    Nfkd,
+}
+
+/// A numbered revision of the default derivation, so that the derivation can evolve
+/// while secrets derived by earlier versions remain recoverable. The version should
+/// be recorded alongside anything derived from a deck; `V1` is assumed if none was.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum HashVersion {
+    /// The original derivation used by `Deck::hash`, stretching the `Preimage::Legacy`
+    /// string preimage.
+    #[default]
+    V1,
+    /// Stretches the domain-separated `Preimage::Binary` preimage, with the password
+    /// converted to NFKD form first, as with `Normalization::Nfkd`.
+    V2,
+}
+
+impl HashVersion {
+    /// The newest version, which new applications should use.
+    pub const LATEST: HashVersion = HashVersion::V2;
+
+    /// Returns the preimage format stretched by this version.
+    pub fn preimage(&self) -> Preimage {
+        match self {
+            HashVersion::V1 => Preimage::Legacy,
+            HashVersion::V2 => Preimage::Binary,
+        }
+    }
+
+    /// Returns the password normalization applied by this version.
+    pub fn normalization(&self) -> Normalization {
+        match self {
+            HashVersion::V1 => Normalization::None,
+            HashVersion::V2 => Normalization::Nfkd,
+        }
+    }
+
+    /// Returns the `HashOptions` which derive secrets according to this version.
+    ///
+    /// ```
+    /// use cardseed::{HashOptions, HashVersion};
+    ///
+    /// assert_eq!(HashVersion::V1.options(), HashOptions::default());
+    /// assert_eq!(HashVersion::V2.options().version(), Some(HashVersion::V2));
+    /// ```
+    pub fn options(&self) -> HashOptions {
+        HashOptions {
+            preimage: self.preimage(),
+            normalization: self.normalization(),
+            ..HashOptions::default()
+        }
+    }
+}
+
+/// Options controlling how `Deck::hash_with` derives a secret from a `Deck`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HashOptions {
+    /// The preimage format to stretch.
+    pub preimage: Preimage,
+    /// The salt passed to PBKDF2. Empty by default.
+    pub salt: Vec<u8>,
+    /// The Unicode normalization applied to string passwords.
+    pub normalization: Normalization,
+    /// The number of PBKDF2 iterations. Defaults to `PBKDF2_ITERATIONS`.
+    /// An iteration count of zero is treated as one.
+    pub iterations: u32,
+}
+
+impl Default for HashOptions {
+    fn default() -> HashOptions {
+        HashOptions {
+            preimage: Preimage::default(),
+            salt: Vec::new(),
+            normalization: Normalization::default(),
+            iterations: PBKDF2_ITERATIONS,
+        }
+    }
+}
+
+/// The shortest benchmark run which `HashOptions::calibrate` extrapolates from,
+/// long enough that timer resolution and scheduling noise are negligible.
+const CALIBRATION_WINDOW: Duration = Duration::from_millis(50);
+
+impl HashOptions {
+    /// Benchmarks PBKDF2 on this machine and returns the default options with
+    /// `iterations` chosen so that a single hash takes roughly `target`. The
+    /// iteration count is never lower than one, nor higher than the 2^24 which a
+    /// serialized `SaltedHash` may hold.
+    ///
+    /// The chosen iteration count must be stored alongside anything derived with
+    /// these options, since the same count is needed to derive the secret again.
+    /// `SaltedHash` records it automatically.
+    ///
+    /// ```
+    /// use cardseed::HashOptions;
+    /// use std::time::Duration;
+    ///
+    /// let options = HashOptions::calibrate(Duration::from_millis(10));
+    /// assert!(options.iterations >= 1);
+    /// ```
+    pub fn calibrate(target: Duration) -> HashOptions {
+        let preimage = [0u8; 64];
+
+        let mut rounds: u32 = 1 << 10;
+        let elapsed = loop {
+            let start = Instant::now();
+            pbkdf2_sha256(&preimage, &[], rounds, &mut |_, _| {});
+            let elapsed = start.elapsed();
+            if elapsed >= CALIBRATION_WINDOW || rounds >= u32::MAX / 2 {
+                break elapsed;
+            }
+            rounds *= 2;
+        };
+
+        let per_second = rounds as f64 / elapsed.as_secs_f64().max(f64::MIN_POSITIVE);
+        let iterations =
+            (per_second * target.as_secs_f64()).clamp(1.0, MAX_SALTED_ITERATIONS as f64);
+
+        HashOptions {
+            iterations: iterations as u32,
+            ..HashOptions::default()
+        }
+    }
+
+    /// Returns the `HashVersion` whose preimage format and password normalization
+    /// these options use, or `None` if they match no version. The salt and
+    /// iteration count are not part of a version.
+    pub fn version(&self) -> Option<HashVersion> {
+        [HashVersion::V1, HashVersion::V2]
+            .into_iter()
+            .find(|version| {
+                self.preimage == version.preimage() && self.normalization == version.normalization()
+            })
+    }
+}
+
+/// A hash of a `Deck` derived with a random salt, bundled with the salt and the
+/// other options needed to verify it later. This is suitable for storing as a
+/// verification record, for instance to check a deck was transcribed correctly.
+///
+/// A `SaltedHash` is serialized as a `$`-delimited string of the `HashVersion`, the
+/// preimage format, the password normalization, the iteration count, the
+/// hex-encoded salt, and the hex-encoded hash:
+///
+/// ```text
+/// $cardseed$v2$binary$nfkd$65536$<salt>$<hash>
+/// ```
+///
+/// The version is `custom` if the options match no `HashVersion`, and a string whose
+/// version disagrees with its preimage format or normalization is rejected, as is
+/// one with more than 2^24 iterations.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SaltedHash {
+    /// The options the hash was derived with, including its salt.
+    pub options: HashOptions,
+    /// The derived 32-byte key.
+    pub hash: [u8; 32],
+}
+
+impl SaltedHash {
+    /// Returns true if the given `Deck` and password produce this `SaltedHash`.
+    ///
+    /// ```
+    /// use cardseed::{Deck, HashOptions, Preimage};
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let options = HashOptions {
+    ///     preimage: Preimage::Framed,
+    ///     ..HashOptions::default()
+    /// };
+    /// # #[cfg(feature = "rand")] {
+    /// let salted = deck.hash_salted(Some("hunter2"), &options);
+    /// assert!(salted.verify(&deck, Some("hunter2")));
+    /// assert!(!salted.verify(&deck, Some("hunter3")));
+    /// # }
+    /// ```
+    pub fn verify(&self, deck: &Deck, password: Option<&str>) -> bool {
+        let hash = Zeroizing::new(deck.hash_with(password, &self.options));
+        bool::from(hash.ct_eq(&self.hash))
+    }
+
+    /// Returns the `HashVersion` this hash was derived with, if its options match one.
+    pub fn version(&self) -> Option<HashVersion> {
+        self.options.version()
+    }
+}
+
+impl fmt::Display for HashVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HashVersion::V1 => write!(f, "v1"),
+            HashVersion::V2 => write!(f, "v2"),
+        }
+    }
+}
+
+impl std::str::FromStr for HashVersion {
+    type Err = errors::ParseError;
+
+    fn from_str(s: &str) -> Result<HashVersion, errors::ParseError> {
+        match s {
+            "v1" => Ok(HashVersion::V1),
+            "v2" => Ok(HashVersion::V2),
+            _ => Err(errors::ParseError::Malformed(String::from(s))),
+        }
+    }
+}
+
+impl fmt::Display for Preimage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Preimage::Legacy => write!(f, "legacy"),
+            Preimage::Framed => write!(f, "framed"),
+            Preimage::Binary => write!(f, "binary"),
+            Preimage::Unordered => write!(f, "unordered"),
+        }
+    }
+}
+
+impl std::str::FromStr for Preimage {
+    type Err = errors::ParseError;
+
+    fn from_str(s: &str) -> Result<Preimage, errors::ParseError> {
+        match s {
+            "legacy" => Ok(Preimage::Legacy),
+            "framed" => Ok(Preimage::Framed),
+            "binary" => Ok(Preimage::Binary),
+            "unordered" => Ok(Preimage::Unordered),
+            _ => Err(errors::ParseError::Malformed(String::from(s))),
+        }
+    }
+}
+
+impl fmt::Display for Normalization {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Normalization::None => write!(f, "none"),
+            Normalization::Nfkd => write!(f, "nfkd"),
+        }
+    }
+}
+
+impl std::str::FromStr for Normalization {
+    type Err = errors::ParseError;
+
+    fn from_str(s: &str) -> Result<Normalization, errors::ParseError> {
+        match s {
+            "none" => Ok(Normalization::None),
+            "nfkd" => Ok(Normalization::Nfkd),
+            _ => Err(errors::ParseError::Malformed(String::from(s))),
+        }
+    }
+}
+
+impl fmt::Display for SaltedHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "$cardseed${}${}${}${}${}${}",
+            self.version()
+                .map_or_else(|| CUSTOM_VERSION.to_string(), |v| v.to_string()),
+            self.options.preimage,
+            self.options.normalization,
+            self.options.iterations,
+            hex::encode(&self.options.salt),
+            hex::encode(self.hash)
+        )
+    }
+}
+
+impl std::str::FromStr for SaltedHash {
+    type Err = errors::ParseError;
+
+    /// Parses a `SaltedHash` from the string format produced by its `Display` implementation.
+    fn from_str(s: &str) -> Result<SaltedHash, errors::ParseError> {
+        let malformed = || errors::ParseError::Malformed(String::from(s));
+
+        let mut parts = s.split('$');
+        if parts.next() != Some("") || parts.next() != Some("cardseed") {
+            return Err(malformed());
+        }
+        let version = match parts.next().ok_or_else(malformed)? {
+            CUSTOM_VERSION => None,
+            version => Some(version.parse::<HashVersion>()?),
+        };
+        let preimage = parts.next().ok_or_else(malformed)?.parse::<Preimage>()?;
+        let normalization = parts
+            .next()
+            .ok_or_else(malformed)?
+            .parse::<Normalization>()?;
+        let iterations = parts
+            .next()
+            .ok_or_else(malformed)?
+            .parse::<u32>()
+            .ok()
+            .filter(|&iterations| iterations <= MAX_SALTED_ITERATIONS)
+            .ok_or_else(malformed)?;
+
+        let salt = hex::decode(parts.next().ok_or_else(malformed)?).map_err(|_| malformed())?;
+        let mut hash = [0u8; 32];
+        hex::decode_to_slice(parts.next().ok_or_else(malformed)?, &mut hash)
+            .map_err(|_| malformed())?;
+        if parts.next().is_some() {
+            return Err(malformed());
+        }
+
+        let salted = SaltedHash {
+            options: HashOptions {
+                preimage,
+                salt,
+                normalization,
+                iterations,
+            },
+            hash,
+        };
+        if salted.version() != version {
+            return Err(malformed());
+        }
+        Ok(salted)
+    }
+}
+
+/// An additional source of entropy which `Deck::hash_mixed` combines with a deck,
+/// so that the derived secret stays secure as long as any one source is random.
+///
+/// Every source must be recorded alongside the deck, since the secret can't be
+/// derived again without all of them.
+#[derive(Debug, Eq, PartialEq)]
+pub enum EntropySource {
+    /// The value of each die roll, in order. Use `EntropySource::dice` to parse the
+    /// rolls from a string.
+    Dice(Vec<u8>),
+    /// A second, independently shuffled deck.
+    Deck(Deck),
+    /// Arbitrary bytes, such as those returned by `EntropySource::os_random`.
+    Bytes(Vec<u8>),
+}
+
+impl EntropySource {
+    /// Returns a source of `OS_RANDOM_SIZE` bytes from the operating system's secure
+    /// random number generator. The bytes must be backed up to derive the secret again.
+    #[cfg(feature = "rand")]
+    pub fn os_random() -> EntropySource {
+        EntropySource::from_rng(&mut rand::rngs::OsRng)
+    }
+
+    /// Returns a source of `OS_RANDOM_SIZE` bytes from the given secure random number
+    /// generator, such as a hardware RNG on a device without an operating system.
+    #[cfg(feature = "rand")]
+    pub fn from_rng<R: CryptoRng + RngCore + ?Sized>(rng: &mut R) -> EntropySource {
+        let mut bytes = vec![0u8; OS_RANDOM_SIZE];
+        rng.fill_bytes(&mut bytes);
+        EntropySource::Bytes(bytes)
+    }
+
+    /// Parses a string of dice rolls separated by whitespace, such as `"3 6 1 2 5"`
+    /// or `"12 3 20"` for a d20. Each roll must be a whole number from 0 to 255, so
+    /// that every roll is encoded as exactly one byte and `"12 3"` can never be read
+    /// as `"1 23"`.
+    ///
+    /// ```
+    /// use cardseed::EntropySource;
+    ///
+    /// let rolls = EntropySource::dice("12 3\n20").unwrap();
+    /// assert_eq!(rolls, EntropySource::Dice(vec![12, 3, 20]));
+    /// assert_ne!(rolls, EntropySource::dice("1 23 20").unwrap());
+    /// assert!(EntropySource::dice("3 6 x").is_err());
+    /// ```
+    pub fn dice(rolls: &str) -> Result<EntropySource, errors::ParseError> {
+        rolls
+            .split_ascii_whitespace()
+            .map(|roll| match roll.parse::<u32>() {
+                Ok(value) => u8::try_from(value).map_err(|_| errors::ParseError::BadInt(value)),
+                Err(_) => Err(errors::ParseError::Malformed(roll.to_string())),
+            })
+            .collect::<Result<_, _>>()
+            .map(EntropySource::Dice)
+    }
+
+    /// Returns the byte which identifies the kind of source in a mixed preimage.
+    fn tag(&self) -> u8 {
+        match self {
+            EntropySource::Dice(_) => 1,
+            EntropySource::Deck(_) => 2,
+            EntropySource::Bytes(_) => 3,
+        }
+    }
+
+    /// Returns the bytes of the source as they appear in a mixed preimage.
+    fn encode(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(match self {
+            EntropySource::Dice(rolls) => rolls.clone(),
+            EntropySource::Deck(deck) => deck.cards.iter().map(|c| c.index() as u8).collect(),
+            EntropySource::Bytes(bytes) => bytes.clone(),
+        })
+    }
+}
+
+/// Builds the preimage hashed by `Deck::hash_mixed`:
+///
+/// ```text
+/// "cardseed/mixed/v1" || u32be(len(deck)) || deck || u32be(count) || sources || u8(has_password) || u32be(len(password)) || password
+/// ```
+///
+/// where `deck` is the packed binary deck, and each source is encoded as
+/// `u8(kind) || u32be(len(bytes)) || bytes`.
+fn mixed_preimage(
+    deck: &Deck,
+    sources: &[EntropySource],
+    password: Option<&[u8]>,
+) -> PreimageBuffer {
+    let encoded: Vec<_> = sources.iter().map(|s| (s.tag(), s.encode())).collect();
+    let sources_len: usize = encoded.iter().map(|(_, bytes)| 5 + bytes.len()).sum();
+    let mut preimage = PreimageBuffer::with_capacity(
+        MIXED_TAG.len() + 13 + deck.cards.len() + sources_len + password.map_or(0, <[u8]>::len),
+    );
+    preimage.extend_from_slice(MIXED_TAG);
+    preimage.extend_from_slice(&(deck.cards.len() as u32).to_be_bytes());
+    for card in &deck.cards {
+        preimage.extend_from_slice(&[card.index() as u8]);
+    }
+    preimage.extend_from_slice(&(encoded.len() as u32).to_be_bytes());
+    for (tag, bytes) in &encoded {
+        preimage.extend_from_slice(&[*tag]);
+        preimage.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+        preimage.extend_from_slice(bytes);
+    }
+    frame_password(&mut preimage, password);
+    preimage
+}
+
+impl Preimage {
+    /// Builds the preimage bytes for the given deck and password. The deck is
+    /// encoded directly into the preimage, which is held on the stack unless it is
+    /// too long, and zeroed when dropped.
+    fn build(&self, deck: &Deck, password: Option<&[u8]>) -> PreimageBuffer {
+        let password_len = password.map_or(0, <[u8]>::len);
+        match self {
+            Preimage::Legacy => {
+                let deck_len = deck_string_len(deck);
+                let mut preimage =
+                    PreimageBuffer::with_capacity(deck_len + password.map_or(0, |p| p.len() + 1));
+                write!(preimage, "{deck}").expect("the preimage buffer fits the deck");
+                if let Some(password) = password {
+                    preimage.extend_from_slice(b":");
+                    preimage.extend_from_slice(password);
+                }
+                preimage
+            }
+            Preimage::Framed => {
+                let deck_len = deck_string_len(deck);
+                let mut preimage =
+                    PreimageBuffer::with_capacity(FRAMED_TAG.len() + 9 + deck_len + password_len);
+                preimage.extend_from_slice(FRAMED_TAG);
+                preimage.extend_from_slice(&(deck_len as u32).to_be_bytes());
+                write!(preimage, "{deck}").expect("the preimage buffer fits the deck");
+                frame_password(&mut preimage, password);
+                preimage
+            }
+            Preimage::Binary => {
+                let deck_len = deck.cards.len();
+                let mut preimage =
+                    PreimageBuffer::with_capacity(BINARY_TAG.len() + 9 + deck_len + password_len);
+                preimage.extend_from_slice(BINARY_TAG);
+                preimage.extend_from_slice(&(deck_len as u32).to_be_bytes());
+                for card in &deck.cards {
+                    preimage.extend_from_slice(&[card.index() as u8]);
+                }
+                frame_password(&mut preimage, password);
+                preimage
+            }
+            Preimage::Unordered => {
+                let mut indexes: Zeroizing<Vec<u8>> =
+                    Zeroizing::new(deck.cards.iter().map(|c| c.index() as u8).collect());
+                indexes.sort_unstable();
+                let mut preimage = PreimageBuffer::with_capacity(
+                    UNORDERED_TAG.len() + 9 + indexes.len() + password_len,
+                );
+                preimage.extend_from_slice(UNORDERED_TAG);
+                preimage.extend_from_slice(&(indexes.len() as u32).to_be_bytes());
+                preimage.extend_from_slice(&indexes);
+                frame_password(&mut preimage, password);
+                preimage
+            }
+        }
+    }
+}
+
+/// Returns the length of the formatted deck string: two characters per card, and
+/// a space between each.
+fn deck_string_len(deck: &Deck) -> usize {
+    (deck.cards.len() * 3).saturating_sub(1)
+}
+
+/// Appends the framed password to a domain-separated preimage: whether a password
+/// is present, followed by its length and the password itself.
+fn frame_password(preimage: &mut PreimageBuffer, password: Option<&[u8]>) {
+    preimage.extend_from_slice(&[password.is_some() as u8]);
+    let password = password.unwrap_or_default();
+    preimage.extend_from_slice(&(password.len() as u32).to_be_bytes());
+    preimage.extend_from_slice(password);
+}
+
+/// A preimage under construction. Preimages which fit in `PREIMAGE_BUFFER_SIZE`
+/// bytes are built on the stack, so hashing a deck doesn't allocate. Longer ones are
+/// built in a heap buffer allocated up front, so no partial copies are left behind
+/// in reallocated memory. Either way the buffer is zeroed when dropped.
+///
+/// The stack variant is deliberately large, since avoiding boxing it is the point.
+#[allow(clippy::large_enum_variant)]
+enum PreimageBuffer {
+    Stack {
+        bytes: Zeroizing<[u8; PREIMAGE_BUFFER_SIZE]>,
+        len: usize,
+    },
+    Heap(Zeroizing<Vec<u8>>),
+}
+
+impl PreimageBuffer {
+    /// Creates an empty buffer which can hold `capacity` bytes without reallocating.
+    fn with_capacity(capacity: usize) -> PreimageBuffer {
+        if capacity <= PREIMAGE_BUFFER_SIZE {
+            PreimageBuffer::Stack {
+                bytes: Zeroizing::new([0; PREIMAGE_BUFFER_SIZE]),
+                len: 0,
+            }
+        } else {
+            PreimageBuffer::Heap(Zeroizing::new(Vec::with_capacity(capacity)))
+        }
+    }
+
+    /// Appends bytes to the buffer. Panics if a stack buffer would overflow.
+    fn extend_from_slice(&mut self, data: &[u8]) {
+        match self {
+            PreimageBuffer::Stack { bytes, len } => {
+                bytes[*len..*len + data.len()].copy_from_slice(data);
+                *len += data.len();
+            }
+            PreimageBuffer::Heap(vec) => vec.extend_from_slice(data),
+        }
+    }
+}
+
+impl std::ops::Deref for PreimageBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            PreimageBuffer::Stack { bytes, len } => &bytes[..*len],
+            PreimageBuffer::Heap(vec) => vec,
+        }
+    }
+}
+
+impl fmt::Write for PreimageBuffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+impl Deck {
+    /// Computes a deterministic hash of the `Deck` and an optional password using
+    /// PBKDF2-SHA256, building the preimage as selected by `options`.
+    ///
+    /// The preimage, and the normalized password if `options.normalization` is set,
+    /// are built in buffers which are zeroed before they are freed.
+    ///
+    /// ```
+    /// use cardseed::{Deck, HashOptions, Preimage};
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let options = HashOptions {
+    ///     preimage: Preimage::Framed,
+    ///     ..HashOptions::default()
+    /// };
+    /// let secret = deck.hash_with(Some("bestpasswordever"), &options);
+    /// assert_ne!(secret, deck.hash(Some("bestpasswordever")));
+    /// ```
+    pub fn hash_with(&self, password: Option<&str>, options: &HashOptions) -> [u8; 32] {
+        self.hash_with_progress(password, options, |_, _| {})
+    }
+
+    /// Computes a deterministic hash of the `Deck` and an optional password, like
+    /// `Deck::hash_with`, while reporting progress. After every `PROGRESS_INTERVAL`
+    /// iterations of PBKDF2, and once more when finished, `progress` is called with
+    /// the number of iterations completed and the total number of iterations.
+    ///
+    /// ```
+    /// use cardseed::{Deck, HashOptions};
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let options = HashOptions::default();
+    /// let secret = deck.hash_with_progress(None, &options, |done, total| {
+    ///     eprint!("\r{}%", done as u64 * 100 / total as u64);
+    /// });
+    /// assert_eq!(secret, deck.hash(None));
+    /// ```
+    pub fn hash_with_progress(
+        &self,
+        password: Option<&str>,
+        options: &HashOptions,
+        mut progress: impl FnMut(u32, u32),
+    ) -> [u8; 32] {
+        with_normalized(password, options.normalization, |password| {
+            self.stretch(password, options, &mut progress)
+        })
+    }
+
+    /// Starts a derivation like `Deck::hash_with` which can be paused and resumed
+    /// later, computing only its first iteration. Continue it with `Deck::hash_resume`.
+    ///
+    /// ```
+    /// use cardseed::{Deck, HashOptions, HashState};
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let options = HashOptions::default();
+    ///
+    /// let mut state = deck.hash_start(Some("pw"), &options);
+    /// deck.hash_resume(Some("pw"), &options, &mut state, 10_000);
+    ///
+    /// // Store the state, and resume where it left off later.
+    /// let saved = state.to_bytes();
+    /// let mut state = HashState::from_bytes(&saved[..]).unwrap();
+    /// let secret = deck.hash_resume(Some("pw"), &options, &mut state, u32::MAX);
+    /// assert_eq!(secret, Some(deck.hash(Some("pw"))));
+    /// ```
+    pub fn hash_start(&self, password: Option<&str>, options: &HashOptions) -> HashState {
+        let prf = self.keyed_prf(password, options);
+        HashState::new(&prf, &options.salt, options.iterations)
+    }
+
+    /// Advances a derivation started by `Deck::hash_start` by up to `steps` more
+    /// iterations. The `Deck`, password, and options must be the same as those the
+    /// derivation was started with. Returns the derived secret once every
+    /// iteration is complete, or `None` if the derivation must be resumed again.
+    pub fn hash_resume(
+        &self,
+        password: Option<&str>,
+        options: &HashOptions,
+        state: &mut HashState,
+        steps: u32,
+    ) -> Option<[u8; 32]> {
+        let prf = self.keyed_prf(password, options);
+        state.advance(&prf, steps, &mut |_, _| {});
+        state.output()
+    }
+
+    /// Builds the preimage for the `Deck` and a password, and returns the HMAC
+    /// keyed with it which PBKDF2 uses as its PRF.
+    fn keyed_prf(&self, password: Option<&str>, options: &HashOptions) -> Hmac<Sha256> {
+        with_normalized(password, options.normalization, |password| {
+            let preimage = options.preimage.build(self, password);
+            Hmac::<Sha256>::new_from_slice(&preimage).expect("HMAC accepts keys of any length")
+        })
+    }
+
+    /// Computes a deterministic hash of the `Deck` and an optional password, like
+    /// `Deck::hash_with`, but accepts passwords which are not valid UTF-8. The
+    /// password bytes are hashed as given, regardless of `options.normalization`.
+    ///
+    /// ```
+    /// use cardseed::{Deck, HashOptions, Preimage};
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let options = HashOptions {
+    ///     preimage: Preimage::Binary,
+    ///     ..HashOptions::default()
+    /// };
+    /// let secret = deck.hash_bytes(Some(&[0xff, 0x00, 0xfe]), &options);
+    /// assert_eq!(deck.hash_bytes(Some(b"pw"), &options), deck.hash_with(Some("pw"), &options));
+    /// ```
+    pub fn hash_bytes(&self, password: Option<&[u8]>, options: &HashOptions) -> [u8; 32] {
+        self.stretch(password, options, &mut |_, _| {})
+    }
+
+    /// Builds the preimage for the `Deck` and password, and stretches it with PBKDF2.
+    fn stretch(
+        &self,
+        password: Option<&[u8]>,
+        options: &HashOptions,
+        progress: &mut dyn FnMut(u32, u32),
+    ) -> [u8; 32] {
+        let preimage = options.preimage.build(self, password);
+        pbkdf2_sha256(&preimage, &options.salt, options.iterations, progress)
+    }
+
+    /// Computes a hash of the `Deck` and an optional password with `options`, replacing
+    /// `options.salt` with `SALT_SIZE` freshly generated random bytes. Returns the hash
+    /// bundled with the options used. Use `SaltedHash::verify` to check a deck against
+    /// the result later.
+    #[cfg(feature = "rand")]
+    pub fn hash_salted(&self, password: Option<&str>, options: &HashOptions) -> SaltedHash {
+        self.hash_salted_with_rng(password, options, &mut rand::rngs::OsRng)
+    }
+
+    /// Computes a salted hash like `Deck::hash_salted`, generating the salt with the
+    /// given secure random number generator rather than the operating system's.
+    #[cfg(feature = "rand")]
+    pub fn hash_salted_with_rng<R: CryptoRng + RngCore + ?Sized>(
+        &self,
+        password: Option<&str>,
+        options: &HashOptions,
+        rng: &mut R,
+    ) -> SaltedHash {
+        let mut salt = [0u8; SALT_SIZE];
+        rng.fill_bytes(&mut salt);
+
+        let options = HashOptions {
+            salt: salt.to_vec(),
+            ..options.clone()
+        };
+        SaltedHash {
+            hash: self.hash_with(password, &options),
+            options,
+        }
+    }
+
+    /// Hashes many decks with the same password and options, like calling
+    /// `Deck::hash_with` on each, but stretching them concurrently across all
+    /// available threads. Returns the hashes in the same order as `decks`.
+    ///
+    /// This suits recovery tools which test many candidate orderings against a
+    /// known output.
+    ///
+    /// ```
+    /// use cardseed::{Deck, HashOptions};
+    ///
+    /// let decks = vec![Deck::new(), "AS 3H KC 3C".parse::<Deck>().unwrap()];
+    /// let hashes = Deck::hash_batch(&decks, None, &HashOptions::default());
+    /// assert_eq!(hashes[1], decks[1].hash(None));
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn hash_batch(
+        decks: &[Deck],
+        password: Option<&str>,
+        options: &HashOptions,
+    ) -> Vec<[u8; 32]> {
+        use rayon::prelude::*;
+
+        decks
+            .par_iter()
+            .map(|deck| deck.hash_with(password, options))
+            .collect()
+    }
+
+    /// Derives one of many independent 32-byte secrets from the `Deck` and an optional
+    /// password, identified by a `purpose` label and an `index`. This lets one deck back
+    /// several keys, such as a password manager master key and an SSH key, without
+    /// reusing any secret between them.
+    ///
+    /// The output of `Deck::hash` is expanded with HKDF-SHA256, using the salt
+    /// `"cardseed/indexed"` and the info `u32be(len(purpose)) || purpose || u32be(index)`.
+    ///
+    /// ```
+    /// use cardseed::Deck;
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let ssh = deck.derive_indexed(None, "ssh", 0);
+    /// assert_ne!(ssh, deck.derive_indexed(None, "ssh", 1));
+    /// assert_ne!(ssh, deck.derive_indexed(None, "password-manager", 0));
+    /// ```
+    pub fn derive_indexed(&self, password: Option<&str>, purpose: &str, index: u32) -> [u8; 32] {
+        let master = Zeroizing::new(self.hash(password));
+
+        let mut info = Vec::with_capacity(8 + purpose.len());
+        info.extend_from_slice(&(purpose.len() as u32).to_be_bytes());
+        info.extend_from_slice(purpose.as_bytes());
+        info.extend_from_slice(&index.to_be_bytes());
+        hkdf_sha256(INDEXED_SALT, master.as_slice(), &info)
+    }
+
+    /// Derives a decoy secret from the `Deck` and a duress password, for a
+    /// plausible-deniability setup in which a small decoy wallet is kept alongside
+    /// the real one and the duress password is surrendered under coercion.
+    ///
+    /// The output of `Deck::hash` with the duress password is expanded with
+    /// HKDF-SHA256, using the salt `"cardseed/decoy"` and an empty info. The decoy is
+    /// therefore never equal to any secret which `Deck::hash` or `Deck::derive_indexed`
+    /// derives, even from the same password, and reveals nothing about the secret
+    /// derived from the real password, which is stretched separately.
+    ///
+    /// The scheme is public, so a decoy does not hide that a decoy may exist. It
+    /// only hides whether the real password exists and what it derives.
+    ///
+    /// ```
+    /// use cardseed::Deck;
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let decoy = deck.hash_decoy("under duress");
+    /// assert_ne!(decoy, deck.hash(Some("under duress")));
+    /// assert_ne!(decoy, deck.hash(Some("real password")));
+    /// ```
+    pub fn hash_decoy(&self, duress_password: &str) -> [u8; 32] {
+        let stretched = Zeroizing::new(self.hash(Some(duress_password)));
+        hkdf_sha256(DECOY_SALT, stretched.as_slice(), &[])
+    }
+
+    /// Computes a deterministic hash of the `Deck`, additional sources of entropy,
+    /// and an optional password, for those who would rather not trust any single
+    /// source. The secret is as strong as the strongest source, provided the
+    /// others aren't chosen by an attacker with knowledge of it.
+    ///
+    /// The sources are combined, in order, into a versioned, domain-separated
+    /// preimage which is stretched with `PBKDF2_ITERATIONS` of PBKDF2-SHA256:
+    ///
+    /// ```text
+    /// "cardseed/mixed/v1" || u32be(len(deck)) || deck || u32be(count) || sources || u8(has_password) || u32be(len(password)) || password
+    /// ```
+    ///
+    /// where `deck` is the packed binary deck, and each source is encoded as a kind
+    /// byte (1 for dice, 2 for a deck, 3 for bytes) followed by its length as a
+    /// `u32be` and its contents: one byte for the value of each die roll, the packed
+    /// binary deck, or the bytes as given.
+    ///
+    /// ```
+    /// use cardseed::{Deck, EntropySource};
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let sources = [
+    ///     EntropySource::dice("12 3 20 7").unwrap(),
+    ///     EntropySource::Deck("7D 2C".parse().unwrap()),
+    /// ];
+    /// let secret = deck.hash_mixed(Some("pw"), &sources);
+    /// assert_ne!(secret, deck.hash(Some("pw")));
+    /// // Each roll is encoded on its own, so regrouping the digits changes the secret.
+    /// assert_ne!(secret, deck.hash_mixed(Some("pw"), &[
+    ///     EntropySource::dice("1 23 20 7").unwrap(),
+    ///     EntropySource::Deck("7D 2C".parse().unwrap()),
+    /// ]));
+    /// ```
+    pub fn hash_mixed(&self, password: Option<&str>, sources: &[EntropySource]) -> [u8; 32] {
+        let preimage = mixed_preimage(self, sources, password.map(str::as_bytes));
+        pbkdf2_sha256(&preimage, &[], PBKDF2_ITERATIONS, &mut |_, _| {})
+    }
+
+    /// Shuffles the `Deck` with a key derived from both the OS RNG and physical
+    /// randomness entered by the user, such as dice rolls or the positions at which
+    /// the deck was cut, so that the result is unpredictable unless the RNG is
+    /// backdoored and the physical sources are guessable. Returns an `Err` if the OS
+    /// RNG fails.
+    ///
+    /// `OS_RANDOM_SIZE` bytes are drawn from the RNG and combined with the sources,
+    /// encoded as in `Deck::hash_mixed`, using HKDF-SHA256 with the salt
+    /// `"cardseed/hybrid-shuffle"`:
+    ///
+    /// ```text
+    /// os_random || u32be(count) || sources
+    /// ```
+    ///
+    /// The derived key drives a ChaCha20 stream which shuffles the `Deck`. Neither
+    /// the OS bytes nor the key are kept, so the sources needn't be recorded.
+    ///
+    /// ```
+    /// use cardseed::{Deck, EntropySource};
+    ///
+    /// let rolls = EntropySource::dice("3 6 1 2 5 4 4 1 6 2").unwrap();
+    /// let deck = Deck::new().shuffle_hybrid(&[rolls]).unwrap();
+    /// assert_eq!(deck.validate(), Ok(()));
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn shuffle_hybrid(&self, sources: &[EntropySource]) -> Result<Deck, errors::RngError> {
+        self.shuffle_hybrid_with_rng(sources, &mut rand::rngs::OsRng)
+    }
+
+    /// Shuffles the `Deck` like `Deck::shuffle_hybrid`, drawing the random bytes from
+    /// the given secure RNG instead of the OS.
+    #[cfg(feature = "rand")]
+    pub fn shuffle_hybrid_with_rng<R: CryptoRng + RngCore + ?Sized>(
+        &self,
+        sources: &[EntropySource],
+        rng: &mut R,
+    ) -> Result<Deck, errors::RngError> {
+        use rand::SeedableRng;
+
+        let encoded: Vec<_> = sources.iter().map(|s| (s.tag(), s.encode())).collect();
+        let sources_len: usize = encoded.iter().map(|(_, bytes)| 5 + bytes.len()).sum();
+        let mut ikm = Zeroizing::new(Vec::with_capacity(OS_RANDOM_SIZE + 4 + sources_len));
+        ikm.resize(OS_RANDOM_SIZE, 0);
+        rng.try_fill_bytes(&mut ikm)?;
+        ikm.extend_from_slice(&(encoded.len() as u32).to_be_bytes());
+        for (tag, bytes) in &encoded {
+            ikm.push(*tag);
+            ikm.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            ikm.extend_from_slice(bytes);
+        }
+
+        let key = Zeroizing::new(hkdf_sha256(HYBRID_SALT, &ikm, &[]));
+        self.shuffle_with_rng(&mut rand_chacha::ChaCha20Rng::from_seed(*key))
+    }
+
+    /// Computes a hash of the `Deck` and an optional password like `Deck::hash`, and
+    /// then authenticates it with HMAC-SHA256 under a `key` held by the caller, such as
+    /// one stored on a hardware device. Deriving the result requires both the physical
+    /// deck and the key, so neither alone is enough to recover the secret.
+    ///
+    /// ```
+    /// use cardseed::Deck;
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let secret = deck.hash_keyed(b"device key", Some("pw"));
+    /// assert_ne!(secret, deck.hash(Some("pw")));
+    /// assert_ne!(secret, deck.hash_keyed(b"other key", Some("pw")));
+    /// ```
+    pub fn hash_keyed(&self, key: &[u8], password: Option<&str>) -> [u8; 32] {
+        let stretched = Zeroizing::new(self.hash(password));
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(stretched.as_slice());
+        mac.finalize().into_bytes().into()
+    }
+
+    /// Returns true if the `Deck` and password hash to `expected` under the default
+    /// `HashOptions`. The comparison runs in constant time, so a verifier does not
+    /// leak how much of the expected hash matched.
+    ///
+    /// ```
+    /// use cardseed::Deck;
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let expected = deck.hash(Some("hunter2"));
+    /// assert!(deck.verify(Some("hunter2"), &expected));
+    /// assert!(!deck.verify(Some("hunter3"), &expected));
+    /// ```
+    pub fn verify(&self, password: Option<&str>, expected: &[u8; 32]) -> bool {
+        let hash = Zeroizing::new(self.hash(password));
+        bool::from(hash.ct_eq(expected))
+    }
+
+    /// Computes a deterministic hash of the `Deck` and an optional password using the
+    /// derivation of the given `HashVersion`. `HashVersion::V1` is equivalent to
+    /// `Deck::hash`.
+    ///
+    /// ```
+    /// use cardseed::{Deck, HashVersion};
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// assert_eq!(deck.hash_versioned(None, HashVersion::V1), deck.hash(None));
+    /// assert_ne!(deck.hash_versioned(None, HashVersion::V2), deck.hash(None));
+    /// ```
+    pub fn hash_versioned(&self, password: Option<&str>, version: HashVersion) -> [u8; 32] {
+        self.hash_with(password, &version.options())
+    }
+
+    /// Returns true if the `Deck` and password hash to `expected` under the given
+    /// `HashVersion`, comparing in constant time like `Deck::verify`.
+    pub fn verify_versioned(
+        &self,
+        password: Option<&str>,
+        version: HashVersion,
+        expected: &[u8; 32],
+    ) -> bool {
+        let hash = Zeroizing::new(self.hash_versioned(password, version));
+        bool::from(hash.ct_eq(expected))
+    }
+}
+
+/// Calls `f` with the password normalized as selected, building any normalized
+/// copy in a buffer which is zeroed before it is freed.
+fn with_normalized<T>(
+    password: Option<&str>,
+    normalization: Normalization,
+    f: impl FnOnce(Option<&[u8]>) -> T,
+) -> T {
+    match (password, normalization) {
+        (Some(password), Normalization::Nfkd) => {
+            let len = password.nfkd().map(char::len_utf8).sum();
+            let mut normalized = Zeroizing::new(String::with_capacity(len));
+            normalized.extend(password.nfkd());
+            f(Some(normalized.as_bytes()))
+        }
+        _ => f(password.map(str::as_bytes)),
+    }
+}
+
+/// Computes the first 32 bytes of HKDF-SHA256 output, as specified by
+/// [RFC 5869](https://www.rfc-editor.org/rfc/rfc5869).
+fn hkdf_sha256(salt: &[u8], ikm: &[u8], info: &[u8]) -> [u8; 32] {
+    let mut extract =
+        Hmac::<Sha256>::new_from_slice(salt).expect("HMAC accepts keys of any length");
+    extract.update(ikm);
+    let prk = Zeroizing::new(<[u8; 32]>::from(extract.finalize().into_bytes()));
+
+    let mut expand =
+        Hmac::<Sha256>::new_from_slice(prk.as_slice()).expect("HMAC accepts keys of any length");
+    expand.update(info);
+    expand.update(&[1]);
+    expand.finalize().into_bytes().into()
+}
+
+/// Computes a single 32-byte block of PBKDF2-HMAC-SHA256, calling `progress`
+/// after every `PROGRESS_INTERVAL` iterations and once when finished. An
+/// iteration count of zero is treated as one.
+pub(crate) fn pbkdf2_sha256(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    progress: &mut dyn FnMut(u32, u32),
+) -> [u8; 32] {
+    let prf = Hmac::<Sha256>::new_from_slice(password).expect("HMAC accepts keys of any length");
+    let mut state = HashState::new(&prf, salt, iterations);
+    state.advance(&prf, u32::MAX, progress);
+    progress(state.iterations, state.iterations);
+    state.output
+}
+
+/// The serialized size of a `HashState`.
+const HASH_STATE_SIZE: usize = 72;
+
+/// A PBKDF2 derivation which can be paused and resumed, for instance by a phone
+/// app which derives a secret with a very high iteration count over several
+/// sessions. Create one with `Deck::hash_start`, advance it with `Deck::hash_resume`,
+/// and store it between sessions with `HashState::to_bytes`.
+///
+/// The state holds the most recent PBKDF2 block and the running output, but not
+/// the deck or password, which must be given again to resume. It is zeroed when
+/// dropped. Resuming with a different deck, password, or options than the
+/// derivation was started with produces a meaningless result.
+#[derive(Clone, Eq, PartialEq)]
+pub struct HashState {
+    completed: u32,
+    iterations: u32,
+    block: [u8; 32],
+    output: [u8; 32],
+}
+
+impl HashState {
+    /// Computes the first iteration of PBKDF2 with the keyed PRF.
+    fn new(prf: &Hmac<Sha256>, salt: &[u8], iterations: u32) -> HashState {
+        let mut mac = prf.clone();
+        mac.update(salt);
+        mac.update(&1u32.to_be_bytes());
+        let block: [u8; 32] = mac.finalize().into_bytes().into();
+        HashState {
+            completed: 1,
+            iterations: iterations.max(1),
+            block,
+            output: block,
+        }
+    }
+
+    /// Computes up to `steps` more iterations, calling `progress` after every
+    /// `PROGRESS_INTERVAL` iterations, except at the last.
+    fn advance(&mut self, prf: &Hmac<Sha256>, steps: u32, progress: &mut dyn FnMut(u32, u32)) {
+        let end = self.completed.saturating_add(steps).min(self.iterations);
+        while self.completed < end {
+            let mut mac = prf.clone();
+            mac.update(&self.block);
+            self.block = mac.finalize().into_bytes().into();
+            for (o, b) in self.output.iter_mut().zip(self.block) {
+                *o ^= b;
+            }
+            self.completed += 1;
+            // `is_multiple_of` would need Rust 1.87.
+            #[allow(clippy::manual_is_multiple_of)]
+            if self.completed % PROGRESS_INTERVAL == 0 && self.completed != self.iterations {
+                progress(self.completed, self.iterations);
+            }
+        }
+    }
+
+    /// Returns the number of iterations completed so far.
+    pub fn completed(&self) -> u32 {
+        self.completed
+    }
+
+    /// Returns the total number of iterations of the derivation.
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    /// Returns the derived secret, or `None` if the derivation isn't finished.
+    pub fn output(&self) -> Option<[u8; 32]> {
+        (self.completed == self.iterations).then_some(self.output)
+    }
+
+    /// Serializes the state as the big-endian completed and total iteration counts,
+    /// followed by the current block and the running output. The bytes are as
+    /// sensitive as the secret being derived.
+    pub fn to_bytes(&self) -> Zeroizing<[u8; HASH_STATE_SIZE]> {
+        let mut bytes = Zeroizing::new([0u8; HASH_STATE_SIZE]);
+        bytes[..4].copy_from_slice(&self.completed.to_be_bytes());
+        bytes[4..8].copy_from_slice(&self.iterations.to_be_bytes());
+        bytes[8..40].copy_from_slice(&self.block);
+        bytes[40..].copy_from_slice(&self.output);
+        bytes
+    }
+
+    /// Parses a state serialized by `HashState::to_bytes`. Returns an `Err` if the
+    /// bytes are the wrong length or the iteration counts are inconsistent.
+    pub fn from_bytes(bytes: &[u8]) -> Result<HashState, errors::ParseError> {
+        let malformed = || errors::ParseError::Malformed(String::from("hash state"));
+        let bytes: &[u8; HASH_STATE_SIZE] = bytes.try_into().map_err(|_| malformed())?;
+
+        let count =
+            |i: usize| u32::from_be_bytes(bytes[i..i + 4].try_into().expect("slice is 4 bytes"));
+        let (completed, iterations) = (count(0), count(4));
+        if completed == 0 || completed > iterations {
+            return Err(malformed());
+        }
+        let mut state = HashState {
+            completed,
+            iterations,
+            block: [0; 32],
+            output: [0; 32],
+        };
+        state.block.copy_from_slice(&bytes[8..40]);
+        state.output.copy_from_slice(&bytes[40..]);
+        Ok(state)
+    }
+}
+
+impl fmt::Debug for HashState {
+    /// Formats the iteration counts, but not the secret state.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HashState")
+            .field("completed", &self.completed)
+            .field("iterations", &self.iterations)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for HashState {
+    fn drop(&mut self) {
+        self.block.zeroize();
+        self.output.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_matches_hash() {
+        let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+        let options = HashOptions::default();
+        assert_eq!(options.preimage, Preimage::Legacy);
+        assert_eq!(deck.hash_with(None, &options), deck.hash(None));
+        assert_eq!(
+            deck.hash_with(Some("bestpasswordever"), &options),
+            [
+                35, 20, 205, 7, 35, 104, 123, 150, 57, 148, 101, 109, 151, 0, 87, 15, 103, 14, 67,
+                214, 165, 165, 44, 218, 5, 232, 30, 26, 100, 90, 169, 244
+            ]
+        );
+    }
+
+    #[test]
+    fn framed_preimage() {
+        let deck = "AS 3H".parse::<Deck>().unwrap();
+        let mut expected = b"cardseed/framed\x00\x00\x00\x05AS 3H\x01\x00\x00\x00\x02pw".to_vec();
+        assert_eq!(*Preimage::Framed.build(&deck, Some(b"pw")), expected);
+
+        expected = b"cardseed/framed\x00\x00\x00\x05AS 3H\x00\x00\x00\x00\x00".to_vec();
+        assert_eq!(*Preimage::Framed.build(&deck, None), expected);
+        assert_ne!(
+            *Preimage::Framed.build(&deck, None),
+            *Preimage::Framed.build(&deck, Some(b""))
+        );
+    }
+
+    #[test]
+    fn preimage_buffer() {
+        let deck = Deck::new();
+        assert_eq!(deck_string_len(&deck), deck.to_string().len());
+        assert_eq!(deck_string_len(&Deck { cards: vec![] }), 0);
+
+        // A full deck with a typical password is built on the stack.
+        let password = [b'x'; 64];
+        let preimage = Preimage::Framed.build(&deck, Some(&password));
+        assert!(matches!(preimage, PreimageBuffer::Stack { .. }));
+        assert!(preimage.ends_with(&password));
+
+        // Longer passwords spill to the heap without reallocating.
+        let password = [b'x'; PREIMAGE_BUFFER_SIZE];
+        for preimage in [
+            Preimage::Legacy,
+            Preimage::Framed,
+            Preimage::Binary,
+            Preimage::Unordered,
+        ] {
+            let built = preimage.build(&deck, Some(&password));
+            match &built {
+                PreimageBuffer::Heap(vec) => assert_eq!(vec.len(), vec.capacity()),
+                PreimageBuffer::Stack { .. } => panic!("expected a heap buffer"),
+            }
+            assert!(built.ends_with(&password));
+        }
+        assert_eq!(
+            *Preimage::Legacy.build(&deck, Some(b"pw")),
+            *format!("{deck}:pw").as_bytes()
+        );
+    }
+
+    #[test]
+    fn binary_preimage() {
+        let deck = "AS 3H KD".parse::<Deck>().unwrap();
+        let expected = b"cardseed/binary\x00\x00\x00\x03\x00\x1c\x33\x01\x00\x00\x00\x02\xff\x00";
+        assert_eq!(
+            *Preimage::Binary.build(&deck, Some(&[0xff, 0x00])),
+            expected.to_vec()
+        );
+
+        let binary = HashOptions {
+            preimage: Preimage::Binary,
+            ..HashOptions::default()
+        };
+        let framed = HashOptions {
+            preimage: Preimage::Framed,
+            ..HashOptions::default()
+        };
+        assert_ne!(
+            deck.hash_with(Some("pw"), &binary),
+            deck.hash_with(Some("pw"), &framed)
+        );
+        assert_ne!(
+            deck.hash_bytes(Some(&[0xff]), &binary),
+            deck.hash_bytes(Some(&[0xfe]), &binary)
+        );
+    }
+
+    #[test]
+    fn unordered_preimage() {
+        let hand = "KD AS 3H".parse::<Deck>().unwrap();
+        let expected = b"cardseed/unordered\x00\x00\x00\x03\x00\x1c\x33\x00\x00\x00\x00\x00";
+        assert_eq!(*Preimage::Unordered.build(&hand, None), expected.to_vec());
+
+        let unordered = HashOptions {
+            preimage: Preimage::Unordered,
+            ..HashOptions::default()
+        };
+        let reordered = "3H KD AS".parse::<Deck>().unwrap();
+        assert_eq!(
+            hand.hash_with(Some("pw"), &unordered),
+            reordered.hash_with(Some("pw"), &unordered)
+        );
+        assert_eq!("unordered".parse::<Preimage>(), Ok(Preimage::Unordered));
+        assert_eq!(unordered.version(), None);
+    }
+
+    #[test]
+    fn cross_version() {
+        let framed = HashOptions {
+            preimage: Preimage::Framed,
+            ..HashOptions::default()
+        };
+        let legacy = HashOptions::default();
+
+        for password in [
+            None,
+            Some(""),
+            Some("slick"),
+            Some("with spaces: and colons"),
+        ] {
+            let deck = Deck::new();
+            assert_ne!(
+                deck.hash_with(password, &framed),
+                deck.hash_with(password, &legacy)
+            );
+        }
+
+        assert_eq!(
+            Deck::new().hash_with(Some("slick"), &framed),
+            [
+                17, 73, 28, 194, 28, 232, 128, 176, 97, 252, 255, 29, 146, 29, 34, 255, 130, 187,
+                82, 166, 234, 195, 141, 204, 104, 202, 179, 64, 238, 135, 103, 14
+            ]
+        );
+    }
+
+    #[test]
+    fn normalization() {
+        let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+        let composed = "caf\u{e9}";
+        let decomposed = "cafe\u{301}";
+
+        let legacy = HashOptions::default();
+        assert_ne!(
+            deck.hash_with(Some(composed), &legacy),
+            deck.hash_with(Some(decomposed), &legacy)
+        );
+
+        let nfkd = HashOptions {
+            normalization: Normalization::Nfkd,
+            ..HashOptions::default()
+        };
+        assert_eq!(
+            deck.hash_with(Some(composed), &nfkd),
+            deck.hash_with(Some(decomposed), &nfkd)
+        );
+        assert_eq!(
+            deck.hash_with(Some(decomposed), &nfkd),
+            deck.hash_with(Some(decomposed), &legacy)
+        );
+        assert_eq!(deck.hash_with(None, &nfkd), deck.hash(None));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn salted() {
+        let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+        let framed = HashOptions {
+            preimage: Preimage::Framed,
+            ..HashOptions::default()
+        };
+        let a = deck.hash_salted(Some("pw"), &framed);
+        let b = deck.hash_salted(Some("pw"), &framed);
+        assert_eq!(a.options.salt.len(), SALT_SIZE);
+
+        use rand::SeedableRng;
+        let seeded = || rand_chacha::ChaCha20Rng::seed_from_u64(9);
+        let c = deck.hash_salted_with_rng(Some("pw"), &framed, &mut seeded());
+        assert_eq!(
+            c,
+            deck.hash_salted_with_rng(Some("pw"), &framed, &mut seeded())
+        );
+        assert!(c.verify(&deck, Some("pw")));
+        let EntropySource::Bytes(bytes) = EntropySource::from_rng(&mut seeded()) else {
+            panic!("expected bytes");
+        };
+        assert_eq!(bytes.len(), OS_RANDOM_SIZE);
+        assert_eq!(bytes[..SALT_SIZE], c.options.salt[..]);
+        assert_ne!(a.options.salt, b.options.salt);
+        assert_ne!(a.hash, b.hash);
+        assert!(a.verify(&deck, Some("pw")));
+        assert!(!a.verify(&deck, None));
+        assert!(!a.verify(&Deck::new(), Some("pw")));
+
+        let legacy = deck.hash_salted(None, &HashOptions::default());
+        assert!(legacy.verify(&deck, None));
+        assert_ne!(legacy.hash, deck.hash(None));
+
+        let fast = HashOptions {
+            iterations: 1000,
+            ..framed
+        };
+        let salted = deck.hash_salted(Some("pw"), &fast);
+        assert!(salted.verify(&deck, Some("pw")));
+        assert_eq!(salted.options.iterations, 1000);
+    }
+
+    #[test]
+    fn pbkdf2_matches() {
+        for (password, salt, iterations) in [
+            (&b"password"[..], &b"salt"[..], 1),
+            (b"password", b"salt", 4096),
+            (b"", b"", 10_000),
+            (&[0xff; 100], &[0xab; SALT_SIZE], 5000),
+        ] {
+            let mut expected = [0u8; 32];
+            pbkdf2::pbkdf2_hmac::<Sha256>(password, salt, iterations, &mut expected);
+            assert_eq!(
+                pbkdf2_sha256(password, salt, iterations, &mut |_, _| {}),
+                expected
+            );
+        }
+        assert_eq!(
+            pbkdf2_sha256(b"pw", b"salt", 0, &mut |_, _| {}),
+            pbkdf2_sha256(b"pw", b"salt", 1, &mut |_, _| {})
+        );
+    }
+
+    #[test]
+    fn progress() {
+        let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+        let options = HashOptions {
+            iterations: PROGRESS_INTERVAL * 3 + 5,
+            ..HashOptions::default()
+        };
+
+        let mut calls = Vec::new();
+        let secret = deck.hash_with_progress(Some("pw"), &options, |done, total| {
+            calls.push((done, total));
+        });
+        assert_eq!(secret, deck.hash_with(Some("pw"), &options));
+
+        let total = options.iterations;
+        assert_eq!(
+            calls,
+            vec![
+                (PROGRESS_INTERVAL, total),
+                (PROGRESS_INTERVAL * 2, total),
+                (PROGRESS_INTERVAL * 3, total),
+                (total, total),
+            ]
+        );
+    }
+
+    #[test]
+    fn resume() {
+        let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+        let options = HashOptions {
+            preimage: Preimage::Framed,
+            salt: b"salt".to_vec(),
+            normalization: Normalization::Nfkd,
+            iterations: 1000,
+        };
+        let expected = deck.hash_with(Some("pw"), &options);
+
+        let mut state = deck.hash_start(Some("pw"), &options);
+        assert_eq!((state.completed(), state.iterations()), (1, 1000));
+        assert_eq!(state.output(), None);
+        for _ in 0..9 {
+            assert_eq!(
+                deck.hash_resume(Some("pw"), &options, &mut state, 100),
+                None
+            );
+            state = HashState::from_bytes(&state.to_bytes()[..]).unwrap();
+        }
+        assert_eq!(state.completed(), 901);
+        assert_eq!(
+            deck.hash_resume(Some("pw"), &options, &mut state, 100),
+            Some(expected)
+        );
+        assert_eq!(
+            deck.hash_resume(Some("pw"), &options, &mut state, 100),
+            Some(expected)
+        );
+        assert!(!format!("{state:?}").contains("output"));
+
+        let single = HashOptions {
+            iterations: 0,
+            ..HashOptions::default()
+        };
+        let state = deck.hash_start(None, &single);
+        assert_eq!(state.output(), Some(deck.hash_with(None, &single)));
+
+        let mut bytes = state.to_bytes();
+        bytes[3] = 2;
+        assert!(HashState::from_bytes(&bytes[..]).is_err());
+        assert!(HashState::from_bytes(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn hkdf() {
+        // Test case 1 from RFC 5869, truncated to 32 bytes.
+        let okm = hkdf_sha256(
+            &hex::decode("000102030405060708090a0b0c").unwrap(),
+            &[0x0b; 22],
+            &hex::decode("f0f1f2f3f4f5f6f7f8f9").unwrap(),
+        );
+        assert_eq!(
+            hex::encode(okm),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf"
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn mixed_preimage() {
+        let deck = "AS 3H".parse::<Deck>().unwrap();
+        let sources = [
+            EntropySource::dice("1 2\n13").unwrap(),
+            EntropySource::Deck("KD".parse().unwrap()),
+            EntropySource::Bytes(vec![0xff]),
+        ];
+        let expected = b"cardseed/mixed/v1\x00\x00\x00\x02\x00\x1c\x00\x00\x00\x03\
+            \x01\x00\x00\x00\x03\x01\x02\x0d\x02\x00\x00\x00\x01\x33\x03\x00\x00\x00\x01\xff\
+            \x01\x00\x00\x00\x02pw";
+        assert_eq!(
+            *super::mixed_preimage(&deck, &sources, Some(b"pw")),
+            expected.to_vec()
+        );
+
+        // Each source is framed, so bytes cannot move between neighboring sources.
+        assert_ne!(
+            deck.hash_mixed(
+                None,
+                &[
+                    EntropySource::Bytes(vec![1, 2]),
+                    EntropySource::Bytes(vec![])
+                ]
+            ),
+            deck.hash_mixed(
+                None,
+                &[EntropySource::Bytes(vec![1]), EntropySource::Bytes(vec![2])]
+            )
+        );
+        assert_ne!(deck.hash_mixed(None, &[]), deck.hash_mixed(Some(""), &[]));
+        assert_ne!(
+            deck.hash_mixed(None, &[EntropySource::os_random()]),
+            deck.hash_mixed(None, &[EntropySource::os_random()])
+        );
+    }
+
+    #[test]
+    fn dice() {
+        assert_eq!(
+            EntropySource::dice(" 6\t0 255\n"),
+            Ok(EntropySource::Dice(vec![6, 0, 255]))
+        );
+        assert_eq!(EntropySource::dice(""), Ok(EntropySource::Dice(vec![])));
+        assert_eq!(
+            EntropySource::dice("6 256"),
+            Err(errors::ParseError::BadInt(256))
+        );
+        for roll in ["-1", "six", "3,4", "99999999999"] {
+            assert_eq!(
+                EntropySource::dice(roll),
+                Err(errors::ParseError::Malformed(roll.into()))
+            );
+        }
+    }
+
+    #[test]
+    fn hash_keyed() {
+        let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"key").unwrap();
+        mac.update(&deck.hash(None));
+        let expected: [u8; 32] = mac.finalize().into_bytes().into();
+        assert_eq!(deck.hash_keyed(b"key", None), expected);
+        assert_ne!(
+            deck.hash_keyed(b"key", None),
+            deck.hash_keyed(b"key", Some(""))
+        );
+        assert_ne!(deck.hash_keyed(b"", None), deck.hash(None));
+    }
+
+    #[test]
+    fn verify() {
+        let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+        let mut expected = deck.hash(None);
+        assert!(deck.verify(None, &expected));
+        assert!(!deck.verify(Some(""), &expected));
+        expected[31] ^= 1;
+        assert!(!deck.verify(None, &expected));
+    }
+
+    #[test]
+    fn hash_versions() {
+        let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+        let vectors = [
+            (
+                HashVersion::V1,
+                [
+                    35, 20, 205, 7, 35, 104, 123, 150, 57, 148, 101, 109, 151, 0, 87, 15, 103, 14,
+                    67, 214, 165, 165, 44, 218, 5, 232, 30, 26, 100, 90, 169, 244,
+                ],
+            ),
+            (
+                HashVersion::V2,
+                [
+                    212, 152, 116, 24, 79, 100, 60, 126, 174, 167, 85, 242, 121, 139, 10, 28, 253,
+                    98, 57, 185, 162, 106, 189, 139, 69, 130, 117, 74, 65, 17, 184, 250,
+                ],
+            ),
+        ];
+        for (version, expected) in vectors {
+            assert_eq!(
+                deck.hash_versioned(Some("bestpasswordever"), version),
+                expected,
+                "{version}"
+            );
+            assert!(deck.verify_versioned(Some("bestpasswordever"), version, &expected));
+            assert_eq!(version.to_string().parse::<HashVersion>(), Ok(version));
+            assert_eq!(version.options().version(), Some(version));
+        }
+        assert_eq!(HashVersion::default(), HashVersion::V1);
+
+        // V2 normalizes passwords, so a decomposed "ä" derives the same secret.
+        // Computed independently with Python's unicodedata and hashlib.pbkdf2_hmac.
+        let expected = "129f1f07912f39c2aaa4c8010954e527dababaa5c7f5415d22eeeec5956ebeee";
+        for password in ["pässword", "pa\u{308}ssword"] {
+            let secret = deck.hash_versioned(Some(password), HashVersion::V2);
+            assert_eq!(hex::encode(secret), expected);
+        }
+        let unnormalized = HashOptions {
+            preimage: Preimage::Binary,
+            ..HashOptions::default()
+        };
+        assert_ne!(
+            hex::encode(deck.hash_with(Some("pässword"), &unnormalized)),
+            expected
+        );
+        assert_eq!(unnormalized.version(), None);
+
+        let options = HashOptions {
+            normalization: Normalization::Nfkd,
+            ..HashOptions::default()
+        };
+        assert_eq!(options.version(), None);
+        assert!("v3".parse::<HashVersion>().is_err());
+    }
+
+    #[test]
+    fn derive_indexed() {
+        let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+        let secret = deck.derive_indexed(Some("pw"), "wallet", 7);
+        assert_eq!(secret, deck.derive_indexed(Some("pw"), "wallet", 7));
+        assert_ne!(secret, deck.derive_indexed(None, "wallet", 7));
+        assert_ne!(secret, deck.hash(Some("pw")));
+
+        // The purpose is length-prefixed, so labels cannot bleed into the index.
+        assert_ne!(
+            deck.derive_indexed(None, "a\x00\x00\x00", 1),
+            deck.derive_indexed(None, "a", 0)
+        );
+    }
+
+    #[test]
+    fn hash_decoy() {
+        let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+        let decoy = deck.hash_decoy("duress");
+        assert_eq!(
+            decoy,
+            hkdf_sha256(b"cardseed/decoy", &deck.hash(Some("duress")), b"")
+        );
+        assert_eq!(decoy, deck.hash_decoy("duress"));
+        assert_ne!(decoy, deck.hash(Some("duress")));
+        assert_ne!(decoy, deck.hash_decoy("Duress"));
+        assert_ne!(decoy, Deck::new().hash_decoy("duress"));
+        assert_ne!(decoy, deck.derive_indexed(Some("duress"), "", 0));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn shuffle_hybrid() {
+        use rand::SeedableRng;
+        let seeded = |seed| rand_chacha::ChaCha20Rng::seed_from_u64(seed);
+        let dice = || vec![EntropySource::Dice(vec![3, 6, 1, 2, 5, 4])];
+        let deck = Deck::new();
+
+        let shuffled = deck
+            .shuffle_hybrid_with_rng(&dice(), &mut seeded(1))
+            .unwrap();
+        assert_eq!(shuffled.validate(), Ok(()));
+        assert_eq!(
+            shuffled,
+            deck.shuffle_hybrid_with_rng(&dice(), &mut seeded(1))
+                .unwrap()
+        );
+
+        // Changing either the RNG or the physical sources changes the order.
+        assert_ne!(
+            shuffled,
+            deck.shuffle_hybrid_with_rng(&dice(), &mut seeded(2))
+                .unwrap()
+        );
+        let cut = EntropySource::Bytes(vec![17, 30]);
+        assert_ne!(
+            shuffled,
+            deck.shuffle_hybrid_with_rng(&[cut], &mut seeded(1))
+                .unwrap()
+        );
+        assert_ne!(
+            shuffled,
+            deck.shuffle_hybrid_with_rng(&[], &mut seeded(1)).unwrap()
+        );
+        // The RNG output is hashed rather than used directly.
+        assert_ne!(shuffled, deck.shuffle_with_rng(&mut seeded(1)).unwrap());
+
+        assert_eq!(deck.shuffle_hybrid(&dice()).unwrap().validate(), Ok(()));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn hash_batch() {
+        let options = HashOptions {
+            iterations: 1000,
+            ..HashOptions::default()
+        };
+        let decks: Vec<Deck> = (0..8)
+            .map(|i| {
+                let mut deck = Deck::new();
+                deck.cards.rotate_left(i);
+                deck
+            })
+            .collect();
+        let hashes = Deck::hash_batch(&decks, Some("pw"), &options);
+        assert_eq!(hashes.len(), decks.len());
+        for (deck, hash) in decks.iter().zip(hashes) {
+            assert_eq!(hash, deck.hash_with(Some("pw"), &options));
+        }
+        assert!(Deck::hash_batch(&[], None, &options).is_empty());
+    }
+
+    #[test]
+    fn calibrate() {
+        assert_eq!(HashOptions::calibrate(Duration::ZERO).iterations, 1);
+        assert_eq!(
+            HashOptions::calibrate(Duration::MAX).iterations,
+            MAX_SALTED_ITERATIONS
+        );
+
+        let options = HashOptions::calibrate(Duration::from_millis(20));
+        assert!(options.iterations > 1);
+        assert_eq!(options.preimage, Preimage::Legacy);
+
+        let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+        let fewer = HashOptions {
+            iterations: 1000,
+            ..HashOptions::default()
+        };
+        assert_ne!(deck.hash_with(None, &fewer), deck.hash(None));
+    }
+
+    #[test]
+    fn salted_serialization() {
+        let salted = SaltedHash {
+            options: HashOptions {
+                preimage: Preimage::Framed,
+                salt: vec![0xab; SALT_SIZE],
+                normalization: Normalization::Nfkd,
+                iterations: 1000,
+            },
+            hash: [1; 32],
+        };
+        let s = salted.to_string();
+        assert_eq!(
+            s,
+            "$cardseed$custom$framed$nfkd$1000$abababababababababababababababab$\
+             0101010101010101010101010101010101010101010101010101010101010101"
+        );
+        assert_eq!(s.parse::<SaltedHash>(), Ok(salted));
+
+        let versioned = SaltedHash {
+            options: HashOptions {
+                salt: vec![0xab; SALT_SIZE],
+                iterations: 1000,
+                ..HashVersion::V2.options()
+            },
+            hash: [1; 32],
+        };
+        let s = versioned.to_string();
+        assert!(s.starts_with("$cardseed$v2$binary$nfkd$1000$"));
+        assert_eq!(s.parse::<SaltedHash>(), Ok(versioned));
+
+        let costly = SaltedHash {
+            options: HashOptions {
+                salt: vec![0xab; SALT_SIZE],
+                iterations: MAX_SALTED_ITERATIONS,
+                ..HashOptions::default()
+            },
+            hash: [1; 32],
+        };
+        assert_eq!(costly.to_string().parse::<SaltedHash>(), Ok(costly));
+
+        for bad in [
+            "",
+            "$cardseed$custom$framed$none$1000$ab$01",
+            "$other$custom$framed$none$1000$abababababababababababababababab$01",
+            // The unversioned form is not accepted.
+            "$cardseed$framed$none$1000$abababababababababababababababab$\
+             0101010101010101010101010101010101010101010101010101010101010101",
+            "$cardseed$v9$framed$none$1000$abababababababababababababababab$\
+             0101010101010101010101010101010101010101010101010101010101010101",
+            "$cardseed$custom$framed$nfc$1000$abababababababababababababababab$\
+             0101010101010101010101010101010101010101010101010101010101010101",
+            "$cardseed$custom$framed$none$-1$abababababababababababababababab$\
+             0101010101010101010101010101010101010101010101010101010101010101",
+            // Iteration counts above the limit are rejected.
+            "$cardseed$custom$framed$none$16777217$abababababababababababababababab$\
+             0101010101010101010101010101010101010101010101010101010101010101",
+            "$cardseed$custom$framed$none$4294967295$abababababababababababababababab$\
+             0101010101010101010101010101010101010101010101010101010101010101",
+            // The version must agree with the preimage format and normalization.
+            "$cardseed$v2$binary$none$1000$abababababababababababababababab$\
+             0101010101010101010101010101010101010101010101010101010101010101",
+            "$cardseed$v1$binary$nfkd$1000$abababababababababababababababab$\
+             0101010101010101010101010101010101010101010101010101010101010101",
+            "$cardseed$custom$binary$nfkd$1000$abababababababababababababababab$\
+             0101010101010101010101010101010101010101010101010101010101010101",
+        ] {
+            assert!(bad.parse::<SaltedHash>().is_err());
+        }
+    }
+}
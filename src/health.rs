@@ -0,0 +1,128 @@
+//! Startup health tests for a random number generator, following the repetition
+//! count and adaptive proportion tests of
+//! [NIST SP 800-90B](https://csrc.nist.gov/pubs/sp/800/90/b/final), section 4.4, so
+//! that a broken entropy source is caught before it produces a "random" deck.
+//!
+//! The tests treat each byte of output as a sample with a claimed 8 bits of
+//! min-entropy, as a healthy RNG provides, and use a false positive probability of
+//! `2^-40` for each test.
+//!
+//! ```
+//! use cardseed::{health, Deck};
+//!
+//! health::self_test(&mut rand::rngs::OsRng).unwrap();
+//! let deck = Deck::new().shuffle_checked().unwrap();
+//! assert_eq!(deck.validate(), Ok(()));
+//! ```
+
+use crate::errors;
+use rand::RngCore;
+use zeroize::Zeroizing;
+
+/// The number of samples drawn by `self_test`, as SP 800-90B requires of startup
+/// testing.
+pub const STARTUP_SAMPLES: usize = 1024;
+
+/// The length of a run of identical samples which fails the repetition count test:
+/// `1 + ceil(40 / 8)`.
+pub const REPETITION_CUTOFF: usize = 6;
+
+/// The number of samples in each window of the adaptive proportion test.
+pub const PROPORTION_WINDOW: usize = 512;
+
+/// The number of times the first sample of a window may appear in the window,
+/// counting itself, before failing the adaptive proportion test: one more than the
+/// critical value of the binomial distribution with 512 trials of probability
+/// `2^-8` at `1 - 2^-40`.
+pub const PROPORTION_CUTOFF: usize = 19;
+
+/// Draws `STARTUP_SAMPLES` bytes from `rng` and runs the repetition count and
+/// adaptive proportion tests on them. Returns an `Err` if the RNG fails to produce
+/// bytes or if either test fails. The samples are discarded afterwards.
+///
+/// A healthy RNG passes almost surely, but passing only shows that the RNG is not
+/// grossly broken, such as stuck on one value. It cannot show that the output is
+/// unpredictable.
+pub fn self_test<R: RngCore + ?Sized>(rng: &mut R) -> Result<(), errors::RngError> {
+    let mut samples = Zeroizing::new([0u8; STARTUP_SAMPLES]);
+    rng.try_fill_bytes(samples.as_mut_slice())?;
+    repetition_count(samples.as_slice())?;
+    adaptive_proportion(samples.as_slice())
+}
+
+/// Fails if any sample repeats `REPETITION_CUTOFF` times in a row.
+fn repetition_count(samples: &[u8]) -> Result<(), errors::RngError> {
+    let mut run = 0;
+    let mut previous = None;
+    for &sample in samples {
+        if previous == Some(sample) {
+            run += 1;
+        } else {
+            previous = Some(sample);
+            run = 1;
+        }
+        if run >= REPETITION_CUTOFF {
+            return Err(errors::RngError::RepetitionCount { run });
+        }
+    }
+    Ok(())
+}
+
+/// Fails if the first sample of any window appears `PROPORTION_CUTOFF` or more
+/// times within it.
+fn adaptive_proportion(samples: &[u8]) -> Result<(), errors::RngError> {
+    for window in samples.chunks_exact(PROPORTION_WINDOW) {
+        let count = window.iter().filter(|&&sample| sample == window[0]).count();
+        if count >= PROPORTION_CUTOFF {
+            return Err(errors::RngError::AdaptiveProportion { count });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn self_test() {
+        assert!(super::self_test(&mut rand::rngs::OsRng).is_ok());
+        for seed in 0..100 {
+            let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(seed);
+            assert!(super::self_test(&mut rng).is_ok());
+        }
+
+        let mut stuck = rand::rngs::mock::StepRng::new(0, 0);
+        assert!(matches!(
+            super::self_test(&mut stuck),
+            Err(errors::RngError::RepetitionCount { run: 6 })
+        ));
+    }
+
+    #[test]
+    fn health_tests() {
+        let mut samples = [0u8; STARTUP_SAMPLES];
+        for (i, sample) in samples.iter_mut().enumerate() {
+            *sample = i as u8;
+        }
+        assert!(repetition_count(&samples).is_ok());
+        assert!(adaptive_proportion(&samples).is_ok());
+
+        samples[100..105].fill(7);
+        assert!(repetition_count(&samples).is_ok());
+        samples[105] = 7;
+        assert!(matches!(
+            repetition_count(&samples),
+            Err(errors::RngError::RepetitionCount { run: 6 })
+        ));
+
+        // Cycling through 20 values never repeats, but is far from uniform.
+        let cycle: Vec<u8> = (0..STARTUP_SAMPLES).map(|i| (i % 20) as u8).collect();
+        assert!(repetition_count(&cycle).is_ok());
+        assert!(matches!(
+            adaptive_proportion(&cycle),
+            Err(errors::RngError::AdaptiveProportion { count: 26 })
+        ));
+    }
+}
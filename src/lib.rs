@@ -1,12 +1,119 @@
+#[cfg(feature = "aezeed")]
+mod aez;
+#[cfg(feature = "aezeed")]
+mod aezeed;
+mod analysis;
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(feature = "blackjack")]
+pub mod blackjack;
+#[cfg(feature = "bridge")]
+pub mod bridge;
+#[cfg(feature = "bitcoin")]
+mod btc;
 #[doc = include_str!("../README.md")]
 mod card;
+mod checkword;
+pub mod codec;
+mod color;
+pub mod commitment;
+mod convention;
+#[cfg(feature = "cosmos")]
+mod cosmos;
 mod deck;
+#[cfg(feature = "rand")]
+mod distributions;
+#[cfg(feature = "encryption")]
+mod encrypted;
+pub mod entropy;
 pub mod errors;
+#[cfg(feature = "eth")]
+mod eth;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod file;
+mod full_deck;
+mod hand;
+mod hash;
+#[cfg(feature = "rand")]
+pub mod health;
+pub mod memorize;
+#[cfg(feature = "mental-poker")]
+pub mod mental_poker;
+mod metal;
+#[cfg(feature = "bip39")]
+mod mnemonic;
+mod paper;
+mod phonetic;
+#[cfg(feature = "poker")]
+pub mod poker;
+mod policy;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "python")]
+pub mod python;
+mod rank;
+#[cfg(feature = "rayon")]
+pub mod recovery;
+mod salvage;
+pub mod secret;
+#[cfg(feature = "solana")]
+mod solana;
+mod story;
 mod suit;
+mod technique;
+mod transcription;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod worksheet;
 
-pub use card::Card;
-pub use deck::Deck;
+#[cfg(feature = "aezeed")]
+pub use aezeed::{CipherSeed, AEZEED_VERSION};
+pub use analysis::{Analysis, BiasReport, Finding};
+#[cfg(feature = "bitcoin")]
+pub use btc::{BitcoinKey, ExtendedKeys};
+pub use card::{Card, STANDARD_DECK};
+pub use checkword::CHECKWORDS;
+pub use color::Colored;
+pub use convention::Convention;
+#[cfg(feature = "cosmos")]
+pub use cosmos::CosmosKey;
+pub use deck::{CycleNotation, Deck, Delimiters};
+#[cfg(feature = "encryption")]
+pub use encrypted::{EncryptionOptions, ENCRYPTED_FILE_MAGIC};
+#[cfg(feature = "eth")]
+pub use eth::EthereumKey;
+pub use file::{DeckFile, DECK_FILE_MAGIC, DECK_FILE_VERSION};
+pub use full_deck::FullDeck;
+pub use hand::Hand;
+pub use hash::{
+    EntropySource, HashOptions, HashState, HashVersion, Normalization, Preimage, SaltedHash,
+    OS_RANDOM_SIZE, PBKDF2_ITERATIONS, PROGRESS_INTERVAL, SALT_SIZE,
+};
+pub use metal::MetalBackup;
+pub use paper::PaperBackup;
+pub use phonetic::{Phonetic, Spoken};
+pub use policy::Policy;
+pub use rank::Rank;
+pub use salvage::SalvageReport;
+#[cfg(feature = "solana")]
+pub use solana::SolanaKeypair;
+pub use story::Story;
 pub use suit::Suit;
+pub use technique::{Recommendation, ShuffleEstimate, Technique};
+pub use transcription::{Mismatch, SuspectedError, TranscriptionReport};
+pub use worksheet::{Worksheet, WorksheetOptions};
+
+#[cfg(feature = "bip39")]
+pub use bip39;
+#[cfg(feature = "bitcoin")]
+pub use bitcoin;
+#[cfg(feature = "custom-rng")]
+pub use getrandom;
+#[cfg(any(feature = "eth", feature = "mental-poker"))]
+pub use k256;
+#[cfg(feature = "bip39")]
+pub use mnemonic::{final_words, mnemonic_from_decks, mnemonic_to_decks};
 
 /// The size of a full valid deck with no duplicates.
 pub const DECK_SIZE: usize = 52;
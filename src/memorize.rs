@@ -0,0 +1,329 @@
+//! Person-Action-Object (PAO) mnemonics for memorizing a deck, as memory athletes
+//! do, for those who want a backup which lives only in their head.
+//!
+//! Every card is assigned a person, an action and an object in a `PaoTable`. The
+//! deck is then memorized three cards at a time, as a single image combining the
+//! person of the first card, the action of the second, and the object of the
+//! third, so that a full deck becomes 18 vivid scenes. Each scene is placed at a
+//! stop along a familiar route, or memory palace, to fix their order.
+//!
+//! ```
+//! use cardseed::memorize::PaoTable;
+//! use cardseed::Deck;
+//!
+//! let table = PaoTable::default();
+//! let deck = "JH 4S 8D 2C".parse::<Deck>().unwrap();
+//! let palace = table.palace(&deck, &["front door", "hallway"]).unwrap();
+//! assert_eq!(
+//!     palace.to_string(),
+//!     "front door: the happy judge sleepily feeding a dusty egg\n\
+//!      hallway: the clumsy tailor\n"
+//! );
+//! ```
+//!
+//! The default table is built from a fixed pattern so that it is easy to learn:
+//! the noun of every entry begins with the same letter as the card's rank, and the
+//! adjective or adverb with the same letter as its suit. Memory athletes usually
+//! replace it with their own table of people and scenes which they find vivid.
+
+use crate::card::{Card, STANDARD_DECK};
+use crate::deck::Deck;
+use crate::errors;
+use crate::story::{CHARACTERS, OBJECTS};
+use crate::DECK_SIZE;
+use std::fmt;
+
+/// The adjective of each person in the default table, by suit index.
+const PERSON_ADJECTIVES: [&str; 4] = ["sleepy", "clumsy", "happy", "dizzy"];
+
+/// The verb of each action in the default table, by rank index.
+const ACTION_VERBS: [&str; 13] = [
+    "admiring",
+    "tickling",
+    "throwing",
+    "feeding",
+    "fixing",
+    "smelling",
+    "sketching",
+    "eating",
+    "nudging",
+    "tossing",
+    "juggling",
+    "quizzing",
+    "kicking",
+];
+
+/// The adverb of each action in the default table, by suit index.
+const ACTION_ADVERBS: [&str; 4] = ["sleepily", "carefully", "hastily", "daintily"];
+
+/// The adjective of each object in the default table, by suit index.
+const OBJECT_ADJECTIVES: [&str; 4] = ["shiny", "cracked", "heavy", "dusty"];
+
+/// Assigns a person, an action and an object to every card, indexed by the card's
+/// index. Any table can be used, as long as each of its three lists has exactly one
+/// entry for each card and no entry twice.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaoTable {
+    /// The person for each card, such as `"the happy judge"`.
+    pub people: Vec<String>,
+    /// The action for each card, such as `"hastily juggling"`.
+    pub actions: Vec<String>,
+    /// The object for each card, such as `"a heavy jar"`.
+    pub objects: Vec<String>,
+}
+
+impl Default for PaoTable {
+    /// Returns the default table, such as `"the happy judge"`, `"hastily juggling"`
+    /// and `"a heavy jar"` for `JH`.
+    fn default() -> PaoTable {
+        let mut table = PaoTable {
+            people: Vec::with_capacity(DECK_SIZE),
+            actions: Vec::with_capacity(DECK_SIZE),
+            objects: Vec::with_capacity(DECK_SIZE),
+        };
+        for card in STANDARD_DECK {
+            let (rank, suit) = (card.rank().index() as usize, card.suit().index() as usize);
+            let person = format!("the {} {}", PERSON_ADJECTIVES[suit], CHARACTERS[rank]);
+            table.people.push(person);
+            let action = format!("{} {}", ACTION_ADVERBS[suit], ACTION_VERBS[rank]);
+            table.actions.push(action);
+            let object = format!("a {} {}", OBJECT_ADJECTIVES[suit], OBJECTS[rank]);
+            table.objects.push(object);
+        }
+        table
+    }
+}
+
+/// The image for up to three consecutive cards: the person of the first, the
+/// action of the second, and the object of the third. The last triad of a deck
+/// whose length isn't a multiple of three lacks an object, or both an action and an
+/// object.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Triad<'a> {
+    /// The cards the triad stands for, in order.
+    pub cards: &'a [Card],
+    /// The person of the first card.
+    pub person: &'a str,
+    /// The action of the second card, if any.
+    pub action: Option<&'a str>,
+    /// The object of the third card, if any.
+    pub object: Option<&'a str>,
+}
+
+/// Formats the triads of a `Deck` as the stops of a memory palace, one per line,
+/// each labelled with its locus. Returned by `PaoTable::palace`.
+#[derive(Clone, Debug)]
+pub struct MemoryPalace<'a> {
+    triads: Vec<Triad<'a>>,
+    loci: &'a [&'a str],
+}
+
+impl PaoTable {
+    /// Checks that each list of the table has one entry for each card, with no
+    /// entry appearing twice in the same list.
+    pub fn validate(&self) -> Result<(), errors::PaoError> {
+        for (table, entries) in self.lists() {
+            if entries.len() != DECK_SIZE {
+                return Err(errors::PaoError::TableSize {
+                    table,
+                    len: entries.len(),
+                });
+            }
+            for (i, entry) in entries.iter().enumerate() {
+                if entries[..i].contains(entry) {
+                    return Err(errors::PaoError::Duplicate(entry.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Splits the `Deck` into groups of three consecutive cards, and returns the
+    /// triad for each. Returns an `Err` if the table is invalid.
+    ///
+    /// ```
+    /// use cardseed::memorize::PaoTable;
+    /// use cardseed::Deck;
+    ///
+    /// let table = PaoTable::default();
+    /// let deck = Deck::new();
+    /// let triads = table.triads(&deck).unwrap();
+    /// assert_eq!(triads.len(), 18);
+    /// assert_eq!(
+    ///     triads[0].to_string(),
+    ///     "the sleepy astronaut sleepily tickling a shiny thimble"
+    /// );
+    /// ```
+    pub fn triads<'a>(&'a self, deck: &'a Deck) -> Result<Vec<Triad<'a>>, errors::PaoError> {
+        self.validate()?;
+        let entry = |list: &'a [String], card: Option<&Card>| {
+            card.map(|card| list[card.index() as usize].as_str())
+        };
+        Ok(deck
+            .cards
+            .chunks(3)
+            .map(|cards| Triad {
+                cards,
+                person: &self.people[cards[0].index() as usize],
+                action: entry(&self.actions, cards.get(1)),
+                object: entry(&self.objects, cards.get(2)),
+            })
+            .collect())
+    }
+
+    /// Returns a wrapper which displays the triads of the `Deck` as a memory palace,
+    /// with one line for each triad labelled by the corresponding locus, such as a
+    /// room or landmark along a familiar route. Triads beyond the last locus are
+    /// labelled with their number, counted from one. Returns an `Err` if the table
+    /// is invalid.
+    pub fn palace<'a>(
+        &'a self,
+        deck: &'a Deck,
+        loci: &'a [&'a str],
+    ) -> Result<MemoryPalace<'a>, errors::PaoError> {
+        Ok(MemoryPalace {
+            triads: self.triads(deck)?,
+            loci,
+        })
+    }
+
+    /// Looks up the cards of recalled triads, each given as its person, action and
+    /// object, and returns the `Deck` they stand for. An empty action or object marks
+    /// the end of a final partial triad. Entries must match the table exactly.
+    ///
+    /// ```
+    /// use cardseed::memorize::PaoTable;
+    ///
+    /// let table = PaoTable::default();
+    /// let deck = table.recall(&[
+    ///     ["the happy judge", "sleepily feeding", "a dusty egg"],
+    ///     ["the clumsy tailor", "", ""],
+    /// ]);
+    /// assert_eq!(deck.unwrap().to_string(), "JH 4S 8D 2C");
+    /// ```
+    pub fn recall(&self, triads: &[[&str; 3]]) -> Result<Deck, errors::PaoError> {
+        self.validate()?;
+        let mut deck = Deck { cards: vec![] };
+        for (i, triad) in triads.iter().enumerate() {
+            // Only the last triad may be partial, and only by leaving off its end.
+            let mut used = triad.iter().take_while(|entry| !entry.is_empty()).count();
+            if i + 1 < triads.len() || used == 0 || triad[used..].iter().any(|e| !e.is_empty()) {
+                used = 3;
+            }
+            let lists = [&self.people, &self.actions, &self.objects];
+            for (list, &entry) in lists.into_iter().zip(&triad[..used]) {
+                let index = list
+                    .iter()
+                    .position(|e| e == entry)
+                    .ok_or_else(|| errors::PaoError::UnknownEntry(entry.to_string()))?;
+                deck.cards.push(STANDARD_DECK[index]);
+            }
+        }
+        Ok(deck)
+    }
+
+    /// Returns the name and entries of each list in the table.
+    fn lists(&self) -> [(&'static str, &[String]); 3] {
+        [
+            ("people", &self.people),
+            ("actions", &self.actions),
+            ("objects", &self.objects),
+        ]
+    }
+}
+
+impl fmt::Display for Triad<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.person)?;
+        for part in [self.action, self.object].into_iter().flatten() {
+            write!(f, " {part}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MemoryPalace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, triad) in self.triads.iter().enumerate() {
+            match self.loci.get(i) {
+                Some(locus) => writeln!(f, "{locus}: {triad}")?,
+                None => writeln!(f, "{}: {triad}", i + 1)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_table() {
+        let table = PaoTable::default();
+        assert_eq!(table.validate(), Ok(()));
+        let jh = "JH".parse::<Card>().unwrap().index() as usize;
+        assert_eq!(table.people[jh], "the happy judge");
+        assert_eq!(table.actions[jh], "hastily juggling");
+        assert_eq!(table.objects[jh], "a heavy jar");
+    }
+
+    #[test]
+    fn triads() {
+        let table = PaoTable::default();
+        let deck = Deck::new();
+        let triads = table.triads(&deck).unwrap();
+        assert_eq!(triads.len(), 18);
+        assert_eq!(triads[17].cards, &deck.cards[51..]);
+        assert_eq!(triads[17].action, None);
+        assert_eq!(triads[17].to_string(), "the dizzy king");
+
+        let recalled: Vec<[&str; 3]> = triads
+            .iter()
+            .map(|t| [t.person, t.action.unwrap_or(""), t.object.unwrap_or("")])
+            .collect();
+        assert_eq!(table.recall(&recalled), Ok(deck));
+        assert_eq!(table.recall(&[]), Ok(Deck { cards: vec![] }));
+        assert_eq!(
+            table.recall(&[["the happy judge", "", "a dusty egg"]]),
+            Err(errors::PaoError::UnknownEntry(String::new()))
+        );
+        assert_eq!(
+            table.recall(&[["the happy judge", "juggling", ""]]),
+            Err(errors::PaoError::UnknownEntry("juggling".into()))
+        );
+    }
+
+    #[test]
+    fn palace() {
+        let table = PaoTable::default();
+        let deck = "AS 2S 3S 4S 5S 6S 7S".parse::<Deck>().unwrap();
+        let palace = table.palace(&deck, &["kitchen"]).unwrap().to_string();
+        assert_eq!(
+            palace,
+            "kitchen: the sleepy astronaut sleepily tickling a shiny thimble\n\
+             2: the sleepy farmer sleepily fixing a shiny saddle\n\
+             3: the sleepy senator\n"
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let mut table = PaoTable::default();
+        table.objects[3] = table.objects[40].clone();
+        assert_eq!(
+            table.validate(),
+            Err(errors::PaoError::Duplicate(table.objects[3].clone()))
+        );
+        assert!(table.triads(&Deck::new()).is_err());
+
+        table.actions.pop();
+        assert_eq!(
+            table.validate(),
+            Err(errors::PaoError::TableSize {
+                table: "actions",
+                len: 51
+            })
+        );
+    }
+}
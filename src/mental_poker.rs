@@ -0,0 +1,251 @@
+//! Primitives for playing cards without a trusted dealer, in the style of the
+//! SRA "mental poker" protocol: cards are encrypted with a commutative cipher, so
+//! that each player can shuffle and encrypt the deck in turn, and a card can only
+//! be read once every player has removed their encryption from it.
+//!
+//! Each card is represented by a point on the secp256k1 curve whose discrete
+//! logarithm nobody knows, found by hashing the card's index. Encrypting with a
+//! `Key` multiplies a point by a secret scalar, and decrypting multiplies by its
+//! inverse. Since multiplication commutes, keys can be applied and removed in any
+//! order.
+//!
+//! A typical two-player game runs:
+//!
+//! 1. Alice encrypts every card of `encode_deck` with her key, shuffles, and passes
+//!    the result to Bob with `shuffle_encrypt`.
+//! 2. Bob does the same with his key, and passes the doubly encrypted deck back.
+//! 3. To deal a card to Bob, Alice decrypts it with her key and sends it to him.
+//!    Bob decrypts it with his key, and reads it with `decode`.
+//! 4. After the game, both players reveal their keys, and each checks the other
+//!    shuffled honestly with `verify_shuffle`.
+//!
+//! Using a separate key for each card after shuffling, as in the full protocol,
+//! lets a player reveal single cards without revealing the rest of their hand.
+//!
+//! These primitives only detect cheating once keys are revealed. They don't include
+//! zero-knowledge proofs that a shuffle was honest while the game is in progress.
+//!
+//! ```
+//! use cardseed::mental_poker::{self, Key};
+//!
+//! let (alice, bob) = (Key::random(), Key::random());
+//! let deck = mental_poker::encode_deck();
+//! let shuffled_by_alice = mental_poker::shuffle_encrypt(&deck, &alice);
+//! let shuffled = mental_poker::shuffle_encrypt(&shuffled_by_alice, &bob);
+//!
+//! // Deal the top card to Bob.
+//! let card = alice.decrypt(&shuffled[0]);
+//! let card = mental_poker::decode(&bob.decrypt(&card)).unwrap();
+//!
+//! // After the game, Bob checks Alice's shuffle with her revealed key.
+//! assert!(mental_poker::verify_shuffle(&deck, &shuffled_by_alice, &alice));
+//! ```
+
+use crate::card::{Card, STANDARD_DECK};
+use crate::errors;
+use k256::elliptic_curve::ff::{Field, PrimeField};
+use k256::elliptic_curve::group::prime::PrimeCurveAffine;
+use k256::elliptic_curve::group::GroupEncoding;
+use k256::elliptic_curve::point::DecompressPoint;
+use k256::elliptic_curve::subtle::Choice;
+use k256::{AffinePoint, ProjectivePoint, Scalar};
+use rand::seq::SliceRandom;
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+
+/// Domain separation tag hashed to find the point representing each card.
+const CARD_TAG: &[u8] = b"cardseed/mental-poker";
+
+/// The number of bytes in a serialized `EncryptedCard`.
+pub const ENCRYPTED_CARD_SIZE: usize = 33;
+
+/// A secret key for the commutative cipher: a nonzero scalar.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Key(Scalar);
+
+impl Key {
+    /// Generates a random key with the operating system's secure random number generator.
+    pub fn random() -> Key {
+        Key::random_with_rng(&mut rand::rngs::OsRng)
+    }
+
+    /// Generates a random key with the given secure random number generator.
+    pub fn random_with_rng<R: CryptoRng + RngCore + ?Sized>(rng: &mut R) -> Key {
+        loop {
+            let scalar = Scalar::random(&mut *rng);
+            if !bool::from(scalar.is_zero()) {
+                return Key(scalar);
+            }
+        }
+    }
+
+    /// Encrypts a card, or adds another layer of encryption to an encrypted card.
+    pub fn encrypt(&self, card: &EncryptedCard) -> EncryptedCard {
+        EncryptedCard(card.0 * self.0)
+    }
+
+    /// Removes this key's layer of encryption from a card.
+    pub fn decrypt(&self, card: &EncryptedCard) -> EncryptedCard {
+        let inverse = self.0.invert().expect("keys are nonzero");
+        EncryptedCard(card.0 * inverse)
+    }
+
+    /// Serializes the key as 32 big-endian bytes, to reveal it after a game.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes().into()
+    }
+
+    /// Parses a key serialized by `Key::to_bytes`. Returns an `Err` if the bytes
+    /// aren't a nonzero scalar below the curve order.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Key, errors::ParseError> {
+        Option::from(Scalar::from_repr((*bytes).into()))
+            .filter(|scalar: &Scalar| !bool::from(scalar.is_zero()))
+            .map(Key)
+            .ok_or_else(|| errors::ParseError::Malformed(hex::encode(bytes)))
+    }
+}
+
+/// A card encrypted under zero or more keys.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EncryptedCard(ProjectivePoint);
+
+impl EncryptedCard {
+    /// Serializes the encrypted card as a compressed curve point.
+    pub fn to_bytes(&self) -> [u8; ENCRYPTED_CARD_SIZE] {
+        self.0.to_affine().to_bytes().into()
+    }
+
+    /// Parses an encrypted card serialized by `EncryptedCard::to_bytes`. Returns an
+    /// `Err` if the bytes aren't a valid compressed curve point, or are the point at
+    /// infinity, which no card encrypts to.
+    pub fn from_bytes(
+        bytes: &[u8; ENCRYPTED_CARD_SIZE],
+    ) -> Result<EncryptedCard, errors::ParseError> {
+        Option::from(AffinePoint::from_bytes(bytes.into()))
+            .filter(|point: &AffinePoint| !bool::from(point.is_identity()))
+            .map(|point| EncryptedCard(point.into()))
+            .ok_or_else(|| errors::ParseError::Malformed(hex::encode(bytes)))
+    }
+}
+
+/// Returns the unencrypted point representing a card. The point's x-coordinate is
+/// the first SHA256 hash of `"cardseed/mental-poker" || u8(index) || u8(counter)`
+/// which lies on the curve, for counters from zero, taking the even y-coordinate.
+pub fn encode(card: Card) -> EncryptedCard {
+    for counter in 0..=u8::MAX {
+        let x = Sha256::new()
+            .chain_update(CARD_TAG)
+            .chain_update([card.index() as u8, counter])
+            .finalize();
+        let point: Option<AffinePoint> = AffinePoint::decompress(&x, Choice::from(0)).into();
+        if let Some(point) = point {
+            return EncryptedCard(point.into());
+        }
+    }
+    unreachable!("about half of all hashes lie on the curve")
+}
+
+/// Returns the unencrypted points representing a standard deck, in order.
+pub fn encode_deck() -> Vec<EncryptedCard> {
+    STANDARD_DECK.iter().map(|&card| encode(card)).collect()
+}
+
+/// Returns the card a fully decrypted point represents, or `None` if it doesn't
+/// represent any card, for instance because it is still encrypted.
+pub fn decode(card: &EncryptedCard) -> Option<Card> {
+    STANDARD_DECK.into_iter().find(|&c| encode(c) == *card)
+}
+
+/// Encrypts every card with `key` and shuffles the result with the operating
+/// system's secure random number generator.
+pub fn shuffle_encrypt(cards: &[EncryptedCard], key: &Key) -> Vec<EncryptedCard> {
+    shuffle_encrypt_with_rng(cards, key, &mut rand::rngs::OsRng)
+}
+
+/// Encrypts every card with `key` and shuffles the result with the given secure
+/// random number generator.
+pub fn shuffle_encrypt_with_rng<R: CryptoRng + RngCore + ?Sized>(
+    cards: &[EncryptedCard],
+    key: &Key,
+    rng: &mut R,
+) -> Vec<EncryptedCard> {
+    let mut encrypted: Vec<EncryptedCard> = cards.iter().map(|card| key.encrypt(card)).collect();
+    encrypted.shuffle(rng);
+    encrypted
+}
+
+/// Checks, once a player has revealed their key, that they produced `output` by
+/// encrypting and permuting `input` with it, and did not add, drop, or replace
+/// any cards.
+pub fn verify_shuffle(input: &[EncryptedCard], output: &[EncryptedCard], key: &Key) -> bool {
+    if input.len() != output.len() {
+        return false;
+    }
+    let mut expected: Vec<[u8; ENCRYPTED_CARD_SIZE]> = input
+        .iter()
+        .map(|card| key.encrypt(card).to_bytes())
+        .collect();
+    let mut found: Vec<[u8; ENCRYPTED_CARD_SIZE]> =
+        output.iter().map(EncryptedCard::to_bytes).collect();
+    expected.sort_unstable();
+    found.sort_unstable();
+    expected == found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commutative() {
+        let (a, b) = (Key::random(), Key::random());
+        let card = encode("QH".parse().unwrap());
+        let ab = b.encrypt(&a.encrypt(&card));
+        assert_eq!(ab, a.encrypt(&b.encrypt(&card)));
+        assert_ne!(ab, card);
+        assert_eq!(decode(&ab), None);
+        assert_eq!(decode(&a.decrypt(&b.decrypt(&ab))), "QH".parse().ok());
+    }
+
+    #[test]
+    fn encode_decode() {
+        let deck = encode_deck();
+        for (i, point) in deck.iter().enumerate() {
+            assert_eq!(decode(point), Some(STANDARD_DECK[i]));
+            assert!(deck[..i].iter().all(|other| other != point));
+        }
+    }
+
+    #[test]
+    fn shuffle() {
+        let key = Key::random();
+        let deck = encode_deck();
+        let shuffled = shuffle_encrypt(&deck, &key);
+        assert!(verify_shuffle(&deck, &shuffled, &key));
+        assert!(!verify_shuffle(&deck, &shuffled, &Key::random()));
+
+        let mut cheated = shuffled.clone();
+        cheated[0] = cheated[1];
+        assert!(!verify_shuffle(&deck, &cheated, &key));
+        assert!(!verify_shuffle(&deck, &shuffled[1..], &key));
+
+        let mut decrypted: Vec<Card> = shuffled
+            .iter()
+            .map(|card| decode(&key.decrypt(card)).unwrap())
+            .collect();
+        decrypted.sort_by_key(Card::index);
+        assert_eq!(decrypted, STANDARD_DECK);
+    }
+
+    #[test]
+    fn serialization() {
+        let key = Key::random();
+        assert_eq!(Key::from_bytes(&key.to_bytes()), Ok(key));
+        assert!(Key::from_bytes(&[0; 32]).is_err());
+        assert!(Key::from_bytes(&[0xff; 32]).is_err());
+
+        let card = encode("2C".parse().unwrap());
+        assert_eq!(EncryptedCard::from_bytes(&card.to_bytes()), Ok(card));
+        assert!(EncryptedCard::from_bytes(&[0; ENCRYPTED_CARD_SIZE]).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::errors;
+use std::fmt;
+
+/// The modulus of the row checksums in a metal backup. As a prime below 100, it
+/// fits in two digits, and its weighted sum catches any single wrong digit and any
+/// swap of two cards within a row.
+const CHECKSUM_MODULUS: u32 = 97;
+
+/// Formats a `Deck` for stamping into metal plates, using only digits: each card
+/// as its two-digit index, in numbered rows which each end with a two-digit
+/// checksum. Returned by `Deck::metal_backup`, and read back by
+/// `Deck::parse_metal_backup`.
+///
+/// ```text
+/// 1  00 01 02 03 04 05 06 07 08 09 10 11 12  49
+/// 2  13 14 15 16 17 18 19 20 21 22 23 24 25  68
+/// 3  26 27 28 29 30 31 32 33 34 35 36 37 38  87
+/// 4  39 40 41 42 43 44 45 46 47 48 49 50 51  09
+/// ```
+///
+/// The checksum of a row is the sum of each card index multiplied by its position
+/// within the row, counted from one, modulo 97.
+#[derive(Clone, Copy, Debug)]
+pub struct MetalBackup<'a> {
+    deck: &'a Deck,
+    row_len: usize,
+}
+
+impl Deck {
+    /// Returns a wrapper which displays the `Deck` as rows of `row_len` two-digit
+    /// card indexes with checksums, for stamping into metal. A `row_len` of zero is
+    /// treated as one.
+    ///
+    /// ```
+    /// use cardseed::Deck;
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let plate = deck.metal_backup(2).to_string();
+    /// assert_eq!(plate, "1  00 28  56\n2  25 15  55\n");
+    /// assert_eq!(Deck::parse_metal_backup(&plate), Ok(deck));
+    /// ```
+    pub fn metal_backup(&self, row_len: usize) -> MetalBackup<'_> {
+        MetalBackup {
+            deck: self,
+            row_len: row_len.max(1),
+        }
+    }
+
+    /// Parses a `Deck` from the layout written by `Deck::metal_backup`, checking the
+    /// number and checksum of every row. Blank lines are skipped.
+    pub fn parse_metal_backup(s: &str) -> Result<Deck, errors::MetalBackupError> {
+        let mut deck = Deck { cards: vec![] };
+        let lines = s.lines().filter(|line| !line.trim().is_empty());
+        for (row, line) in (1..).zip(lines) {
+            let malformed = || errors::MetalBackupError::Malformed(String::from(line));
+            let numbers = line
+                .split_whitespace()
+                .map(|token| token.parse::<u32>().map_err(|_| malformed()))
+                .collect::<Result<Vec<u32>, _>>()?;
+            let [number, indexes @ .., checksum] = &numbers[..] else {
+                return Err(malformed());
+            };
+            if *number != row || indexes.is_empty() {
+                return Err(malformed());
+            }
+            if row_checksum(indexes) != *checksum {
+                return Err(errors::MetalBackupError::Checksum { row });
+            }
+
+            for &index in indexes {
+                let card = Card::try_from(index).map_err(|_| errors::DeckError::UnknownCard {
+                    position: deck.cards.len(),
+                    source: errors::ParseError::BadInt(index),
+                })?;
+                deck.cards.push(card);
+            }
+        }
+        Ok(deck)
+    }
+}
+
+/// Computes the checksum of a row of card indexes.
+fn row_checksum(indexes: &[u32]) -> u32 {
+    (1..)
+        .zip(indexes)
+        .map(|(weight, &index)| weight * index)
+        .sum::<u32>()
+        % CHECKSUM_MODULUS
+}
+
+impl fmt::Display for MetalBackup<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (row, chunk) in self.deck.cards.chunks(self.row_len).enumerate() {
+            write!(f, "{} ", row + 1)?;
+            let indexes: Vec<u32> = chunk.iter().map(Card::index).collect();
+            for index in &indexes {
+                write!(f, " {index:02}")?;
+            }
+            writeln!(f, "  {:02}", row_checksum(&indexes))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metal_backup() {
+        let deck = Deck::new();
+        let plate = deck.metal_backup(13).to_string();
+        assert!(plate.starts_with("1  00 01 02 03 04 05 06 07 08 09 10 11 12  49\n"));
+        assert!(plate.ends_with("\n4  39 40 41 42 43 44 45 46 47 48 49 50 51  09\n"));
+        assert_eq!(plate.lines().count(), 4);
+        assert_eq!(Deck::parse_metal_backup(&plate), Ok(deck.clone()));
+        assert_eq!(
+            Deck::parse_metal_backup(&deck.metal_backup(0).to_string()),
+            Ok(deck.clone())
+        );
+        assert_eq!(Deck { cards: vec![] }.metal_backup(13).to_string(), "");
+    }
+
+    #[test]
+    fn parse_metal_backup() {
+        // Any single wrong digit, or two swapped cards, breaks the checksum.
+        assert_eq!(
+            Deck::parse_metal_backup("1  00 01 02 03  20\n"),
+            Ok("AS 2S 3S 4S".parse().unwrap())
+        );
+        for plate in [
+            "1  00 01 02 13  20",
+            "1  00 02 01 03  20",
+            "1  00 01 02 03  21",
+        ] {
+            assert_eq!(
+                Deck::parse_metal_backup(plate),
+                Err(errors::MetalBackupError::Checksum { row: 1 })
+            );
+        }
+
+        assert!(matches!(
+            Deck::parse_metal_backup("2  00  00"),
+            Err(errors::MetalBackupError::Malformed(_))
+        ));
+        assert!(matches!(
+            Deck::parse_metal_backup("1  AS  00"),
+            Err(errors::MetalBackupError::Malformed(_))
+        ));
+        assert!(matches!(
+            Deck::parse_metal_backup("1  52  52"),
+            Err(errors::MetalBackupError::Deck(_))
+        ));
+    }
+}
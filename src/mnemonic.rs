@@ -0,0 +1,230 @@
+use crate::codec::{self, CAPACITY};
+use crate::deck::Deck;
+use crate::errors;
+use bip39::Language;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+impl Deck {
+    /// Derives a 24-word [BIP39](https://github.com/bitcoin/bips/blob/master/bip-0039.mediawiki)
+    /// mnemonic from the `Deck`, using the output of `Deck::hash` as the mnemonic's entropy.
+    ///
+    /// ```
+    /// use cardseed::Deck;
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let mnemonic = deck.mnemonic(Some("bestpasswordever"));
+    /// assert_eq!(mnemonic.word_count(), 24);
+    /// assert_eq!(mnemonic.to_entropy(), deck.hash(Some("bestpasswordever")));
+    /// ```
+    pub fn mnemonic(&self, password: Option<&str>) -> bip39::Mnemonic {
+        let entropy = Zeroizing::new(self.hash(password));
+        match bip39::Mnemonic::from_entropy(entropy.as_slice()) {
+            Ok(mnemonic) => mnemonic,
+            Err(e) => panic!("failed to encode 32 bytes of entropy as a mnemonic: {}", e),
+        }
+    }
+}
+
+/// Returns every English BIP39 word which completes the given words into a mnemonic
+/// with a valid checksum, in word list order.
+///
+/// The last word of a mnemonic holds the final few bits of entropy followed by a
+/// checksum of all the entropy, so only some words may end it. This supports
+/// building a mnemonic by hand, for instance by drawing a card for each word:
+/// once all but the last word are chosen, the last draw must pick one of these.
+/// Returns an `Err` if a word is not in the word list, or if one more word would
+/// not make a mnemonic of 12, 15, 18, 21, or 24 words.
+///
+/// ```
+/// let words = ["abandon"; 11];
+/// let finals = cardseed::final_words(&words).unwrap();
+/// assert_eq!(finals.len(), 128);
+/// assert_eq!(finals[0], "about");
+/// ```
+pub fn final_words(words: &[&str]) -> Result<Vec<&'static str>, bip39::Error> {
+    let word_count = words.len() + 1;
+    // `is_multiple_of` would need Rust 1.87.
+    #[allow(clippy::manual_is_multiple_of)]
+    if word_count % 3 != 0 || !(12..=24).contains(&word_count) {
+        return Err(bip39::Error::BadWordCount(word_count));
+    }
+
+    let mut bits = Vec::with_capacity(word_count * 11);
+    for (i, word) in words.iter().enumerate() {
+        let index = Language::English
+            .find_word(word)
+            .ok_or(bip39::Error::UnknownWord(i))?;
+        bits.extend((0..11).rev().map(|b| index >> b & 1 == 1));
+    }
+
+    let checksum_bits = word_count / 3;
+    let free_bits = 11 - checksum_bits;
+    let list = Language::English.word_list();
+
+    let mut finals = Vec::with_capacity(1 << free_bits);
+    for free in 0..1u16 << free_bits {
+        let mut entropy = bits.clone();
+        entropy.extend((0..free_bits).rev().map(|b| free >> b & 1 == 1));
+        let bytes: Vec<u8> = entropy
+            .chunks(8)
+            .map(|chunk| chunk.iter().fold(0, |byte, &bit| byte << 1 | bit as u8))
+            .collect();
+
+        let checksum = Sha256::digest(&bytes)[0] >> (8 - checksum_bits);
+        finals.push(list[((free << checksum_bits) | checksum as u16) as usize]);
+    }
+    Ok(finals)
+}
+
+/// Stores an existing [BIP39](https://github.com/bitcoin/bips/blob/master/bip-0039.mediawiki)
+/// mnemonic in the order of one or more decks, so that a seed which wasn't derived
+/// from cards can still be backed up by arranging a deck. Use `mnemonic_from_decks`
+/// to read it back.
+///
+/// Only the entropy of the mnemonic is stored, with `codec::encode`, since its
+/// checksum is computed from the entropy when the mnemonic is restored. Mnemonics
+/// of up to 21 words fit in a single deck. The 256 bits of a 24-word mnemonic are
+/// more than a deck can hold, so they are split evenly over two decks, which must be
+/// kept in order.
+///
+/// ```
+/// use cardseed::bip39::Mnemonic;
+///
+/// let words = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+/// let mnemonic = Mnemonic::parse(words).unwrap();
+/// let decks = cardseed::mnemonic_to_decks(&mnemonic);
+/// assert_eq!(decks.len(), 1);
+/// assert_eq!(cardseed::mnemonic_from_decks(&decks, 12), Ok(mnemonic));
+/// ```
+pub fn mnemonic_to_decks(mnemonic: &bip39::Mnemonic) -> Vec<Deck> {
+    let entropy = Zeroizing::new(mnemonic.to_entropy());
+    let chunk_len = entropy.len() / entropy.len().div_ceil(CAPACITY);
+    entropy
+        .chunks(chunk_len)
+        .map(|chunk| match codec::encode(chunk) {
+            Ok(deck) => deck,
+            Err(e) => panic!("failed to encode mnemonic entropy in a deck: {}", e),
+        })
+        .collect()
+}
+
+/// Restores a mnemonic of `word_count` words stored by `mnemonic_to_decks`, given
+/// its decks in order. The checksum of the mnemonic is recomputed from the stored
+/// entropy.
+///
+/// Most decks do not hold a number small enough to be entropy, so a deck which was
+/// rearranged by mistake is usually caught and returns an `Err`, though this is not
+/// guaranteed. A deck holding all 224 bits of a 21-word mnemonic has no such slack.
+/// Returns an `Err` if `word_count` is not 12, 15, 18, 21, or 24, or if the number
+/// of decks is wrong for it.
+pub fn mnemonic_from_decks(
+    decks: &[Deck],
+    word_count: usize,
+) -> Result<bip39::Mnemonic, errors::MnemonicError> {
+    // `is_multiple_of` would need Rust 1.87.
+    #[allow(clippy::manual_is_multiple_of)]
+    if word_count % 3 != 0 || !(12..=24).contains(&word_count) {
+        return Err(errors::MnemonicError::WordCount(word_count));
+    }
+    let entropy_len = word_count * 11 * 32 / 33 / 8;
+    let expected = entropy_len.div_ceil(CAPACITY);
+    if decks.len() != expected {
+        return Err(errors::MnemonicError::DeckCount {
+            expected,
+            found: decks.len(),
+        });
+    }
+
+    let mut entropy = Zeroizing::new(Vec::with_capacity(entropy_len));
+    for deck in decks {
+        entropy.extend(Zeroizing::new(codec::decode(deck, entropy_len / expected)?).iter());
+    }
+    match bip39::Mnemonic::from_entropy(&entropy) {
+        Ok(mnemonic) => Ok(mnemonic),
+        Err(e) => panic!("failed to restore a mnemonic from its entropy: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mnemonic() {
+        let mnemonic = Deck::new().mnemonic(None);
+        assert_eq!(mnemonic.to_entropy(), Deck::new().hash(None));
+        assert_eq!(
+            mnemonic.to_string(),
+            "smart online call margin wisdom monitor drink only badge nice almost hotel \
+             vanish jar weekend entry under grace junk hint design please moon acid"
+        );
+    }
+
+    #[test]
+    fn final_words() {
+        let mnemonic = Deck::new().mnemonic(None).to_string();
+        let words: Vec<&str> = mnemonic.split_whitespace().collect();
+        let finals = super::final_words(&words[..23]).unwrap();
+        assert_eq!(finals.len(), 8);
+        assert!(finals.contains(&words[23]));
+        for word in finals {
+            let completed = format!("{} {}", words[..23].join(" "), word);
+            assert!(bip39::Mnemonic::parse(&completed).is_ok());
+        }
+
+        let twelve = super::final_words(&["zoo"; 11]).unwrap();
+        assert_eq!(twelve.len(), 128);
+        assert!(twelve.contains(&"wrong"));
+
+        assert_eq!(
+            super::final_words(&["abandon"; 12]),
+            Err(bip39::Error::BadWordCount(13))
+        );
+        assert_eq!(
+            super::final_words(&["abandon", "cardseed"]),
+            Err(bip39::Error::BadWordCount(3))
+        );
+        let mut words = ["abandon"; 14];
+        words[3] = "cardseed";
+        assert_eq!(
+            super::final_words(&words),
+            Err(bip39::Error::UnknownWord(3))
+        );
+    }
+
+    #[test]
+    fn mnemonic_decks() {
+        for word_count in [12, 15, 18, 21, 24] {
+            let entropy: Vec<u8> = (0..word_count * 4 / 3).map(|i| i as u8 ^ 0xA5).collect();
+            let mnemonic = bip39::Mnemonic::from_entropy(&entropy).unwrap();
+            let decks = mnemonic_to_decks(&mnemonic);
+            assert_eq!(decks.len(), if word_count == 24 { 2 } else { 1 });
+            for deck in &decks {
+                assert_eq!(deck.validate(), Ok(()));
+            }
+            assert_eq!(mnemonic_from_decks(&decks, word_count), Ok(mnemonic));
+        }
+
+        let mnemonic = Deck::new().mnemonic(None);
+        let decks = mnemonic_to_decks(&mnemonic);
+        assert_eq!(mnemonic_from_decks(&decks, 24), Ok(mnemonic.clone()));
+        assert_eq!(
+            mnemonic_from_decks(&decks[..1], 24),
+            Err(errors::MnemonicError::DeckCount {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            mnemonic_from_decks(&decks, 13),
+            Err(errors::MnemonicError::WordCount(13))
+        );
+        let mut reversed = Deck::new();
+        reversed.cards.reverse();
+        assert_eq!(
+            mnemonic_from_decks(&[reversed], 12),
+            Err(errors::CodecError::Corrupt.into())
+        );
+    }
+}
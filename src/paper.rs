@@ -0,0 +1,160 @@
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::errors;
+use std::fmt;
+
+/// Formats a `Deck` for printing onto a physical backup sheet, in numbered rows
+/// of cards labelled with the positions they hold, such as `14-26`. Returned by
+/// `Deck::paper_backup`, and read back by `Deck::parse_paper_backup`.
+///
+/// ```text
+///  1-13  AS 2S 3S 4S 5S 6S 7S 8S 9S TS JS QS KS
+/// 14-26  AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC QC KC
+/// 27-39  AH 2H 3H 4H 5H 6H 7H 8H 9H TH JH QH KH
+/// 40-52  AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD KD
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct PaperBackup<'a> {
+    deck: &'a Deck,
+    row_len: usize,
+}
+
+impl Deck {
+    /// Returns a wrapper which displays the `Deck` in rows of `row_len` cards, each
+    /// labelled with the positions of its cards counted from one. A `row_len` of
+    /// zero is treated as one.
+    ///
+    /// ```
+    /// use cardseed::Deck;
+    ///
+    /// let deck = "AS 2C 3H 4D 5S".parse::<Deck>().unwrap();
+    /// assert_eq!(deck.paper_backup(2).to_string(), "1-2  AS 2C\n3-4  3H 4D\n5-5  5S\n");
+    /// ```
+    pub fn paper_backup(&self, row_len: usize) -> PaperBackup<'_> {
+        PaperBackup {
+            deck: self,
+            row_len: row_len.max(1),
+        }
+    }
+
+    /// Parses a `Deck` from the layout written by `Deck::paper_backup`. Blank lines
+    /// are skipped, and each row may be labelled with the positions of its cards,
+    /// such as `14-26` or `14-26:`, or left unlabelled.
+    ///
+    /// Labels are checked against the cards read so far, so that a row skipped or
+    /// copied short while transcribing the sheet is reported as a
+    /// `DeckError::WrongLength`, where `expected` is the number of cards which the
+    /// label says come before or within the row.
+    ///
+    /// ```
+    /// use cardseed::{errors::DeckError, Deck};
+    ///
+    /// let deck = Deck::parse_paper_backup(" 1-2  AS 2C\n\n 3-3: 3H\n").unwrap();
+    /// assert_eq!(deck.to_string(), "AS 2C 3H");
+    ///
+    /// let skipped = Deck::parse_paper_backup("1-2  AS 2C\n5-6  5S 6S\n");
+    /// assert_eq!(skipped, Err(DeckError::WrongLength { expected: 4, found: 2 }));
+    /// ```
+    pub fn parse_paper_backup(s: &str) -> Result<Deck, errors::DeckError> {
+        let mut deck = Deck { cards: vec![] };
+        for line in s.lines() {
+            let mut tokens = line.split_whitespace().peekable();
+            let label = tokens.peek().and_then(|token| parse_label(token));
+            if label.is_some() {
+                tokens.next();
+            }
+
+            if let Some((first, _)) = label {
+                if first != deck.cards.len() + 1 {
+                    return Err(errors::DeckError::WrongLength {
+                        expected: first - 1,
+                        found: deck.cards.len(),
+                    });
+                }
+            }
+            for token in tokens {
+                let card =
+                    Card::parse_token(token).map_err(|source| errors::DeckError::UnknownCard {
+                        position: deck.cards.len(),
+                        source,
+                    })?;
+                deck.cards.push(card);
+            }
+            if let Some((_, last)) = label {
+                if last != deck.cards.len() {
+                    return Err(errors::DeckError::WrongLength {
+                        expected: last,
+                        found: deck.cards.len(),
+                    });
+                }
+            }
+        }
+        Ok(deck)
+    }
+}
+
+/// Parses a row label such as `14-26` or `14-26:` into the positions of the first
+/// and last cards of the row, or returns `None` if the token is not a label.
+fn parse_label(token: &str) -> Option<(usize, usize)> {
+    let (first, last) = token.strip_suffix(':').unwrap_or(token).split_once('-')?;
+    let (first, last) = (first.parse::<usize>().ok()?, last.parse::<usize>().ok()?);
+    (first >= 1 && last >= first).then_some((first, last))
+}
+
+impl fmt::Display for PaperBackup<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cards = &self.deck.cards;
+        let width = cards.len().to_string().len();
+        for (row, chunk) in cards.chunks(self.row_len).enumerate() {
+            let first = row * self.row_len + 1;
+            let last = first + chunk.len() - 1;
+            write!(f, "{first:>width$}-{last:<width$} ")?;
+            for card in chunk {
+                write!(f, " {card}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paper_backup() {
+        let deck = Deck::new();
+        let sheet = deck.paper_backup(13).to_string();
+        assert_eq!(sheet.lines().count(), 4);
+        assert!(sheet.starts_with(" 1-13  AS 2S"));
+        assert!(sheet.contains("\n40-52  AD 2D"));
+        assert_eq!(Deck::parse_paper_backup(&sheet), Ok(deck.clone()));
+
+        for row_len in [0, 1, 5, 52, 100] {
+            let sheet = deck.paper_backup(row_len).to_string();
+            assert_eq!(Deck::parse_paper_backup(&sheet), Ok(deck.clone()));
+        }
+        assert_eq!(Deck { cards: vec![] }.paper_backup(13).to_string(), "");
+        assert_eq!(Deck::parse_paper_backup(""), Ok(Deck { cards: vec![] }));
+    }
+
+    #[test]
+    fn parse_paper_backup() {
+        assert_eq!(
+            Deck::parse_paper_backup("1-3  AS 2S\n"),
+            Err(errors::DeckError::WrongLength {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert!(matches!(
+            Deck::parse_paper_backup("1-2  AS XX\n"),
+            Err(errors::DeckError::UnknownCard { position: 1, .. })
+        ));
+        assert_eq!(parse_label("0-3"), None);
+        assert_eq!(parse_label("AS"), None);
+        assert_eq!(parse_label("4-3"), None);
+        assert_eq!(parse_label("4-9:"), Some((4, 9)));
+    }
+}
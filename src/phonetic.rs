@@ -0,0 +1,129 @@
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::rank::Rank;
+use std::fmt;
+
+/// Selects how `Card::spoken` and `Deck::spoken` spell out each rank. Suits are
+/// always spelled out by name, since their names are hard to mishear, unlike the
+/// letters `S` and `C`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Phonetic {
+    /// Ranks are spoken by their English names, such as `"Queen Hearts"`.
+    #[default]
+    Names,
+    /// Face cards, aces, and tens are spoken with the NATO phonetic alphabet
+    /// word for their card string character, such as `"Quebec Hearts"`, and the
+    /// number ranks by their English names.
+    Nato,
+}
+
+/// Formats a `Card` or `Deck` as words which are easy to read aloud over a phone
+/// or voice channel, such as `"Queen Hearts, Two Clubs"`. Returned by `Card::spoken`
+/// and `Deck::spoken`.
+///
+/// The words can be entered again with `Deck::parse_lenient`, which understands
+/// both styles of `Phonetic` rank words.
+#[derive(Clone, Copy, Debug)]
+pub struct Spoken<'a, T>(&'a T, Phonetic);
+
+impl Rank {
+    /// Returns the NATO phonetic alphabet word for the card string character of the
+    /// `Rank`, such as `"Kilo"` for a king, or the English name of number ranks.
+    pub const fn nato_name(self) -> &'static str {
+        match self {
+            Rank::Ace => "Alfa",
+            Rank::Ten => "Tango",
+            Rank::Jack => "Juliett",
+            Rank::Queen => "Quebec",
+            Rank::King => "Kilo",
+            _ => self.name(),
+        }
+    }
+
+    /// Returns the spoken word for the `Rank` in the given `Phonetic` style.
+    fn spoken(self, style: Phonetic) -> &'static str {
+        match style {
+            Phonetic::Names => self.name(),
+            Phonetic::Nato => self.nato_name(),
+        }
+    }
+}
+
+impl Card {
+    /// Returns a wrapper which displays the `Card` as its rank and suit spoken aloud,
+    /// with the rank in the given `Phonetic` style.
+    ///
+    /// ```
+    /// use cardseed::{Card, Phonetic};
+    ///
+    /// let card = "TS".parse::<Card>().unwrap();
+    /// assert_eq!(card.spoken(Phonetic::Names).to_string(), "Ten Spades");
+    /// assert_eq!(card.spoken(Phonetic::Nato).to_string(), "Tango Spades");
+    /// ```
+    pub fn spoken(&self, style: Phonetic) -> Spoken<'_, Card> {
+        Spoken(self, style)
+    }
+}
+
+impl Deck {
+    /// Returns a wrapper which displays the `Deck` as comma-delimited cards, each
+    /// formatted like `Card::spoken`.
+    ///
+    /// ```
+    /// use cardseed::{Deck, Phonetic};
+    ///
+    /// let deck = "QH 2C AS".parse::<Deck>().unwrap();
+    /// let spoken = deck.spoken(Phonetic::Nato).to_string();
+    /// assert_eq!(spoken, "Quebec Hearts, Two Clubs, Alfa Spades");
+    /// assert_eq!(Deck::parse_lenient(&spoken).unwrap(), deck);
+    /// ```
+    pub fn spoken(&self, style: Phonetic) -> Spoken<'_, Deck> {
+        Spoken(self, style)
+    }
+}
+
+impl fmt::Display for Spoken<'_, Card> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let card = self.0;
+        write!(f, "{} {}", card.rank().spoken(self.1), card.suit().name())
+    }
+}
+
+impl fmt::Display for Spoken<'_, Deck> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, card) in self.0.cards.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", card.spoken(self.1))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spoken() {
+        let deck = Deck::new();
+        for style in [Phonetic::Names, Phonetic::Nato] {
+            let spoken = deck.spoken(style).to_string();
+            assert_eq!(Deck::parse_lenient(&spoken).unwrap(), deck);
+            assert_eq!(Deck::parse_lenient(&spoken.to_lowercase()).unwrap(), deck);
+        }
+        assert_eq!(
+            deck.spoken(Phonetic::Names).to_string()[..40],
+            *"Ace Spades, Two Spades, Three Spades, Fo"
+        );
+        assert_eq!(
+            Deck { cards: vec![] }.spoken(Phonetic::Nato).to_string(),
+            ""
+        );
+
+        // Common alternative spellings are understood too.
+        let deck = Deck::parse_lenient("Alpha Clubs, Juliet Diamonds, Niner Hearts").unwrap();
+        assert_eq!(deck.to_string(), "AC JD 9H");
+    }
+}
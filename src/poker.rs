@@ -0,0 +1,230 @@
+//! Ranking poker hands of five to seven cards, so that games built on this crate
+//! don't need a second card type to evaluate hands.
+//!
+//! ```
+//! use cardseed::poker::{self, Category};
+//! use cardseed::Card;
+//!
+//! let cards = |s: &str| -> Vec<Card> { s.split(' ').map(|c| c.parse().unwrap()).collect() };
+//! let flush = poker::evaluate(&cards("2H 9H JH 4H KH 3C 3S")).unwrap();
+//! let trips = poker::evaluate(&cards("QS QD QC 4H 7D")).unwrap();
+//! assert_eq!(flush.category(), Category::Flush);
+//! assert!(flush > trips);
+//! ```
+
+use crate::card::Card;
+use crate::deck::CardSet;
+use crate::errors;
+use std::fmt;
+
+/// The category of a poker hand, in increasing order of strength.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Category {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Category::HighCard => "high card",
+            Category::OnePair => "one pair",
+            Category::TwoPair => "two pair",
+            Category::ThreeOfAKind => "three of a kind",
+            Category::Straight => "straight",
+            Category::Flush => "flush",
+            Category::FullHouse => "full house",
+            Category::FourOfAKind => "four of a kind",
+            Category::StraightFlush => "straight flush",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The strength of the best five-card poker hand among some cards. Stronger hands
+/// compare greater, and hands which split the pot compare equal.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct HandRank {
+    category: Category,
+    ranks: [u8; 5],
+}
+
+impl HandRank {
+    /// Returns the category of the hand.
+    pub fn category(&self) -> Category {
+        self.category
+    }
+
+    /// Returns the ranks which break ties between hands of the same category, in
+    /// order of significance, from 2 for a deuce up to 14 for an ace. Grouped cards
+    /// come first, such as the pair in a one pair hand, followed by the kickers.
+    /// Straights list only their highest rank, which is 5 for an ace-low straight,
+    /// and the remaining ranks are zero.
+    pub fn ranks(&self) -> [u8; 5] {
+        self.ranks
+    }
+}
+
+impl fmt::Display for HandRank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.category)
+    }
+}
+
+/// Ranks the best five-card poker hand among five to seven cards, such as a
+/// Texas hold'em player's two hole cards and the five community cards. Returns an
+/// `Err` if there are too few or too many cards, or if any card appears twice.
+pub fn evaluate(cards: &[Card]) -> Result<HandRank, errors::PokerError> {
+    if !(5..=7).contains(&cards.len()) {
+        return Err(errors::PokerError::HandSize(cards.len()));
+    }
+    let mut seen = CardSet::default();
+    if let Some(&card) = cards.iter().find(|&&card| !seen.insert(card)) {
+        return Err(errors::PokerError::Duplicate(card));
+    }
+
+    // Try every subset of five cards, of which there are at most 21.
+    let subsets = (0u32..1 << cards.len()).filter(|mask| mask.count_ones() == 5);
+    let best = subsets.map(|mask| {
+        let mut hand = [cards[0]; 5];
+        let chosen = (0..cards.len()).filter(|i| mask & 1 << i != 0);
+        for (slot, i) in hand.iter_mut().zip(chosen) {
+            *slot = cards[i];
+        }
+        evaluate5(&hand)
+    });
+    Ok(best.max().expect("at least one hand was evaluated"))
+}
+
+/// Returns the poker rank of a card, from 2 for a deuce up to 14 for an ace.
+fn rank(card: Card) -> u8 {
+    match card.value() {
+        0 => 14,
+        value => value as u8 + 1,
+    }
+}
+
+/// Ranks exactly five distinct cards.
+fn evaluate5(hand: &[Card; 5]) -> HandRank {
+    let flush = hand.iter().all(|c| c.suit() == hand[0].suit());
+
+    let mut counts = [0u8; 15];
+    for &card in hand {
+        counts[rank(card) as usize] += 1;
+    }
+    // Distinct ranks ordered by how many times they appear, then by rank.
+    let mut groups: Vec<(u8, u8)> = (2..=14)
+        .rev()
+        .filter(|&r| counts[r] > 0)
+        .map(|r| (counts[r], r as u8))
+        .collect();
+    groups.sort_by(|x, y| y.cmp(x));
+
+    let straight_high = if groups.len() < 5 {
+        None
+    } else if groups[0].1 - groups[4].1 == 4 {
+        Some(groups[0].1)
+    } else if groups[0].1 == 14 && groups[1].1 == 5 {
+        Some(5)
+    } else {
+        None
+    };
+
+    if let Some(high) = straight_high {
+        let category = if flush {
+            Category::StraightFlush
+        } else {
+            Category::Straight
+        };
+        return HandRank {
+            category,
+            ranks: [high, 0, 0, 0, 0],
+        };
+    }
+
+    let category = match (groups[0].0, groups[1].0) {
+        _ if flush => Category::Flush,
+        (4, _) => Category::FourOfAKind,
+        (3, 2) => Category::FullHouse,
+        (3, _) => Category::ThreeOfAKind,
+        (2, 2) => Category::TwoPair,
+        (2, _) => Category::OnePair,
+        _ => Category::HighCard,
+    };
+    let mut ranks = [0; 5];
+    for (slot, &(_, r)) in ranks.iter_mut().zip(&groups) {
+        *slot = r;
+    }
+    HandRank { category, ranks }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand(s: &str) -> HandRank {
+        let cards: Vec<Card> = s.split(' ').map(|c| c.parse().unwrap()).collect();
+        evaluate(&cards).unwrap()
+    }
+
+    #[test]
+    fn categories() {
+        let cases = [
+            ("TS JS QS KS AS", Category::StraightFlush),
+            ("9C 9D 9H 9S 2C", Category::FourOfAKind),
+            ("3C 3D 3H KS KC", Category::FullHouse),
+            ("2H 9H JH 4H KH", Category::Flush),
+            ("AC 2D 3H 4S 5C", Category::Straight),
+            ("7C 7D 7H 2S 4C", Category::ThreeOfAKind),
+            ("7C 7D 4H 4S AC", Category::TwoPair),
+            ("7C 7D 4H 5S AC", Category::OnePair),
+            // Straights don't wrap around from king to deuce.
+            ("QC KD AH 2S 3C", Category::HighCard),
+            ("7C 9D 4H 5S AC", Category::HighCard),
+        ];
+        for (cards, category) in cases {
+            assert_eq!(hand(cards).category(), category, "{cards}");
+        }
+        let mut sorted = cases.map(|(cards, _)| hand(cards));
+        sorted.sort_by(|a, b| b.cmp(a));
+        assert_eq!(sorted, cases.map(|(cards, _)| hand(cards)));
+    }
+
+    #[test]
+    fn kickers() {
+        assert!(hand("7C 7D 4H 5S AC") > hand("7H 7S 4C 5D KC"));
+        assert!(hand("7C 7D 4H 4S 2C") < hand("7H 7S 5C 5D 2D"));
+        assert_eq!(hand("7C 7D 4H 5S AC"), hand("7H 7S 4C 5D AD"));
+        assert!(hand("3C 3D 3H 2S 2C") > hand("2H 2S 2D AC AD"));
+        assert_eq!(hand("AC 2D 3H 4S 5C").ranks(), [5, 0, 0, 0, 0]);
+        assert!(hand("AC 2D 3H 4S 5C") < hand("2D 3H 4S 5C 6D"));
+        assert_eq!(hand("7C 7D 4H 4S AC").ranks(), [7, 4, 14, 0, 0]);
+    }
+
+    #[test]
+    fn seven_cards() {
+        // The board's straight is beaten by the flush made with a hole card.
+        assert_eq!(hand("5H 6H 7D 8S 9H 2H KH").category(), Category::Flush);
+        assert_eq!(hand("5H 6H 7D 8S 9H 2H KH").ranks(), [13, 9, 6, 5, 2]);
+        // The best two pair among three pairs keeps the highest kicker.
+        assert_eq!(hand("2C 2D 5H 5S 9C 9D 3H").ranks(), [9, 5, 3, 0, 0]);
+
+        let cards: Vec<Card> = "AS KS".split(' ').map(|c| c.parse().unwrap()).collect();
+        assert_eq!(evaluate(&cards), Err(errors::PokerError::HandSize(2)));
+        let cards: Vec<Card> = "AS KS AS 2C 3D"
+            .split(' ')
+            .map(|c| c.parse().unwrap())
+            .collect();
+        assert_eq!(
+            evaluate(&cards),
+            Err(errors::PokerError::Duplicate("AS".parse().unwrap()))
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use crate::deck::Deck;
+use crate::errors;
+use crate::hash::HashOptions;
+
+/// Requirements a `Deck` must meet before `Deck::derive_checked` will derive a
+/// secret from it, protecting against hashing a deck which was never shuffled,
+/// was transcribed incompletely, or is too short to be secure.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Policy {
+    /// The minimum number of bits of entropy the deck must contain, as measured
+    /// by `Deck::entropy_bits`.
+    pub min_entropy_bits: f64,
+    /// Whether the deck must be a complete 52-card deck.
+    pub require_full_deck: bool,
+    /// Whether the deck must not contain any card more than once.
+    pub forbid_duplicates: bool,
+    /// Whether the deck must not be in the canonical order of `Deck::new`, nor
+    /// any prefix of it.
+    pub forbid_canonical_order: bool,
+}
+
+impl Policy {
+    /// The strictest policy: a complete, shuffled 52-card deck without duplicates.
+    /// This is the default.
+    pub fn strict() -> Policy {
+        Policy {
+            min_entropy_bits: 225.0,
+            require_full_deck: true,
+            forbid_duplicates: true,
+            forbid_canonical_order: true,
+        }
+    }
+
+    /// Checks a `Deck` against the policy, returning the first requirement which
+    /// it fails.
+    ///
+    /// ```
+    /// use cardseed::{errors::PolicyError, Deck, Policy};
+    ///
+    /// let policy = Policy::strict();
+    /// assert_eq!(policy.check(&Deck::new()), Err(PolicyError::CanonicalOrder));
+    /// # #[cfg(feature = "rand")]
+    /// assert_eq!(policy.check(&Deck::new().shuffle()), Ok(()));
+    /// ```
+    pub fn check(&self, deck: &Deck) -> Result<(), errors::PolicyError> {
+        if self.require_full_deck {
+            deck.validate()?;
+        } else if self.forbid_duplicates {
+            if let Some(card) = deck.first_duplicate() {
+                return Err(errors::DeckError::Duplicate(card).into());
+            }
+        }
+
+        let bits = deck.entropy_bits();
+        if bits < self.min_entropy_bits {
+            return Err(errors::PolicyError::InsufficientEntropy {
+                bits,
+                required: self.min_entropy_bits,
+            });
+        }
+
+        if self.forbid_canonical_order && Deck::new().cards.starts_with(&deck.cards) {
+            return Err(errors::PolicyError::CanonicalOrder);
+        }
+        Ok(())
+    }
+}
+
+impl Default for Policy {
+    fn default() -> Policy {
+        Policy::strict()
+    }
+}
+
+impl Deck {
+    /// Derives a secret from the `Deck` and an optional password like `Deck::hash_with`,
+    /// but first checks the deck against `policy`. Returns an `Err` describing the
+    /// failed requirement, without hashing, if the deck is too weak.
+    ///
+    /// ```
+    /// use cardseed::{Deck, HashOptions, Policy};
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// assert!(deck
+    ///     .derive_checked(&Policy::strict(), None, &HashOptions::default())
+    ///     .is_err());
+    /// ```
+    pub fn derive_checked(
+        &self,
+        policy: &Policy,
+        password: Option<&str>,
+        options: &HashOptions,
+    ) -> Result<[u8; 32], errors::PolicyError> {
+        policy.check(self)?;
+        Ok(self.hash_with(password, options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn strict() {
+        let policy = Policy::default();
+        assert_eq!(policy, Policy::strict());
+
+        let shuffled = Deck::new().shuffle();
+        assert_eq!(policy.check(&shuffled), Ok(()));
+        assert_eq!(
+            policy.check(&Deck::new()),
+            Err(errors::PolicyError::CanonicalOrder)
+        );
+
+        let mut partial = shuffled.cards.clone();
+        let removed = partial.pop().unwrap();
+        assert_eq!(
+            policy.check(&Deck { cards: partial }),
+            Err(errors::DeckError::Incomplete(vec![removed]).into())
+        );
+
+        let mut duplicated = shuffled.cards.clone();
+        duplicated[1] = duplicated[0];
+        assert_eq!(
+            policy.check(&Deck { cards: duplicated }),
+            Err(errors::DeckError::Duplicate(shuffled.cards[0]).into())
+        );
+    }
+
+    #[test]
+    fn relaxed() {
+        let policy = Policy {
+            min_entropy_bits: 12.0,
+            require_full_deck: false,
+            forbid_duplicates: true,
+            forbid_canonical_order: true,
+        };
+
+        let deck = "AS 3H KC 3C 7D 2S 9H".parse::<Deck>().unwrap();
+        assert_eq!(policy.check(&deck), Ok(()));
+        assert!(matches!(
+            policy.check(&"AS 3H KC 3C".parse::<Deck>().unwrap()),
+            Err(errors::PolicyError::InsufficientEntropy { .. })
+        ));
+        assert_eq!(
+            policy.check(&"AS 3H KC 3C 7D AS 9H".parse::<Deck>().unwrap()),
+            Err(errors::DeckError::Duplicate("AS".parse().unwrap()).into())
+        );
+        assert_eq!(
+            policy.check(&"AS 2S 3S 4S 5S 6S 7S 8S 9S".parse::<Deck>().unwrap()),
+            Err(errors::PolicyError::CanonicalOrder)
+        );
+
+        let options = HashOptions::default();
+        assert_eq!(
+            deck.derive_checked(&policy, Some("pw"), &options),
+            Ok(deck.hash(Some("pw")))
+        );
+    }
+}
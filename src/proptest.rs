@@ -0,0 +1,109 @@
+//! [proptest](https://docs.rs/proptest) strategies for generating cards and decks,
+//! so that downstream crates can property-test code built on this crate's types.
+//!
+//! ```
+//! use cardseed::proptest::shuffled_deck;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     fn deck_round_trips(deck in shuffled_deck()) {
+//!         prop_assert_eq!(deck.to_string().parse::<cardseed::Deck>(), Ok(deck));
+//!     }
+//! }
+//! # deck_round_trips();
+//! ```
+
+use crate::card::{Card, STANDARD_DECK};
+use crate::deck::Deck;
+use crate::DECK_SIZE;
+use ::proptest::prelude::*;
+use ::proptest::sample::{subsequence, SizeRange};
+
+/// Generates any valid `Card`, each with equal probability.
+pub fn any_card() -> impl Strategy<Value = Card> {
+    (0..DECK_SIZE).prop_map(|i| STANDARD_DECK[i])
+}
+
+/// Generates complete 52-card decks in uniformly random order.
+pub fn shuffled_deck() -> impl Strategy<Value = Deck> {
+    Just(STANDARD_DECK.to_vec())
+        .prop_shuffle()
+        .prop_map(|cards| Deck { cards })
+}
+
+/// Generates decks of distinct cards in random order, with a number of cards in
+/// the given range, such as `1..=10`. Lengths above 52 are capped at 52.
+pub fn partial_deck(len: impl Into<SizeRange>) -> impl Strategy<Value = Deck> {
+    let len = len.into();
+    let (min, max) = (len.start().min(DECK_SIZE), len.end_incl().min(DECK_SIZE));
+    subsequence(STANDARD_DECK.to_vec(), min..=max)
+        .prop_shuffle()
+        .prop_map(|cards| Deck { cards })
+}
+
+/// Generates pairs of a complete shuffled deck and a copy of it with transcription
+/// errors injected, as a person might make while writing a deck down: swapping two
+/// neighboring cards, writing one card in place of another, or leaving a card out.
+///
+/// The number of errors is drawn from the given range. Each error is applied in turn,
+/// so a later error may undo an earlier one, as when the same neighbors are swapped
+/// twice.
+pub fn deck_with_errors(errors: impl Into<SizeRange>) -> impl Strategy<Value = (Deck, Deck)> {
+    let mistakes = prop::collection::vec(
+        (
+            0..3u8,
+            any::<prop::sample::Index>(),
+            any::<prop::sample::Index>(),
+        ),
+        errors,
+    );
+    (shuffled_deck(), mistakes).prop_map(|(deck, mistakes)| {
+        let mut cards = deck.cards.clone();
+        for (kind, a, b) in mistakes {
+            let len = cards.len();
+            if len < 2 {
+                break;
+            }
+            let i = a.index(len);
+            match kind {
+                0 => cards.swap(i, (i + 1) % len),
+                1 => {
+                    let j = b.index(len - 1);
+                    cards[i] = cards[if j >= i { j + 1 } else { j }];
+                }
+                _ => {
+                    cards.remove(i);
+                }
+            }
+        }
+        (deck, Deck { cards })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn strategies(
+            card in any_card(),
+            deck in shuffled_deck(),
+            partial in partial_deck(1..10),
+            (original, corrupted) in deck_with_errors(1..4),
+        ) {
+            prop_assert!(card.index() < DECK_SIZE as u32);
+            prop_assert_eq!(deck.validate(), Ok(()));
+            prop_assert!((1..10).contains(&partial.len()));
+            prop_assert!(!partial.has_duplicates());
+            prop_assert_eq!(original.validate(), Ok(()));
+            prop_assert!(corrupted.len() >= DECK_SIZE - 3);
+        }
+
+        #[test]
+        fn pack_round_trips(deck in partial_deck(0..=DECK_SIZE)) {
+            prop_assert_eq!(Deck::unpack(&deck.pack()), Ok(deck.clone()));
+            prop_assert_eq!(Deck::parse_compact(&deck.to_compact()), Ok(deck));
+        }
+    }
+}
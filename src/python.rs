@@ -0,0 +1,190 @@
+//! Python bindings for `cardseed`, built with [PyO3](https://pyo3.rs).
+//!
+//! Build the extension module with [maturin](https://www.maturin.rs), which enables
+//! the `python` and `pyo3/extension-module` features as configured in `pyproject.toml`:
+//!
+//! ```text
+//! maturin develop --release
+//! ```
+//!
+//! Errors are raised as Python `ValueError`s carrying the formatted Rust error message.
+
+// The code generated by `#[pymethods]` for fallible methods trips this lint.
+#![allow(clippy::useless_conversion)]
+
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::suit::Suit;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+fn value_error(e: impl std::fmt::Display) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+/// A single playing card, exported to Python as `Card`.
+#[pyclass(name = "Card", frozen, eq, hash)]
+#[derive(Clone, PartialEq, Hash)]
+pub struct PyCard(Card);
+
+#[pymethods]
+impl PyCard {
+    /// Parses a card from a 2-character string such as `"TH"`.
+    #[new]
+    fn new(s: &str) -> PyResult<PyCard> {
+        s.parse::<Card>().map(PyCard).map_err(value_error)
+    }
+
+    /// Creates a card from its index in the range `[0..52]`.
+    #[staticmethod]
+    fn from_index(index: u8) -> PyResult<PyCard> {
+        Card::from_index(index).map(PyCard).map_err(value_error)
+    }
+
+    /// The card's face value index from 0 to 12, where ace is zero and king is 12.
+    #[getter]
+    fn value(&self) -> u32 {
+        self.0.value()
+    }
+
+    /// The card's suit as a single character: one of `S`, `C`, `H`, or `D`.
+    #[getter]
+    fn suit(&self) -> String {
+        self.0.suit().to_string()
+    }
+
+    /// The card's index from 0 to 51.
+    #[getter]
+    fn index(&self) -> u32 {
+        self.0.index()
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Card('{}')", self.0)
+    }
+}
+
+/// A sequence of playing cards, exported to Python as `Deck`.
+#[pyclass(name = "Deck", eq)]
+#[derive(PartialEq)]
+pub struct PyDeck(Deck);
+
+#[pymethods]
+impl PyDeck {
+    /// Parses a deck from a string of whitespace-delimited card strings. If no
+    /// string is given, creates a new deck in canonical order.
+    #[new]
+    #[pyo3(signature = (s=None))]
+    fn new(s: Option<&str>) -> PyResult<PyDeck> {
+        match s {
+            Some(s) => s.parse::<Deck>().map(PyDeck).map_err(value_error),
+            None => Ok(PyDeck(Deck::new())),
+        }
+    }
+
+    /// Returns a randomly shuffled copy of the deck, using a secure OS RNG.
+    fn shuffle(&self) -> PyDeck {
+        PyDeck(self.0.shuffle())
+    }
+
+    /// The cards in the deck, in order.
+    #[getter]
+    fn cards(&self) -> Vec<PyCard> {
+        self.0.cards.iter().copied().map(PyCard).collect()
+    }
+
+    /// Returns true if the deck contains any duplicate cards.
+    fn has_duplicates(&self) -> bool {
+        self.0.has_duplicates()
+    }
+
+    /// Raises a `ValueError` if the deck is not a complete 52-card deck without duplicates.
+    fn validate(&self) -> PyResult<()> {
+        self.0.validate().map_err(value_error)
+    }
+
+    /// Returns the number of bits of entropy in the deck, assuming it was randomly shuffled.
+    fn entropy_bits(&self) -> f64 {
+        self.0.entropy_bits()
+    }
+
+    /// Derives a 32-byte secret from the deck and an optional password.
+    #[pyo3(signature = (password=None))]
+    fn hash<'py>(&self, py: Python<'py>, password: Option<&str>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &self.0.hash(password))
+    }
+
+    /// Derives a 24-word BIP39 mnemonic from the deck and an optional password.
+    #[cfg(feature = "bip39")]
+    #[pyo3(signature = (password=None))]
+    fn mnemonic(&self, password: Option<&str>) -> String {
+        self.0.mnemonic(password).to_string()
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.cards.len()
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Deck('{}')", self.0)
+    }
+}
+
+/// The `cardseed` Python module.
+#[pymodule]
+fn cardseed(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyCard>()?;
+    m.add_class::<PyDeck>()?;
+    m.add("DECK_SIZE", crate::DECK_SIZE)?;
+    m.add(
+        "SUITS",
+        Suit::all().map(|s| s.to_string()).collect::<Vec<_>>(),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new_bound(py, "cardseed").unwrap();
+            cardseed(&module).unwrap();
+
+            let deck: PyRef<PyDeck> = module
+                .getattr("Deck")
+                .unwrap()
+                .call1(("AS 3H KC 3C",))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(deck.__len__(), 4);
+            assert_eq!(deck.cards()[1].__repr__(), "Card('3H')");
+
+            let hash = deck.hash(py, Some("bestpasswordever"));
+            assert_eq!(
+                hash.as_bytes(),
+                "AS 3H KC 3C"
+                    .parse::<Deck>()
+                    .unwrap()
+                    .hash(Some("bestpasswordever"))
+            );
+
+            let err = module.getattr("Deck").unwrap().call1(("AS XX",));
+            assert!(err.unwrap_err().is_instance_of::<PyValueError>(py));
+            assert!(deck.validate().is_err());
+        });
+    }
+}
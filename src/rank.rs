@@ -0,0 +1,197 @@
+use crate::errors;
+use std::fmt;
+use std::ops::RangeBounds;
+
+/// Every rank, in order of index.
+pub(crate) const ALL_RANKS: [Rank; 13] = [
+    Rank::Ace,
+    Rank::Two,
+    Rank::Three,
+    Rank::Four,
+    Rank::Five,
+    Rank::Six,
+    Rank::Seven,
+    Rank::Eight,
+    Rank::Nine,
+    Rank::Ten,
+    Rank::Jack,
+    Rank::Queen,
+    Rank::King,
+];
+
+/// Represents the face value of a playing card. Ranks are ordered by index, the
+/// same as `Card::value`, so the ace is lowest and the king highest.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Rank {
+    Ace,
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+}
+
+impl From<Rank> for u32 {
+    fn from(rank: Rank) -> u32 {
+        rank.index()
+    }
+}
+
+impl TryFrom<u32> for Rank {
+    type Error = errors::ParseError;
+
+    fn try_from(x: u32) -> Result<Rank, errors::ParseError> {
+        Rank::from_index(x)
+    }
+}
+
+impl TryFrom<char> for Rank {
+    type Error = errors::ParseError;
+
+    fn try_from(c: char) -> Result<Rank, errors::ParseError> {
+        match c {
+            'A' => Ok(Rank::Ace),
+            '2'..='9' => Rank::from_index(c as u32 - '1' as u32),
+            'T' => Ok(Rank::Ten),
+            'J' => Ok(Rank::Jack),
+            'Q' => Ok(Rank::Queen),
+            'K' => Ok(Rank::King),
+            c => Err(errors::ParseError::BadChar(c)),
+        }
+    }
+}
+
+impl fmt::Display for Rank {
+    /// Formats the `Rank` as the first character of a card string, such as `T` for ten.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let c = match self {
+            Rank::Ace => 'A',
+            Rank::Ten => 'T',
+            Rank::Jack => 'J',
+            Rank::Queen => 'Q',
+            Rank::King => 'K',
+            rank => char::from(b'1' + rank.index() as u8),
+        };
+        write!(f, "{}", c)
+    }
+}
+
+impl Rank {
+    /// Returns an iterator over every rank, from ace to king.
+    ///
+    /// ```
+    /// use cardseed::Rank;
+    ///
+    /// let ranks: String = Rank::all().map(|r| r.to_string()).collect();
+    /// assert_eq!(ranks, "A23456789TJQK");
+    /// ```
+    pub fn all() -> std::array::IntoIter<Rank, 13> {
+        ALL_RANKS.into_iter()
+    }
+
+    /// Returns an iterator over the ranks within a range, from lowest to highest.
+    /// Ranges of ranks can't be iterated directly, since that requires an unstable trait.
+    ///
+    /// ```
+    /// use cardseed::{Card, Rank, Suit};
+    ///
+    /// let faces: Vec<Card> = Rank::range(Rank::Jack..)
+    ///     .map(|rank| Card::from_rank(rank, Suit::Hearts))
+    ///     .collect();
+    /// assert_eq!(faces.len(), 3);
+    /// ```
+    pub fn range(range: impl RangeBounds<Rank>) -> impl Iterator<Item = Rank> {
+        Rank::all().filter(move |rank| range.contains(rank))
+    }
+
+    /// Returns the English name of the `Rank`, such as `"Ace"` or `"Ten"`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Rank::Ace => "Ace",
+            Rank::Two => "Two",
+            Rank::Three => "Three",
+            Rank::Four => "Four",
+            Rank::Five => "Five",
+            Rank::Six => "Six",
+            Rank::Seven => "Seven",
+            Rank::Eight => "Eight",
+            Rank::Nine => "Nine",
+            Rank::Ten => "Ten",
+            Rank::Jack => "Jack",
+            Rank::Queen => "Queen",
+            Rank::King => "King",
+        }
+    }
+
+    /// Parses a `Rank` from a word, ignoring case: its name, such as `"queen"`, its
+    /// NATO phonetic name, such as `"quebec"`, its number from 2 to 10, or its card
+    /// string character, such as `"q"`.
+    pub(crate) fn from_word(word: &str) -> Option<Rank> {
+        if let Some(rank) = Rank::all().find(|r| {
+            r.name().eq_ignore_ascii_case(word) || r.nato_name().eq_ignore_ascii_case(word)
+        }) {
+            return Some(rank);
+        }
+        match word.to_ascii_lowercase().as_str() {
+            "alpha" => Some(Rank::Ace),
+            "juliet" => Some(Rank::Jack),
+            "niner" => Some(Rank::Nine),
+            "10" => Some(Rank::Ten),
+            w if w.len() == 1 => Rank::try_from(w.chars().next()?.to_ascii_uppercase()).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the `Rank` from 0 to 12, where ace is zero and king is 12.
+    /// Equivalent to `u32::from`, but usable in `const` contexts.
+    pub const fn index(self) -> u32 {
+        self as u32
+    }
+
+    /// Creates a `Rank` from its index from 0 to 12. Equivalent to `Rank::try_from`,
+    /// but usable in `const` contexts.
+    pub const fn from_index(x: u32) -> Result<Rank, errors::ParseError> {
+        if x as usize >= ALL_RANKS.len() {
+            return Err(errors::ParseError::BadInt(x));
+        }
+        Ok(ALL_RANKS[x as usize])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Card, Suit, STANDARD_DECK};
+
+    #[test]
+    fn index() {
+        for (i, rank) in Rank::all().enumerate() {
+            assert_eq!(rank.index(), i as u32);
+            assert_eq!(Rank::try_from(i as u32), Ok(rank));
+            assert_eq!(
+                Rank::try_from(rank.to_string().chars().next().unwrap()),
+                Ok(rank)
+            );
+        }
+        assert_eq!(Rank::try_from(13), Err(errors::ParseError::BadInt(13)));
+        assert_eq!(Rank::try_from('1'), Err(errors::ParseError::BadChar('1')));
+    }
+
+    #[test]
+    fn table() {
+        let cards: Vec<Card> = Suit::all()
+            .flat_map(|suit| Rank::all().map(move |rank| Card::from_rank(rank, suit)))
+            .collect();
+        assert_eq!(cards, STANDARD_DECK);
+        assert!(cards.iter().all(|c| c.rank().index() == c.value()));
+        assert!(Rank::Ace < Rank::King);
+        assert_eq!(Rank::range(Rank::Two..=Rank::Ten).count(), 9);
+    }
+}
@@ -0,0 +1,685 @@
+//! Brute-force recovery of a partially lost deck transcription. Each position of
+//! the deck is a `Slot`, which is either known, entirely unknown, or one of a few
+//! cards the user can't tell apart. A `Search` enumerates every completion of the
+//! deck without duplicate cards, in parallel, until one matches a `Target` recorded
+//! earlier, such as the deck's hash or fingerprint.
+//!
+//! Each candidate costs one full hash, so only a few positions can be recovered
+//! this way. `Search::count` reports the size of the search before running it.
+//!
+//! ```
+//! use cardseed::recovery::{Search, Slot, Target};
+//! use cardseed::Deck;
+//!
+//! # #[cfg(feature = "rand")] {
+//! let deck = Deck::new().shuffle();
+//! let target = Target::Fingerprint(deck.fingerprint64());
+//!
+//! let mut slots: Vec<Slot> = deck.cards.iter().map(|&card| Slot::Known(card)).collect();
+//! slots[3] = Slot::Unknown;
+//! slots[17] = Slot::Unknown;
+//!
+//! let search = Search::new(slots, target);
+//! assert_eq!(search.count(), Ok(2));
+//! assert_eq!(search.run(|_, _| {}), Ok(Some(deck)));
+//! # }
+//! ```
+//!
+//! When a transcription skipped a few cards but kept the rest in order, `placements`
+//! instead searches every position the missing cards could have held.
+
+use crate::card::{Card, STANDARD_DECK};
+use crate::deck::{CardSet, Deck};
+use crate::errors;
+use crate::hash::{HashOptions, SaltedHash};
+use crate::DECK_SIZE;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// The default limit on the number of candidates a `Search` will try.
+pub const MAX_CANDIDATES: u64 = 1 << 32;
+
+/// What is known about the card at one position of a deck.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Slot {
+    /// The card is known exactly.
+    Known(Card),
+    /// The card could be any card not known to be elsewhere in the deck.
+    Unknown,
+    /// The card is one of these, such as when a transcription is smudged.
+    OneOf(Vec<Card>),
+}
+
+impl std::str::FromStr for Slot {
+    type Err = errors::ParseError;
+
+    /// Parses a `Slot` from a card string such as `"7H"`, a `"?"` or `"??"` for an
+    /// unknown card, or `/`-delimited card strings such as `"7H/7D"` for a card
+    /// which is one of several.
+    ///
+    /// ```
+    /// use cardseed::recovery::Slot;
+    ///
+    /// let slots: Vec<Slot> = "AS ?? 7H/7D".split_whitespace().map(|s| s.parse().unwrap()).collect();
+    /// assert_eq!(slots[1], Slot::Unknown);
+    /// ```
+    fn from_str(s: &str) -> Result<Slot, errors::ParseError> {
+        match s {
+            "?" | "??" => Ok(Slot::Unknown),
+            s if s.contains('/') => Ok(Slot::OneOf(
+                s.split('/').map(str::parse).collect::<Result<_, _>>()?,
+            )),
+            s => Ok(Slot::Known(s.parse()?)),
+        }
+    }
+}
+
+/// A record of a deck which a `Search` checks candidates against.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Target {
+    /// The output of `Deck::hash_with` with the given password and options.
+    Hash {
+        hash: [u8; 32],
+        password: Option<String>,
+        options: HashOptions,
+    },
+    /// A `SaltedHash` of the deck, derived with the given password.
+    Salted {
+        salted: SaltedHash,
+        password: Option<String>,
+    },
+    /// The output of `Deck::fingerprint64`. Checking a fingerprint is far faster
+    /// than a hash, but a short fingerprint may match more than one candidate.
+    Fingerprint(u64),
+    /// The output of `Deck::checkword` with the given password. A checkword holds
+    /// only 8 bits, so about 1 in 256 candidates match it by chance.
+    Checkword {
+        word: String,
+        password: Option<String>,
+    },
+}
+
+impl Target {
+    /// Returns true if the `Deck` matches the target.
+    pub fn matches(&self, deck: &Deck) -> bool {
+        match self {
+            Target::Hash {
+                hash,
+                password,
+                options,
+            } => deck.hash_with(password.as_deref(), options) == *hash,
+            Target::Salted { salted, password } => salted.verify(deck, password.as_deref()),
+            Target::Fingerprint(fingerprint) => deck.fingerprint64() == *fingerprint,
+            Target::Checkword { word, password } => deck
+                .checkword(password.as_deref())
+                .eq_ignore_ascii_case(word),
+        }
+    }
+}
+
+/// A search for the completion of a partially known deck which matches a `Target`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Search {
+    /// The deck's positions, in order.
+    pub slots: Vec<Slot>,
+    /// The record candidates are checked against.
+    pub target: Target,
+    /// The most candidates the search will try. Searches with more candidates
+    /// fail with `RecoveryError::TooManyCandidates` before trying any.
+    /// Defaults to `MAX_CANDIDATES`.
+    pub max_candidates: u64,
+}
+
+/// The cards of one pile of a deck, such as one of four 13-card piles it was dealt
+/// into, when the cards in the pile are known but their order is partly lost.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pile {
+    /// Every card in the pile, in any order.
+    pub cards: Vec<Card>,
+    /// The cards whose positions within the pile are known, with their positions
+    /// counting from zero at the start of the pile.
+    pub known: Vec<(usize, Card)>,
+}
+
+/// The size and expected duration of a `Search`, as returned by `Search::estimate`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Estimate {
+    /// The number of candidate decks the search would try.
+    pub candidates: u64,
+    /// How long trying every candidate would take on this machine, using all
+    /// available threads. A search which finds its match stops sooner.
+    pub duration: Duration,
+}
+
+/// An unresolved position of the deck, and the cards which could fill it.
+#[derive(Clone)]
+struct Free {
+    position: usize,
+    options: Vec<Card>,
+}
+
+impl Search {
+    /// Creates a `Search` with the default candidate limit.
+    pub fn new(slots: Vec<Slot>, target: Target) -> Search {
+        Search {
+            slots,
+            target,
+            max_candidates: MAX_CANDIDATES,
+        }
+    }
+
+    /// Creates a `Search` for a deck which was split into consecutive piles, where
+    /// the cards in each pile are known but their order within the pile is only
+    /// partly known. Every ordering of each pile's cards which agrees with its known
+    /// positions is a candidate. Returns an `Err` naming the first pile with a known
+    /// card which isn't among its cards, or at a position outside it.
+    ///
+    /// ```
+    /// use cardseed::recovery::{Pile, Search, Target};
+    /// use cardseed::Deck;
+    ///
+    /// let deck = "AS 3H KC 3C 7D 2S".parse::<Deck>().unwrap();
+    /// let piles = [
+    ///     Pile { cards: deck.cards[..3].to_vec(), known: vec![(0, deck.cards[0])] },
+    ///     Pile { cards: deck.cards[3..].to_vec(), known: vec![] },
+    /// ];
+    /// let search = Search::from_piles(&piles, Target::Fingerprint(deck.fingerprint64())).unwrap();
+    /// assert_eq!(search.estimate().unwrap().candidates, 2 * 6);
+    /// assert_eq!(search.run(|_, _| {}), Ok(Some(deck)));
+    /// ```
+    pub fn from_piles(piles: &[Pile], target: Target) -> Result<Search, errors::RecoveryError> {
+        let mut slots = Vec::new();
+        for (i, pile) in piles.iter().enumerate() {
+            let mut pile_slots = vec![None; pile.cards.len()];
+            for &(position, card) in &pile.known {
+                let valid = pile.cards.contains(&card)
+                    && pile_slots.get(position) == Some(&None)
+                    && !pile_slots.contains(&Some(Slot::Known(card)));
+                if !valid {
+                    return Err(errors::RecoveryError::InvalidPile { pile: i });
+                }
+                pile_slots[position] = Some(Slot::Known(card));
+            }
+
+            let unplaced: Vec<Card> = pile
+                .cards
+                .iter()
+                .copied()
+                .filter(|card| !pile_slots.contains(&Some(Slot::Known(*card))))
+                .collect();
+            slots.extend(
+                pile_slots
+                    .into_iter()
+                    .map(|slot| slot.unwrap_or_else(|| Slot::OneOf(unplaced.clone()))),
+            );
+        }
+        Ok(Search::new(slots, target))
+    }
+
+    /// Counts the candidate decks the search would try. Returns an `Err` if a known
+    /// card appears twice, or if there are more than `max_candidates` candidates.
+    /// Counting is much faster than checking candidates against a hash.
+    pub fn count(&self) -> Result<u64, errors::RecoveryError> {
+        let too_many = || errors::RecoveryError::TooManyCandidates {
+            limit: self.max_candidates,
+        };
+        let (template, known, free) = self.prepare()?;
+        let mut deck = Deck { cards: template };
+
+        // Positions which can't hold any of the same cards are independent, so the
+        // total is the product of the counts of each group of dependent positions.
+        let mut total = 1u64;
+        for group in groups(&free) {
+            let limit = self.max_candidates / total;
+            let count = count_group(&group, &mut deck, known, limit).ok_or_else(too_many)?;
+            if count == 0 {
+                return Ok(0);
+            }
+            total *= count;
+        }
+        Ok(total)
+    }
+
+    /// Counts the candidates, and times checking one of them against the target to
+    /// estimate how long the whole search would take. Returns an `Err` if the
+    /// search can't run.
+    pub fn estimate(&self) -> Result<Estimate, errors::RecoveryError> {
+        let candidates = self.count()?;
+        let (template, _, _) = self.prepare()?;
+
+        let start = Instant::now();
+        self.target.matches(&Deck { cards: template });
+        let threads = rayon::current_num_threads().max(1) as f64;
+        Ok(Estimate {
+            candidates,
+            duration: start.elapsed().mul_f64(candidates as f64 / threads),
+        })
+    }
+
+    /// Tries every candidate deck in parallel across all available threads, returning
+    /// the first which matches the target, or `None` if none do. The remaining
+    /// candidates are abandoned as soon as a match is found.
+    ///
+    /// After each candidate is checked, `progress` is called with the number of
+    /// candidates checked so far and the total number of candidates.
+    pub fn run(
+        &self,
+        progress: impl Fn(u64, u64) + Sync,
+    ) -> Result<Option<Deck>, errors::RecoveryError> {
+        let total = self.count()?;
+        let (template, known, free) = self.prepare()?;
+
+        // Split the search into branches by the first free position, so that each
+        // thread enumerates the rest of its branch without coordination.
+        let Some((first, rest)) = free.split_first() else {
+            let deck = Deck { cards: template };
+            progress(1, 1);
+            return Ok(self.target.matches(&deck).then_some(deck));
+        };
+
+        let found = AtomicBool::new(false);
+        let checked = AtomicU64::new(0);
+        let result = first.options.par_iter().find_map_any(|&card| {
+            let mut used = known;
+            if !used.insert(card) {
+                return None;
+            }
+            let mut deck = Deck {
+                cards: template.clone(),
+            };
+            deck.cards[first.position] = card;
+
+            let mut matched = None;
+            visit(rest, &mut deck, used, &mut |candidate| {
+                if found.load(Ordering::Relaxed) {
+                    return true;
+                }
+                let is_match = self.target.matches(candidate);
+                progress(checked.fetch_add(1, Ordering::Relaxed) + 1, total);
+                if is_match {
+                    found.store(true, Ordering::Relaxed);
+                    matched = Some(candidate.cards.clone());
+                }
+                is_match
+            });
+            matched.map(|cards| Deck { cards })
+        });
+        Ok(result)
+    }
+
+    /// Returns a deck with the known cards filled in, the set of known cards, and
+    /// the free positions with the cards which could fill each of them.
+    fn prepare(&self) -> Result<(Vec<Card>, CardSet, Vec<Free>), errors::RecoveryError> {
+        let mut known = CardSet::default();
+        let mut template = vec![Card::ace_of_spades(); self.slots.len()];
+        for (position, slot) in self.slots.iter().enumerate() {
+            if let Slot::Known(card) = slot {
+                if !known.insert(*card) {
+                    return Err(errors::RecoveryError::Duplicate(*card));
+                }
+                template[position] = *card;
+            }
+        }
+
+        let mut free = Vec::new();
+        for (position, slot) in self.slots.iter().enumerate() {
+            let candidates = match slot {
+                Slot::Known(_) => continue,
+                Slot::Unknown => (0..DECK_SIZE as u8)
+                    .map(|i| Card::from_index(i).expect("index is below DECK_SIZE"))
+                    .collect(),
+                Slot::OneOf(cards) => cards.clone(),
+            };
+
+            let mut seen = known;
+            let options = candidates
+                .into_iter()
+                .filter(|&card| seen.insert(card))
+                .collect();
+            free.push(Free { position, options });
+        }
+        Ok((template, known, free))
+    }
+}
+
+/// A complete deck found by `placements`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Placement {
+    /// The completed deck.
+    pub deck: Deck,
+    /// The positions in `deck` at which the missing cards were placed, in ascending order.
+    pub positions: Vec<usize>,
+}
+
+/// Finds every complete 52-card deck which contains the cards of `known` in the same
+/// order, with the missing cards inserted anywhere between them, and which matches
+/// all of the `targets`.
+///
+/// The results are ranked by how close to the end of the deck the missing cards were
+/// placed, closest first, since a transcription which is cut short loses its last
+/// cards. Returns an `Err` if `known` contains a duplicate card, or if there are more
+/// than `MAX_CANDIDATES` possible placements.
+///
+/// ```
+/// use cardseed::recovery::{self, Target};
+/// use cardseed::Deck;
+///
+/// # #[cfg(feature = "rand")] {
+/// let deck = Deck::new().shuffle();
+/// let mut known = deck.clone();
+/// known.cards.remove(40);
+/// known.cards.remove(7);
+///
+/// let found = recovery::placements(&known, &[Target::Fingerprint(deck.fingerprint64())]).unwrap();
+/// assert_eq!(found[0].deck, deck);
+/// assert_eq!(found[0].positions, vec![7, 40]);
+/// # }
+/// ```
+pub fn placements(
+    known: &Deck,
+    targets: &[Target],
+) -> Result<Vec<Placement>, errors::RecoveryError> {
+    if let Some(card) = known.first_duplicate() {
+        return Err(errors::RecoveryError::Duplicate(card));
+    }
+    let missing: Vec<Card> = STANDARD_DECK
+        .into_iter()
+        .filter(|card| !known.cards.contains(card))
+        .collect();
+
+    // Each candidate assigns every missing card a distinct position in the deck, so
+    // they can be numbered in a mixed radix of `len`, `len - 1`, and so on.
+    let len = known.cards.len() + missing.len();
+    let candidates = (len - missing.len() + 1..=len).try_fold(1u64, |n, radix| {
+        n.checked_mul(radix as u64).filter(|&n| n <= MAX_CANDIDATES)
+    });
+    let Some(candidates) = candidates else {
+        return Err(errors::RecoveryError::TooManyCandidates {
+            limit: MAX_CANDIDATES,
+        });
+    };
+
+    let mut found: Vec<Placement> = (0..candidates)
+        .into_par_iter()
+        .filter_map(|mut n| {
+            let mut cards = vec![None; len];
+            for (i, &card) in missing.iter().enumerate() {
+                let radix = (len - i) as u64;
+                let skip = (n % radix) as usize;
+                n /= radix;
+                let position = (0..len)
+                    .filter(|&p| cards[p].is_none())
+                    .nth(skip)
+                    .expect("a free position remains for each missing card");
+                cards[position] = Some(card);
+            }
+
+            let positions = (0..len).filter(|&p| cards[p].is_some()).collect();
+            let mut rest = known.cards.iter();
+            let deck = Deck {
+                cards: cards
+                    .into_iter()
+                    .map(|card| card.or_else(|| rest.next().copied()))
+                    .collect::<Option<_>>()?,
+            };
+            targets
+                .iter()
+                .all(|target| target.matches(&deck))
+                .then_some(Placement { deck, positions })
+        })
+        .collect();
+
+    found.sort_by_key(|placement| {
+        placement
+            .positions
+            .iter()
+            .map(|p| len - 1 - p)
+            .sum::<usize>()
+    });
+    Ok(found)
+}
+
+/// Splits the free positions into groups which share no possible cards with any
+/// other group, so that each group can be counted independently.
+fn groups(free: &[Free]) -> Vec<Vec<Free>> {
+    let mut group: Vec<usize> = (0..free.len()).collect();
+    let find = |group: &[usize], mut i: usize| {
+        while group[i] != i {
+            i = group[i];
+        }
+        i
+    };
+
+    let mut holder: Vec<Option<usize>> = vec![None; DECK_SIZE];
+    for (i, slot) in free.iter().enumerate() {
+        for card in &slot.options {
+            match holder[card.index() as usize] {
+                Some(j) => {
+                    let (a, b) = (find(&group, i), find(&group, j));
+                    group[a] = b;
+                }
+                None => holder[card.index() as usize] = Some(i),
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<Free>> = vec![Vec::new(); free.len()];
+    for (i, slot) in free.iter().enumerate() {
+        groups[find(&group, i)].push(slot.clone());
+    }
+    groups.retain(|group| !group.is_empty());
+    groups
+}
+
+/// Counts the distinct assignments of cards to a group of free positions, or
+/// returns `None` if there are more than `limit`.
+fn count_group(group: &[Free], deck: &mut Deck, used: CardSet, limit: u64) -> Option<u64> {
+    // When every position could hold any of the same cards, as in a pile whose
+    // order is lost, the count is a falling factorial.
+    let sorted = |options: &[Card]| {
+        let mut options = options.to_vec();
+        options.sort_by_key(Card::index);
+        options
+    };
+    let first = sorted(&group[0].options);
+    if group.iter().all(|slot| sorted(&slot.options) == first) {
+        let available = first.len() as u64;
+        return (0..group.len() as u64).try_fold(1u64, |count, i| {
+            count
+                .checked_mul(available.saturating_sub(i))
+                .filter(|&count| count <= limit)
+        });
+    }
+
+    let mut count = 0u64;
+    visit(group, deck, used, &mut |_| {
+        count += 1;
+        count > limit
+    });
+    (count <= limit).then_some(count)
+}
+
+/// Fills the free positions of `deck` with every combination of distinct cards not
+/// already in `used`, calling `f` with each completed deck. Stops early and returns
+/// true as soon as `f` returns true.
+fn visit(free: &[Free], deck: &mut Deck, used: CardSet, f: &mut dyn FnMut(&Deck) -> bool) -> bool {
+    let Some((slot, rest)) = free.split_first() else {
+        return f(deck);
+    };
+    for &card in &slot.options {
+        let mut used = used;
+        if !used.insert(card) {
+            continue;
+        }
+        deck.cards[slot.position] = card;
+        if visit(rest, deck, used, f) {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slots(s: &str) -> Vec<Slot> {
+        s.split_whitespace().map(|s| s.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn parse_slots() {
+        assert_eq!(
+            slots("AS ? 7H/7D"),
+            vec![
+                Slot::Known("AS".parse().unwrap()),
+                Slot::Unknown,
+                Slot::OneOf(vec!["7H".parse().unwrap(), "7D".parse().unwrap()]),
+            ]
+        );
+        assert!("7H/XX".parse::<Slot>().is_err());
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn recover_hash() {
+        let deck = Deck::new().shuffle();
+        let options = HashOptions {
+            iterations: 1,
+            ..HashOptions::default()
+        };
+        let target = Target::Hash {
+            hash: deck.hash_with(Some("pw"), &options),
+            password: Some(String::from("pw")),
+            options,
+        };
+
+        let mut slots: Vec<Slot> = deck.cards.iter().map(|&c| Slot::Known(c)).collect();
+        slots[0] = Slot::Unknown;
+        slots[10] = Slot::Unknown;
+        slots[51] = Slot::OneOf(vec![deck.cards[0], deck.cards[51], deck.cards[10]]);
+        let search = Search::new(slots, target);
+        assert_eq!(search.count(), Ok(6));
+
+        let calls = AtomicU64::new(0);
+        let recovered = search.run(|done, total| {
+            assert!(done <= total && total == 6);
+            calls.fetch_add(1, Ordering::Relaxed);
+        });
+        assert_eq!(recovered, Ok(Some(deck)));
+        assert!(calls.load(Ordering::Relaxed) >= 1);
+    }
+
+    #[test]
+    fn recover_partial() {
+        let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+        let search = Search::new(
+            slots("AS ?? KC 3C/3S"),
+            Target::Fingerprint(deck.fingerprint64()),
+        );
+        assert_eq!(search.count(), Ok(50 * 2 - 2));
+        assert_eq!(search.run(|_, _| {}), Ok(Some(deck)));
+
+        let missing = Search::new(slots("AS KC"), Target::Fingerprint(0));
+        assert_eq!(missing.run(|_, _| {}), Ok(None));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn placements() {
+        let deck = Deck::new().shuffle();
+        let mut known = Deck {
+            cards: deck.cards[..50].to_vec(),
+        };
+
+        // Every placement of the two missing cards is consistent without any targets.
+        let all = super::placements(&known, &[]).unwrap();
+        assert_eq!(all.len(), 52 * 51);
+        assert_eq!(all[0].positions, vec![50, 51]);
+        assert!(all.iter().all(|p| p.deck.validate().is_ok()));
+
+        let found = super::placements(&known, &[Target::Fingerprint(deck.fingerprint64())]);
+        assert_eq!(
+            found,
+            Ok(vec![Placement {
+                deck: deck.clone(),
+                positions: vec![50, 51],
+            }])
+        );
+
+        let checkword = Target::Checkword {
+            word: deck.checkword(None).to_uppercase(),
+            password: None,
+        };
+        known.cards = deck.cards.clone();
+        known.cards.remove(20);
+        let found = super::placements(&known, &[checkword]).unwrap();
+        assert!(found.iter().any(|p| p.deck == deck));
+
+        let mut duplicated = deck.clone();
+        duplicated.cards[1] = duplicated.cards[0];
+        assert_eq!(
+            super::placements(&duplicated, &[]),
+            Err(errors::RecoveryError::Duplicate(deck.cards[0]))
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn piles() {
+        let deck = Deck::new().shuffle();
+        let piles: Vec<Pile> = deck
+            .cards
+            .chunks(13)
+            .map(|chunk| Pile {
+                cards: chunk.to_vec(),
+                known: chunk[4..]
+                    .iter()
+                    .copied()
+                    .enumerate()
+                    .map(|(i, c)| (i + 4, c))
+                    .collect(),
+            })
+            .collect();
+        let target = Target::Fingerprint(deck.fingerprint64());
+        let search = Search::from_piles(&piles, target.clone()).unwrap();
+        assert_eq!(search.count(), Ok(24 * 24 * 24 * 24));
+        assert_eq!(search.run(|_, _| {}), Ok(Some(deck.clone())));
+
+        // A pile with no known order is counted without enumerating it.
+        let unordered = [Pile {
+            cards: deck.cards.clone(),
+            known: vec![],
+        }];
+        let search = Search::from_piles(&unordered, target.clone()).unwrap();
+        assert!(matches!(
+            search.estimate(),
+            Err(errors::RecoveryError::TooManyCandidates { .. })
+        ));
+
+        let mut invalid = piles.clone();
+        invalid[2].known.push((0, deck.cards[0]));
+        assert_eq!(
+            Search::from_piles(&invalid, target),
+            Err(errors::RecoveryError::InvalidPile { pile: 2 })
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let search = Search::new(slots("AS ?? AS"), Target::Fingerprint(0));
+        assert_eq!(
+            search.count(),
+            Err(errors::RecoveryError::Duplicate("AS".parse().unwrap()))
+        );
+
+        let mut search = Search::new(slots("?? ?? ??"), Target::Fingerprint(0));
+        assert_eq!(search.count(), Ok(52 * 51 * 50));
+        search.max_candidates = 1000;
+        assert_eq!(
+            search.run(|_, _| {}),
+            Err(errors::RecoveryError::TooManyCandidates { limit: 1000 })
+        );
+    }
+}
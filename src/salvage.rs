@@ -0,0 +1,150 @@
+use crate::card::Card;
+use crate::deck::Deck;
+use std::ops::Range;
+
+/// Two-letter card tokens which are also common English words, and so are only
+/// trusted as cards when written in uppercase.
+const WORD_TOKENS: [&str; 3] = ["as", "ad", "ah"];
+
+/// The most words `Card::parse_words` reads for a single card, as in `"queen of hearts"`.
+const MAX_CARD_WORDS: usize = 3;
+
+/// The cards recovered from noisy text by `Deck::salvage`, with the parts of the
+/// text which weren't read as cards. Spans are byte ranges into the salvaged text.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SalvageReport {
+    /// The cards recognized in the text, in order.
+    pub deck: Deck,
+    /// Spans of consecutive words which aren't cards and were skipped.
+    pub skipped: Vec<Range<usize>>,
+    /// Spans which could be read as a card but are more likely ordinary words, such
+    /// as `"as"` or `"a heart"`. These are left out of the deck.
+    pub ambiguous: Vec<Range<usize>>,
+}
+
+impl SalvageReport {
+    /// Returns true if every word of the text was read as a card.
+    pub fn is_clean(&self) -> bool {
+        self.skipped.is_empty() && self.ambiguous.is_empty()
+    }
+}
+
+impl Deck {
+    /// Scans arbitrary text, such as OCR output or a chat log, for cards in any form
+    /// `Card::parse_lenient` accepts, skipping whatever else is in the way rather than
+    /// failing at the first word which isn't a card.
+    ///
+    /// The text is split into words at every character which isn't a letter or a
+    /// digit. Words which aren't part of a card are reported as skipped, and cards
+    /// which are likely ordinary English, such as a lowercase `"as"` or the article
+    /// `"a"` followed by a suit name as in `"a heart"`, are reported as ambiguous and
+    /// left out. Like `Deck::from_str`, this doesn't check for duplicates.
+    ///
+    /// ```
+    /// use cardseed::Deck;
+    ///
+    /// let text = "Deck: AS, 2S; 3$ 4S\nqueen of hearts as well";
+    /// let report = Deck::salvage(text);
+    /// assert_eq!(report.deck.to_string(), "AS 2S 4S QH");
+    /// assert_eq!(&text[report.skipped[0].clone()], "Deck");
+    /// assert_eq!(&text[report.skipped[1].clone()], "3");
+    /// assert_eq!(&text[report.ambiguous[0].clone()], "as");
+    /// ```
+    pub fn salvage(s: &str) -> SalvageReport {
+        let words = split_words(s);
+        let mut report = SalvageReport {
+            deck: Deck { cards: vec![] },
+            skipped: vec![],
+            ambiguous: vec![],
+        };
+        let mut i = 0;
+        while i < words.len() {
+            let window: Vec<&str> = words[i..]
+                .iter()
+                .take(MAX_CARD_WORDS)
+                .map(|&(_, word)| word)
+                .collect();
+            let Some((card, used)) = Card::parse_words(&window) else {
+                let range = words[i].0.clone();
+                match report.skipped.last_mut() {
+                    Some(last) if i > 0 && last.end == words[i - 1].0.end => last.end = range.end,
+                    _ => report.skipped.push(range),
+                }
+                i += 1;
+                continue;
+            };
+
+            let span = words[i].0.start..words[i + used - 1].0.end;
+            if is_ambiguous(&window[..used]) {
+                report.ambiguous.push(span);
+            } else {
+                report.deck.cards.push(card);
+            }
+            i += used;
+        }
+        report
+    }
+}
+
+/// Splits `s` into words of letters and digits, with their byte ranges.
+fn split_words(s: &str) -> Vec<(Range<usize>, &str)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in s.char_indices().chain([(s.len(), ' ')]) {
+        match (start, c.is_alphanumeric()) {
+            (None, true) => start = Some(i),
+            (Some(begin), false) => {
+                words.push((begin..i, &s[begin..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+/// Returns true if the words of a card are more likely ordinary English.
+fn is_ambiguous(words: &[&str]) -> bool {
+    match words {
+        [word] => {
+            WORD_TOKENS.iter().any(|w| w.eq_ignore_ascii_case(word))
+                && *word != word.to_ascii_uppercase()
+        }
+        // "a heart" reads as the English article, but "A of hearts" is an ace.
+        [rank, ..] => *rank == "a",
+        [] => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn salvage() {
+        let deck = Deck::new();
+        let report = Deck::salvage(&deck.to_string());
+        assert!(report.is_clean());
+        assert_eq!(report.deck, deck);
+        assert_eq!(Deck::salvage("").deck, Deck { cards: vec![] });
+
+        let text = "ok: AS, Ace of Spades!! 10h (a heart) ~~~ KD As ad? 7 of clubs.";
+        let report = Deck::salvage(text);
+        assert_eq!(report.deck.to_string(), "AS AS TH KD 7C");
+        let spans = |ranges: &[Range<usize>]| -> Vec<&str> {
+            ranges.iter().map(|range| &text[range.clone()]).collect()
+        };
+        assert_eq!(spans(&report.skipped), ["ok"]);
+        assert_eq!(spans(&report.ambiguous), ["a heart", "As", "ad"]);
+
+        let report = Deck::salvage("K of hearts, J of clubs, Q spades, A of diamonds, 7 of clubs");
+        assert_eq!(report.deck.to_string(), "KH JC QS AD 7C");
+        assert!(report.ambiguous.is_empty());
+
+        // Consecutive skipped words are reported as one span.
+        let report = Deck::salvage("2C the quick brown fox 9H");
+        assert_eq!(report.deck.to_string(), "2C 9H");
+        assert_eq!(report.skipped, vec![3..22]);
+        assert!(!report.is_clean());
+    }
+}
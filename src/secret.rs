@@ -0,0 +1,338 @@
+//! [Shamir secret sharing](https://en.wikipedia.org/wiki/Shamir%27s_secret_sharing)
+//! of arbitrary bytes over GF(256), for splitting a deck-derived secret into
+//! `n` shares of which any `k` can recover it.
+//!
+//! ```
+//! use cardseed::{secret, Deck};
+//!
+//! let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+//! # #[cfg(feature = "rand")] {
+//! let shares = deck.split_secret(Some("bestpasswordever"), 2, 3).unwrap();
+//! let recovered = secret::combine(&shares[1..]).unwrap();
+//! assert_eq!(recovered, deck.hash(Some("bestpasswordever")));
+//! # }
+//! ```
+
+use crate::deck::Deck;
+use crate::errors;
+#[cfg(feature = "rand")]
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// The number of bytes of SHA-256 checksum appended to each serialized share, and
+/// to the secret before it is split.
+const CHECKSUM_SIZE: usize = 4;
+
+/// One share of a secret split by `split`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Share {
+    /// The number of shares needed to recover the secret.
+    pub threshold: u8,
+    /// The share's x-coordinate, from 1 to 255. Each share of a secret has a distinct index.
+    pub index: u8,
+    /// The share's y-coordinates: one byte per byte of the secret and its checksum.
+    pub data: Vec<u8>,
+}
+
+impl Share {
+    /// Serializes the share as its threshold, index, and data, followed by a
+    /// 4-byte checksum which detects corrupted or mistranscribed shares.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(2 + self.data.len() + CHECKSUM_SIZE);
+        bytes.push(self.threshold);
+        bytes.push(self.index);
+        bytes.extend_from_slice(&self.data);
+        let checksum = Sha256::digest(&bytes);
+        bytes.extend_from_slice(&checksum[..CHECKSUM_SIZE]);
+        bytes
+    }
+
+    /// Parses a share serialized by `Share::to_bytes`. Returns an `Err` if the
+    /// bytes are too short or the checksum does not match.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Share, errors::ShareError> {
+        if bytes.len() < 2 + CHECKSUM_SIZE {
+            return Err(errors::ShareError::BadChecksum);
+        }
+        let (body, checksum) = bytes.split_at(bytes.len() - CHECKSUM_SIZE);
+        if Sha256::digest(body)[..CHECKSUM_SIZE] != *checksum {
+            return Err(errors::ShareError::BadChecksum);
+        }
+        Ok(Share {
+            threshold: body[0],
+            index: body[1],
+            data: body[2..].to_vec(),
+        })
+    }
+}
+
+impl fmt::Display for Share {
+    /// Formats the share as the hex encoding of `Share::to_bytes`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+impl std::str::FromStr for Share {
+    type Err = errors::ShareError;
+
+    /// Parses a share from the hex string produced by its `Display` implementation.
+    fn from_str(s: &str) -> Result<Share, errors::ShareError> {
+        let bytes = hex::decode(s).map_err(|_| errors::ShareError::BadChecksum)?;
+        Share::from_bytes(&bytes)
+    }
+}
+
+/// Splits `secret` into `n` shares, any `k` of which can recover it with `combine`.
+/// A 4-byte checksum of the secret is appended before splitting, so that `combine`
+/// can detect shares from different secrets. Returns an `Err` unless
+/// `1 <= k <= n <= 255`.
+#[cfg(feature = "rand")]
+pub fn split(secret: &[u8], k: u8, n: u8) -> Result<Vec<Share>, errors::ShareError> {
+    split_with_rng(secret, k, n, &mut rand::rngs::OsRng)
+}
+
+/// Splits `secret` into shares like `split`, drawing the random coefficients from
+/// the given secure random number generator rather than the operating system's.
+#[cfg(feature = "rand")]
+pub fn split_with_rng<R: CryptoRng + RngCore + ?Sized>(
+    secret: &[u8],
+    k: u8,
+    n: u8,
+    rng: &mut R,
+) -> Result<Vec<Share>, errors::ShareError> {
+    if k == 0 || k > n {
+        return Err(errors::ShareError::BadThreshold {
+            threshold: k,
+            shares: n,
+        });
+    }
+
+    let mut padded = secret.to_vec();
+    padded.extend_from_slice(&Sha256::digest(secret)[..CHECKSUM_SIZE]);
+
+    let mut shares: Vec<Share> = (1..=n)
+        .map(|index| Share {
+            threshold: k,
+            index,
+            data: Vec::with_capacity(padded.len()),
+        })
+        .collect();
+
+    // Each byte of the secret is the constant term of its own random polynomial
+    // of degree k - 1, which is evaluated at each share's index.
+    let mut coefficients = vec![0u8; k as usize];
+    for &byte in padded.iter() {
+        coefficients[0] = byte;
+        rng.fill_bytes(&mut coefficients[1..]);
+        for share in shares.iter_mut() {
+            let y = coefficients
+                .iter()
+                .rev()
+                .fold(0, |acc, &c| gf_mul(acc, share.index) ^ c);
+            share.data.push(y);
+        }
+    }
+    coefficients.fill(0);
+    padded.fill(0);
+
+    Ok(shares)
+}
+
+/// Recovers a secret from shares produced by `split`. Only the first `threshold`
+/// shares are used. Returns an `Err` if there are too few shares, if they are
+/// inconsistent, or if the recovered secret does not match its checksum.
+pub fn combine(shares: &[Share]) -> Result<Vec<u8>, errors::ShareError> {
+    let first = shares.first().ok_or(errors::ShareError::NotEnoughShares {
+        found: 0,
+        required: 1,
+    })?;
+    let threshold = first.threshold as usize;
+    if shares.len() < threshold {
+        return Err(errors::ShareError::NotEnoughShares {
+            found: shares.len(),
+            required: threshold,
+        });
+    }
+    let shares = &shares[..threshold];
+
+    for (i, share) in shares.iter().enumerate() {
+        if share.threshold != first.threshold
+            || share.data.len() != first.data.len()
+            || share.index == 0
+        {
+            return Err(errors::ShareError::Mismatched);
+        }
+        if shares[..i].iter().any(|other| other.index == share.index) {
+            return Err(errors::ShareError::DuplicateShare(share.index));
+        }
+    }
+    if first.data.len() < CHECKSUM_SIZE {
+        return Err(errors::ShareError::BadChecksum);
+    }
+
+    // Lagrange interpolation at x = 0.
+    let weights: Vec<u8> = shares
+        .iter()
+        .map(|share| {
+            shares
+                .iter()
+                .filter(|other| other.index != share.index)
+                .fold(1, |acc, other| {
+                    gf_mul(acc, gf_div(other.index, other.index ^ share.index))
+                })
+        })
+        .collect();
+
+    let mut padded: Vec<u8> = (0..first.data.len())
+        .map(|i| {
+            shares
+                .iter()
+                .zip(weights.iter())
+                .fold(0, |acc, (share, &w)| acc ^ gf_mul(share.data[i], w))
+        })
+        .collect();
+
+    let secret_len = padded.len() - CHECKSUM_SIZE;
+    if Sha256::digest(&padded[..secret_len])[..CHECKSUM_SIZE] != padded[secret_len..] {
+        padded.fill(0);
+        return Err(errors::ShareError::BadChecksum);
+    }
+    padded.truncate(secret_len);
+    Ok(padded)
+}
+
+/// Multiplies two elements of GF(256), using the AES reduction polynomial.
+pub(crate) fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = 0u8.wrapping_sub(a >> 7);
+        a = (a << 1) ^ (carry & 0x1b);
+        b >>= 1;
+    }
+    product
+}
+
+/// Divides two elements of GF(256), by multiplying by the inverse `b^254`. The
+/// divisor must not be zero.
+pub(crate) fn gf_div(a: u8, b: u8) -> u8 {
+    let mut inverse = 1;
+    let mut power = b;
+    let mut exponent = 254u8;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            inverse = gf_mul(inverse, power);
+        }
+        power = gf_mul(power, power);
+        exponent >>= 1;
+    }
+    gf_mul(a, inverse)
+}
+
+impl Deck {
+    /// Derives a secret from the `Deck` and an optional password with `Deck::hash`,
+    /// and splits it into `n` shares, any `k` of which can recover it with
+    /// `secret::combine`.
+    #[cfg(feature = "rand")]
+    pub fn split_secret(
+        &self,
+        password: Option<&str>,
+        k: u8,
+        n: u8,
+    ) -> Result<Vec<Share>, errors::ShareError> {
+        split(&self.hash(password), k, n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field() {
+        // Examples from FIPS 197, section 4.2.
+        assert_eq!(gf_mul(0x57, 0x83), 0xc1);
+        assert_eq!(gf_mul(0x57, 0x13), 0xfe);
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(a, gf_div(1, a)), 1);
+            assert_eq!(gf_div(gf_mul(a, 0x53), 0x53), a);
+        }
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn split_combine() {
+        let secret = b"correct horse battery staple";
+        let shares = split(secret, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+        assert!(shares.iter().all(|s| s.data.len() == secret.len() + 4));
+
+        for combination in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let subset: Vec<Share> = combination.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(combine(&subset).unwrap(), secret);
+        }
+        assert_eq!(
+            combine(&shares[..2]),
+            Err(errors::ShareError::NotEnoughShares {
+                found: 2,
+                required: 3
+            })
+        );
+
+        let single = split(secret, 1, 1).unwrap();
+        assert_eq!(single[0].data[..secret.len()], secret[..]);
+        assert_eq!(combine(&single).unwrap(), secret);
+
+        use rand::SeedableRng;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(3);
+        let shares = split_with_rng(secret, 2, 3, &mut rng).unwrap();
+        assert_eq!(combine(&shares[1..]).unwrap(), secret);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn errors() {
+        assert_eq!(
+            split(b"x", 3, 2),
+            Err(errors::ShareError::BadThreshold {
+                threshold: 3,
+                shares: 2
+            })
+        );
+        assert!(split(b"x", 0, 2).is_err());
+        assert!(combine(&[]).is_err());
+
+        let shares = split(b"secret", 2, 3).unwrap();
+        assert_eq!(
+            combine(&[shares[0].clone(), shares[0].clone()]),
+            Err(errors::ShareError::DuplicateShare(1))
+        );
+
+        let other = split(b"secres", 2, 3).unwrap();
+        assert_eq!(
+            combine(&[shares[0].clone(), other[1].clone()]),
+            Err(errors::ShareError::BadChecksum)
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn serialization() {
+        let shares = Deck::new().split_secret(None, 2, 3).unwrap();
+        let parsed: Vec<Share> = shares
+            .iter()
+            .map(|s| s.to_string().parse::<Share>().unwrap())
+            .collect();
+        assert_eq!(parsed, shares);
+        assert_eq!(combine(&parsed).unwrap(), Deck::new().hash(None));
+
+        let mut bytes = shares[0].to_bytes();
+        bytes[5] ^= 1;
+        assert_eq!(
+            Share::from_bytes(&bytes),
+            Err(errors::ShareError::BadChecksum)
+        );
+        assert!("zz".parse::<Share>().is_err());
+    }
+}
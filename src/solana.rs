@@ -0,0 +1,158 @@
+use crate::deck::Deck;
+use crate::errors;
+use ed25519_dalek::SigningKey;
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use zeroize::Zeroizing;
+
+/// The HMAC key used to derive a SLIP-10 ed25519 master key from a seed.
+const SLIP10_ED25519_KEY: &[u8] = b"ed25519 seed";
+
+/// An ed25519 keypair derived from a `Deck`, for use as a Solana account.
+#[derive(Clone, Debug)]
+pub struct SolanaKeypair {
+    /// The derived signing key.
+    pub signing_key: SigningKey,
+}
+
+impl SolanaKeypair {
+    /// Returns the 32-byte public key.
+    pub fn pubkey(&self) -> [u8; 32] {
+        self.signing_key.verifying_key().to_bytes()
+    }
+
+    /// Returns the account address: the base58-encoded public key.
+    pub fn address(&self) -> String {
+        bs58::encode(self.pubkey()).into_string()
+    }
+
+    /// Returns the 64-byte keypair, the secret key followed by the public key, as
+    /// stored in the JSON keypair files of the Solana CLI.
+    pub fn to_bytes(&self) -> [u8; 64] {
+        self.signing_key.to_keypair_bytes()
+    }
+}
+
+/// Derives an ed25519 private key from a seed along a path of hardened indexes, as
+/// specified by [SLIP-10](https://github.com/satoshilabs/slips/blob/master/slip-0010.md).
+/// Each index must be below 2^31, and is hardened by setting its top bit.
+fn slip10_ed25519(seed: &[u8], path: &[u32]) -> Zeroizing<[u8; 32]> {
+    let hmac = |key: &[u8], parts: &[&[u8]]| {
+        let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        for part in parts {
+            mac.update(part);
+        }
+        let output = Zeroizing::new(<[u8; 64]>::from(mac.finalize().into_bytes()));
+        let mut key = Zeroizing::new([0u8; 32]);
+        let mut chain_code = Zeroizing::new([0u8; 32]);
+        key.copy_from_slice(&output[..32]);
+        chain_code.copy_from_slice(&output[32..]);
+        (key, chain_code)
+    };
+
+    let (mut key, mut chain_code) = hmac(SLIP10_ED25519_KEY, &[seed]);
+    for &index in path {
+        let hardened = (index | 1 << 31).to_be_bytes();
+        (key, chain_code) = hmac(chain_code.as_slice(), &[&[0], key.as_slice(), &hardened]);
+    }
+    key
+}
+
+impl Deck {
+    /// Derives a Solana keypair from the `Deck` and an optional password. The BIP39 seed
+    /// of `Deck::mnemonic` with an empty passphrase is used as a seed, from which the
+    /// key is derived with SLIP-10 along the path `m/44'/501'/account'/0'`. The account
+    /// therefore matches the one Phantom and other Solana wallets derive when the
+    /// mnemonic is imported.
+    ///
+    /// Returns an `Err` if `account` is 2^31 or more, since hardening it would alias
+    /// a smaller account.
+    ///
+    /// ```
+    /// use cardseed::Deck;
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let keypair = deck.solana_keypair(Some("bestpasswordever"), 0).unwrap();
+    /// let other = deck.solana_keypair(Some("bestpasswordever"), 1).unwrap();
+    /// assert_ne!(keypair.address(), other.address());
+    /// ```
+    pub fn solana_keypair(
+        &self,
+        password: Option<&str>,
+        account: u32,
+    ) -> Result<SolanaKeypair, errors::DerivationError> {
+        if account >= 1 << 31 {
+            return Err(errors::DerivationError::IndexTooLarge(account));
+        }
+        let seed = Zeroizing::new(self.mnemonic(password).to_seed(""));
+        let key = slip10_ed25519(seed.as_slice(), &[44, 501, account, 0]);
+        Ok(SolanaKeypair {
+            signing_key: SigningKey::from_bytes(&key),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slip10() {
+        // Test vector 1 for ed25519 from SLIP-10.
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        assert_eq!(
+            hex::encode(*slip10_ed25519(&seed, &[])),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+        let key = slip10_ed25519(&seed, &[0]);
+        assert_eq!(
+            hex::encode(*key),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+        assert_eq!(
+            hex::encode(SigningKey::from_bytes(&key).verifying_key().to_bytes()),
+            "8c8a13df77a28f3445213a0f432fde644acaa215fc72dcdf300d5efaa85d350c"
+        );
+    }
+
+    #[test]
+    fn mnemonic_vector() {
+        // The first account of the mnemonic "abandon abandon ... about", as derived by
+        // Phantom and `solana-keygen recover`.
+        let words = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let seed = bip39::Mnemonic::parse(words).unwrap().to_seed("");
+        let key = slip10_ed25519(&seed, &[44, 501, 0, 0]);
+        let keypair = SolanaKeypair {
+            signing_key: SigningKey::from_bytes(&key),
+        };
+        assert_eq!(
+            keypair.address(),
+            "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk"
+        );
+    }
+
+    #[test]
+    fn solana_keypair() {
+        let keypair = Deck::new().solana_keypair(None, 0).unwrap();
+        let bytes = keypair.to_bytes();
+        assert_eq!(bytes[32..], keypair.pubkey());
+        assert_eq!(
+            bs58::decode(keypair.address()).into_vec().unwrap(),
+            keypair.pubkey()
+        );
+        assert_eq!(
+            keypair.pubkey(),
+            Deck::new().solana_keypair(None, 0).unwrap().pubkey()
+        );
+    }
+
+    #[test]
+    fn account_too_large() {
+        for account in [1 << 31, u32::MAX] {
+            assert!(matches!(
+                Deck::new().solana_keypair(None, account),
+                Err(errors::DerivationError::IndexTooLarge(a)) if a == account
+            ));
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::errors;
+use crate::rank::ALL_RANKS;
+use crate::suit::ALL_SUITS;
+use std::fmt;
+
+/// The subject of each sentence, by rank index. Each begins with the same letter as
+/// its rank's name, as a memory aid.
+pub(crate) const CHARACTERS: [&str; 13] = [
+    "astronaut",
+    "tailor",
+    "thief",
+    "farmer",
+    "fisherman",
+    "sailor",
+    "senator",
+    "engineer",
+    "nurse",
+    "teacher",
+    "judge",
+    "queen",
+    "king",
+];
+
+/// The verb of each sentence, by suit index.
+const VERBS: [&str; 4] = ["stole", "carried", "hid", "dropped"];
+
+/// The object of each sentence, by rank index.
+pub(crate) const OBJECTS: [&str; 13] = [
+    "apple", "trumpet", "thimble", "feather", "fiddle", "saddle", "sword", "egg", "necklace",
+    "teapot", "jar", "quilt", "kettle",
+];
+
+/// The place where each sentence happens, by suit index.
+const PLACES: [&str; 4] = ["snow", "cellar", "harbor", "desert"];
+
+/// Formats a `Deck` as a short story which is easier to remember, or to hide in
+/// plain sight, than a list of cards. Returned by `Deck::story`, and read back by
+/// `Deck::parse_story`.
+///
+/// Each sentence holds two cards. The rank and suit of the first card pick who
+/// acts and what they do, and those of the second pick what they act on and where:
+///
+/// ```text
+/// The thief stole a feather in the desert.
+/// ```
+///
+/// reads as `3S 4D`. The words for each rank begin with the same letter as its
+/// name, as do the words for each suit. A deck with an odd number of cards ends
+/// with a sentence such as `"The nurse hid nothing."` for a lone `9H`.
+#[derive(Clone, Copy, Debug)]
+pub struct Story<'a>(&'a Deck);
+
+impl Deck {
+    /// Returns a wrapper which displays the `Deck` as a story of one sentence for
+    /// every two cards.
+    ///
+    /// ```
+    /// use cardseed::Deck;
+    ///
+    /// let deck = "3S 4D KC AH 9H".parse::<Deck>().unwrap();
+    /// let story = deck.story().to_string();
+    /// assert_eq!(
+    ///     story,
+    ///     "The thief stole a feather in the desert. \
+    ///      The king carried an apple in the harbor. \
+    ///      The nurse hid nothing."
+    /// );
+    /// assert_eq!(Deck::parse_story(&story), Ok(deck));
+    /// ```
+    pub fn story(&self) -> Story<'_> {
+        Story(self)
+    }
+
+    /// Parses a `Deck` from a story written by `Deck::story`. Case, and the spacing
+    /// and line breaks between words and sentences, are ignored, so the story may be
+    /// written out however is easiest to remember. Every other word must match
+    /// exactly, so that a misremembered story is never read as the wrong deck.
+    ///
+    /// Returns `DeckError::UnknownCard` at the position of the first card of any
+    /// sentence which doesn't fit the pattern. Like `Deck::from_str`, this doesn't
+    /// check for duplicates.
+    pub fn parse_story(s: &str) -> Result<Deck, errors::DeckError> {
+        let mut deck = Deck { cards: vec![] };
+        let sentences: Vec<&str> = s
+            .split('.')
+            .map(str::trim)
+            .filter(|sentence| !sentence.is_empty())
+            .collect();
+        for (i, sentence) in sentences.iter().enumerate() {
+            let malformed = || errors::DeckError::UnknownCard {
+                position: deck.cards.len(),
+                source: errors::ParseError::Malformed(sentence.to_string()),
+            };
+            let lowercase = sentence.to_lowercase();
+            let words: Vec<&str> = lowercase.split_whitespace().collect();
+            let (first, second) = match words[..] {
+                ["the", character, verb, "nothing"] if i == sentences.len() - 1 => {
+                    (card_from_words(character, verb, &CHARACTERS, &VERBS), None)
+                }
+                ["the", character, verb, article, object, "in", "the", place]
+                    if article == article_for(object) =>
+                {
+                    (
+                        card_from_words(character, verb, &CHARACTERS, &VERBS),
+                        Some(card_from_words(object, place, &OBJECTS, &PLACES)),
+                    )
+                }
+                _ => return Err(malformed()),
+            };
+
+            let first = first.ok_or_else(malformed)?;
+            match second {
+                Some(second) => {
+                    let second = second.ok_or_else(malformed)?;
+                    deck.cards.extend([first, second]);
+                }
+                None => deck.cards.push(first),
+            }
+        }
+        Ok(deck)
+    }
+}
+
+/// Looks up the card named by a rank word and a suit word in the given tables.
+fn card_from_words(
+    rank_word: &str,
+    suit_word: &str,
+    ranks: &[&str; 13],
+    suits: &[&str; 4],
+) -> Option<Card> {
+    let rank = ranks.iter().position(|&w| w == rank_word)?;
+    let suit = suits.iter().position(|&w| w == suit_word)?;
+    Some(Card::from_rank(ALL_RANKS[rank], ALL_SUITS[suit]))
+}
+
+/// Returns the indefinite article for an object.
+fn article_for(object: &str) -> &'static str {
+    match object.chars().next() {
+        Some('a' | 'e' | 'i' | 'o' | 'u') => "an",
+        _ => "a",
+    }
+}
+
+impl fmt::Display for Story<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, pair) in self.0.cards.chunks(2).enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            let first = pair[0];
+            write!(
+                f,
+                "The {} {}",
+                CHARACTERS[first.rank().index() as usize],
+                VERBS[first.suit().index() as usize]
+            )?;
+            match pair.get(1) {
+                Some(second) => {
+                    let object = OBJECTS[second.rank().index() as usize];
+                    let place = PLACES[second.suit().index() as usize];
+                    write!(f, " {} {object} in the {place}.", article_for(object))?;
+                }
+                None => f.write_str(" nothing.")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn story() {
+        let deck = Deck::new();
+        let story = deck.story().to_string();
+        assert!(story.starts_with("The astronaut stole a trumpet in the snow. "));
+        assert_eq!(story.matches('.').count(), 26);
+        assert_eq!(Deck::parse_story(&story), Ok(deck));
+
+        for len in [0, 1, 2, 51] {
+            let deck = Deck {
+                cards: Deck::new().cards.into_iter().rev().take(len).collect(),
+            };
+            assert_eq!(Deck::parse_story(&deck.story().to_string()), Ok(deck));
+        }
+        assert_eq!(Deck { cards: vec![] }.story().to_string(), "");
+    }
+
+    #[test]
+    fn parse_story() {
+        let expected = "JS 8H".parse::<Deck>().unwrap();
+        assert_eq!(
+            Deck::parse_story("the JUDGE stole\nan  Egg in the harbor"),
+            Ok(expected)
+        );
+
+        let malformed = |position: usize, sentence: &str| {
+            Err(errors::DeckError::UnknownCard {
+                position,
+                source: errors::ParseError::Malformed(sentence.into()),
+            })
+        };
+        for sentence in [
+            "The judge stole a egg in the harbor",
+            "The judge sold an egg in the harbor",
+            "The judge stole an eagle in the harbor",
+            "The apple stole an egg in the harbor",
+            "The apple snow an egg in the harbor",
+            "The judge stole an judge in the stole",
+            "The judge stole an egg in the harbour",
+            "The judge stole an egg",
+        ] {
+            let story = format!("The thief stole a feather in the desert. {sentence}.");
+            assert_eq!(Deck::parse_story(&story), malformed(2, sentence));
+        }
+        assert_eq!(
+            Deck::parse_story("The nurse hid nothing. The nurse hid nothing."),
+            malformed(0, "The nurse hid nothing")
+        );
+    }
+
+    #[test]
+    fn words() {
+        for table in [&CHARACTERS[..], &VERBS[..], &OBJECTS[..], &PLACES[..]] {
+            for (i, word) in table.iter().enumerate() {
+                assert!(!table[..i].contains(word));
+                assert!(word.chars().all(|c| c.is_ascii_lowercase()));
+            }
+        }
+        for (rank, (character, object)) in ALL_RANKS.iter().zip(CHARACTERS.iter().zip(OBJECTS)) {
+            let initial = rank.name().chars().next().unwrap().to_ascii_lowercase();
+            assert!(character.starts_with(initial));
+            assert!(object.starts_with(initial));
+        }
+        for (suit, (verb, place)) in ALL_SUITS.iter().zip(VERBS.iter().zip(PLACES)) {
+            let initial = suit.name().chars().next().unwrap().to_ascii_lowercase();
+            assert!(verb.starts_with(initial));
+            assert!(place.starts_with(initial));
+        }
+    }
+}
@@ -1,8 +1,13 @@
 use crate::errors;
 use std::fmt;
+use std::ops::RangeBounds;
 
-/// Represents a playing card suit.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+/// Every suit, in order of index.
+pub(crate) const ALL_SUITS: [Suit; 4] = [Suit::Spades, Suit::Clubs, Suit::Hearts, Suit::Diamonds];
+
+/// Represents a playing card suit. Suits are ordered by index: spades, clubs,
+/// hearts, then diamonds.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum Suit {
     Spades,
     Clubs,
@@ -12,12 +17,7 @@ pub enum Suit {
 
 impl From<Suit> for u32 {
     fn from(suit: Suit) -> u32 {
-        match suit {
-            Suit::Spades => 0,
-            Suit::Clubs => 1,
-            Suit::Hearts => 2,
-            Suit::Diamonds => 3,
-        }
+        suit.index()
     }
 }
 
@@ -25,13 +25,7 @@ impl TryFrom<u32> for Suit {
     type Error = errors::ParseError;
 
     fn try_from(x: u32) -> Result<Suit, errors::ParseError> {
-        match x {
-            0 => Ok(Suit::Spades),
-            1 => Ok(Suit::Clubs),
-            2 => Ok(Suit::Hearts),
-            3 => Ok(Suit::Diamonds),
-            i => Err(errors::ParseError::BadInt(i)),
-        }
+        Suit::from_index(x)
     }
 }
 
@@ -62,9 +56,80 @@ impl fmt::Display for Suit {
 }
 
 impl Suit {
-    /// Returns the set of all suits.
-    pub fn all() -> [Suit; 4] {
-        [Suit::Spades, Suit::Clubs, Suit::Hearts, Suit::Diamonds]
+    /// Returns an iterator over every suit, in order of index.
+    ///
+    /// ```
+    /// use cardseed::Suit;
+    ///
+    /// let suits: String = Suit::all().map(|s| s.to_string()).collect();
+    /// assert_eq!(suits, "SCHD");
+    /// ```
+    pub fn all() -> std::array::IntoIter<Suit, 4> {
+        ALL_SUITS.into_iter()
+    }
+
+    /// Returns an iterator over the suits within a range, in order of index. Ranges of
+    /// suits can't be iterated directly, since that requires an unstable trait.
+    ///
+    /// ```
+    /// use cardseed::Suit;
+    ///
+    /// let suits: Vec<Suit> = Suit::range(Suit::Clubs..=Suit::Diamonds).collect();
+    /// assert_eq!(suits, [Suit::Clubs, Suit::Hearts, Suit::Diamonds]);
+    /// ```
+    pub fn range(range: impl RangeBounds<Suit>) -> impl Iterator<Item = Suit> {
+        Suit::all().filter(move |suit| range.contains(suit))
+    }
+
+    /// Returns the plural English name of the `Suit`, such as `"Spades"`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Suit::Spades => "Spades",
+            Suit::Clubs => "Clubs",
+            Suit::Hearts => "Hearts",
+            Suit::Diamonds => "Diamonds",
+        }
+    }
+
+    /// Parses a `Suit` from a word, ignoring case: its name in singular or plural,
+    /// such as `"hearts"`, or its card string character, such as `"h"`.
+    pub(crate) fn from_word(word: &str) -> Option<Suit> {
+        let singular = word.strip_suffix(['s', 'S']).unwrap_or(word);
+        Suit::all().find(|s| {
+            let name = s.name();
+            name[..name.len() - 1].eq_ignore_ascii_case(singular)
+                || s.to_string().eq_ignore_ascii_case(word)
+        })
+    }
+
+    /// Returns the index of the `Suit` from 0 to 3. Equivalent to `u32::from`,
+    /// but usable in `const` contexts.
+    ///
+    /// ```
+    /// use cardseed::Suit;
+    ///
+    /// const HEARTS: u32 = Suit::Hearts.index();
+    /// assert_eq!(HEARTS, 2);
+    /// ```
+    pub const fn index(self) -> u32 {
+        match self {
+            Suit::Spades => 0,
+            Suit::Clubs => 1,
+            Suit::Hearts => 2,
+            Suit::Diamonds => 3,
+        }
+    }
+
+    /// Creates a `Suit` from its index from 0 to 3. Equivalent to `Suit::try_from`,
+    /// but usable in `const` contexts.
+    pub const fn from_index(x: u32) -> Result<Suit, errors::ParseError> {
+        match x {
+            0 => Ok(Suit::Spades),
+            1 => Ok(Suit::Clubs),
+            2 => Ok(Suit::Hearts),
+            3 => Ok(Suit::Diamonds),
+            i => Err(errors::ParseError::BadInt(i)),
+        }
     }
 }
 
@@ -81,6 +146,17 @@ mod tests {
         assert_eq!(Suit::try_from(5), Err(errors::ParseError::BadInt(5)));
     }
 
+    #[test]
+    fn order() {
+        assert!(Suit::all().zip(Suit::all().skip(1)).all(|(a, b)| a < b));
+        assert!(
+            Suit::all().all(|s| u32::from(s) == Suit::all().position(|t| t == s).unwrap() as u32)
+        );
+        assert_eq!(Suit::range(..Suit::Hearts).count(), 2);
+        assert_eq!(Suit::range(Suit::Hearts..Suit::Hearts).count(), 0);
+        assert_eq!(Suit::range(..).count(), 4);
+    }
+
     #[test]
     fn to_u32() {
         assert_eq!(u32::from(Suit::Spades), 0);
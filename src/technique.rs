@@ -0,0 +1,264 @@
+use crate::deck::log2_factorial;
+use std::fmt;
+
+/// The number of seconds of washing after which a deck is treated as uniformly
+/// shuffled, following Diaconis' recommendation of one minute of "smooshing".
+const WASH_SECONDS: u32 = 60;
+
+/// A riffle count is recommended once the total variation distance from a
+/// uniformly random order falls below this, as in Bayer and Diaconis' "seven shuffles".
+const VARIATION_THRESHOLD: f64 = 0.5;
+
+/// The most riffle shuffles `ShuffleEstimate` will ever recommend.
+const MAX_RIFFLES: u32 = 64;
+
+/// A technique a person used to shuffle a physical deck of cards.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Technique {
+    /// A riffle shuffle: the deck is cut roughly in half, and the halves are
+    /// interleaved by letting cards drop from each thumb.
+    Riffle,
+    /// An overhand shuffle: small packets are slid from one hand to the other.
+    /// This mixes a deck very slowly, needing thousands of repetitions to
+    /// randomize 52 cards, so it is credited no more than a cut.
+    Overhand,
+    /// A single cut, moving the top part of the deck beneath the bottom part.
+    Cut,
+    /// Washing, or "smooshing": the cards are spread face down on a table and
+    /// mixed by hand for the given number of seconds.
+    Wash { seconds: u32 },
+}
+
+/// Advice for shuffling a deck more thoroughly, returned by `ShuffleEstimate`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Recommendation {
+    /// Riffle the deck at least this many times in total, or wash it for a minute.
+    Riffle { times: u32 },
+}
+
+impl fmt::Display for Recommendation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Recommendation::Riffle { times } => write!(
+                f,
+                "riffle at least {times} times, or wash the cards for {WASH_SECONDS} seconds"
+            ),
+        }
+    }
+}
+
+/// An estimate of the entropy of a deck shuffled by hand, which is usually far
+/// lower than the `log2(52!)` bits of a uniformly random order assumed by
+/// `Deck::entropy_bits`.
+///
+/// Riffle shuffles are modeled exactly using the Gilbert–Shannon–Reeds model
+/// analyzed by [Bayer and Diaconis](https://doi.org/10.1214/aoap/1177005705).
+/// Other techniques are rough heuristics: all cuts and overhand shuffles together
+/// contribute at most `log2(n)` bits, and washing contributes in proportion to
+/// its duration up to one minute, after which the deck is treated as uniform.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShuffleEstimate {
+    /// The estimated bits of entropy in the order of the deck.
+    pub bits: f64,
+    /// The bits of entropy in a uniformly random order of the deck.
+    pub max_bits: f64,
+    /// How to shuffle more thoroughly, if the deck was not shuffled enough.
+    pub recommendation: Option<Recommendation>,
+}
+
+impl ShuffleEstimate {
+    /// Estimates the entropy of a deck of `cards` cards after it was shuffled
+    /// using the given techniques, in any order.
+    ///
+    /// ```
+    /// use cardseed::{ShuffleEstimate, Technique};
+    ///
+    /// let estimate = ShuffleEstimate::new(52, &[Technique::Riffle; 3]);
+    /// assert!(estimate.bits < estimate.max_bits - 50.0);
+    /// assert_eq!(
+    ///     estimate.recommendation.unwrap().to_string(),
+    ///     "riffle at least 7 times, or wash the cards for 60 seconds"
+    /// );
+    ///
+    /// let estimate = ShuffleEstimate::new(52, &[Technique::Riffle; 7]);
+    /// assert!(estimate.bits > 220.0);
+    /// assert_eq!(estimate.recommendation, None);
+    /// ```
+    pub fn new(cards: usize, techniques: &[Technique]) -> ShuffleEstimate {
+        let max_bits = log2_factorial(cards);
+
+        let mut riffles = 0;
+        let mut cut = false;
+        let mut seconds: u32 = 0;
+        for technique in techniques {
+            match technique {
+                Technique::Riffle => riffles += 1,
+                Technique::Overhand | Technique::Cut => cut = true,
+                Technique::Wash { seconds: s } => seconds = seconds.saturating_add(*s),
+            }
+        }
+
+        let riffle = Riffles::new(cards, riffles);
+        let mut bits = riffle.bits;
+        if cut && cards > 1 {
+            bits += (cards as f64).log2();
+        }
+        bits += max_bits * seconds.min(WASH_SECONDS) as f64 / WASH_SECONDS as f64;
+
+        let recommendation = if seconds < WASH_SECONDS && riffle.variation > VARIATION_THRESHOLD {
+            Some(Recommendation::Riffle {
+                times: recommended_riffles(cards),
+            })
+        } else {
+            None
+        };
+
+        ShuffleEstimate {
+            bits: bits.min(max_bits),
+            max_bits,
+            recommendation,
+        }
+    }
+}
+
+/// The exact distribution of a deck after some number of GSR riffle shuffles.
+struct Riffles {
+    /// The entropy of the resulting order, in bits.
+    bits: f64,
+    /// The total variation distance from a uniformly random order.
+    variation: f64,
+}
+
+impl Riffles {
+    /// Following Bayer and Diaconis, `k` riffles of `n` cards produce each permutation
+    /// with `r` rising sequences with probability `C(2^k + n - r, n) / 2^(kn)`. Both
+    /// the entropy and the variation distance are sums over the number of rising
+    /// sequences, weighted by the fraction of permutations with that many.
+    fn new(n: usize, k: u32) -> Riffles {
+        let log2_n_factorial = log2_factorial(n);
+        let hands = 2f64.powi(k.min(1023) as i32);
+
+        let mut bits = 0.0;
+        let mut variation = 0.0;
+        for (r, weight) in (1..).zip(eulerian_fractions(n)) {
+            // The probability of each such permutation, multiplied by n!.
+            let mut ln_q = 0.0;
+            for j in 1..=n {
+                let factor = 1.0 + (j as f64 - r as f64) / hands;
+                if factor <= 0.0 {
+                    ln_q = f64::NEG_INFINITY;
+                    break;
+                }
+                ln_q += factor.ln();
+            }
+            let q = ln_q.exp();
+
+            if q > 0.0 {
+                bits += weight * q * (log2_n_factorial - ln_q / std::f64::consts::LN_2);
+            }
+            variation += weight * (q - 1.0).abs() / 2.0;
+        }
+
+        // Clamp away rounding error, which can make the entropy of a single
+        // certain order slightly negative.
+        Riffles {
+            bits: bits.max(0.0),
+            variation,
+        }
+    }
+}
+
+/// Returns the fraction of permutations of `n` elements which have each number of
+/// rising sequences from 1 to `n`: the Eulerian numbers divided by `n!`.
+fn eulerian_fractions(n: usize) -> Vec<f64> {
+    let mut row = vec![1.0];
+    for size in 2..=n {
+        let mut next = vec![0.0; size];
+        for (m, entry) in next.iter_mut().enumerate() {
+            let stay = row.get(m).map_or(0.0, |e| (m + 1) as f64 * e);
+            let grow = m
+                .checked_sub(1)
+                .map_or(0.0, |m| (size - 1 - m) as f64 * row[m]);
+            *entry = (stay + grow) / size as f64;
+        }
+        row = next;
+    }
+    row
+}
+
+/// Returns the fewest riffles after which a deck of `cards` cards is within the
+/// variation threshold of a uniformly random order.
+fn recommended_riffles(cards: usize) -> u32 {
+    (1..MAX_RIFFLES)
+        .find(|&k| Riffles::new(cards, k).variation <= VARIATION_THRESHOLD)
+        .unwrap_or(MAX_RIFFLES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eulerian() {
+        assert_eq!(eulerian_fractions(1), vec![1.0]);
+        let fractions = eulerian_fractions(4);
+        let counts: Vec<f64> = fractions.iter().map(|f| (f * 24.0).round()).collect();
+        assert_eq!(counts, vec![1.0, 11.0, 11.0, 1.0]);
+        assert!((eulerian_fractions(52).iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn bayer_diaconis() {
+        // Total variation distances for 52 cards from Bayer and Diaconis (1992).
+        for (k, expected) in [
+            (4, 1.0),
+            (5, 0.924),
+            (6, 0.614),
+            (7, 0.334),
+            (8, 0.167),
+            (10, 0.043),
+        ] {
+            let variation = Riffles::new(52, k).variation;
+            assert!((variation - expected).abs() < 0.001, "{k}: {variation}");
+        }
+        assert_eq!(recommended_riffles(52), 7);
+    }
+
+    #[test]
+    fn entropy() {
+        assert_eq!(Riffles::new(52, 0).bits, 0.0);
+        // One riffle drops each card from either hand: 2^52 equally likely outcomes,
+        // almost all of which are distinct orders.
+        let one = Riffles::new(52, 1).bits;
+        assert!((one - 52.0).abs() < 1e-6);
+
+        let mut previous = 0.0;
+        for k in 1..12 {
+            let bits = ShuffleEstimate::new(52, &vec![Technique::Riffle; k]).bits;
+            assert!(bits > previous);
+            previous = bits;
+        }
+        assert!((previous - log2_factorial(52)).abs() < 0.01);
+    }
+
+    #[test]
+    fn techniques() {
+        let none = ShuffleEstimate::new(52, &[]);
+        assert_eq!(none.bits, 0.0);
+        assert!(none.recommendation.is_some());
+
+        let cuts = ShuffleEstimate::new(52, &[Technique::Cut, Technique::Overhand]);
+        assert!((cuts.bits - 52f64.log2()).abs() < 1e-9);
+
+        let washed = ShuffleEstimate::new(52, &[Technique::Wash { seconds: 90 }]);
+        assert_eq!(washed.bits, washed.max_bits);
+        assert_eq!(washed.recommendation, None);
+
+        let half = ShuffleEstimate::new(52, &[Technique::Wash { seconds: 30 }]);
+        assert!((half.bits - half.max_bits / 2.0).abs() < 1e-9);
+        assert_eq!(
+            half.recommendation,
+            Some(Recommendation::Riffle { times: 7 })
+        );
+    }
+}
@@ -0,0 +1,357 @@
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::DECK_SIZE;
+use std::fmt;
+
+/// A position at which two transcriptions of a deck disagree. Either card is `None`
+/// if that transcription is too short to reach the position.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Mismatch {
+    /// The position in the deck, counting from zero.
+    pub position: usize,
+    /// The card at the position in the first transcription.
+    pub first: Option<Card>,
+    /// The card at the position in the second transcription.
+    pub second: Option<Card>,
+}
+
+/// The differences between two transcriptions of the same deck, as returned by
+/// `Deck::verify_transcription`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TranscriptionReport {
+    /// Positions at which the transcriptions hold different cards, excluding
+    /// swapped neighbors.
+    pub mismatches: Vec<Mismatch>,
+    /// Positions `i` at which the cards at `i` and `i + 1` in one transcription
+    /// appear in the opposite order in the other.
+    pub swaps: Vec<usize>,
+    /// Cards in the first transcription which are missing from the second.
+    pub missing: Vec<Card>,
+    /// Cards in the second transcription which are not in the first.
+    pub extra: Vec<Card>,
+}
+
+impl TranscriptionReport {
+    /// Returns true if the transcriptions are identical.
+    pub fn is_match(&self) -> bool {
+        self.mismatches.is_empty() && self.swaps.is_empty()
+    }
+}
+
+impl fmt::Display for TranscriptionReport {
+    /// Formats the report with one difference per line, suitable for showing to
+    /// the person who entered the transcriptions.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_match() {
+            return write!(f, "transcriptions match");
+        }
+
+        let card = |card: Option<Card>| card.map_or(String::from("nothing"), |c| c.to_string());
+        let cards = |cards: &[Card]| {
+            let cards: Vec<String> = cards.iter().map(Card::to_string).collect();
+            cards.join(" ")
+        };
+        let mut lines = Vec::new();
+        for &i in &self.swaps {
+            lines.push(format!("positions {} and {} are swapped", i + 1, i + 2));
+        }
+        for m in &self.mismatches {
+            lines.push(format!(
+                "position {}: {} in the first entry, {} in the second",
+                m.position + 1,
+                card(m.first),
+                card(m.second)
+            ));
+        }
+        if !self.missing.is_empty() {
+            lines.push(format!(
+                "missing from the second entry: {}",
+                cards(&self.missing)
+            ));
+        }
+        if !self.extra.is_empty() {
+            lines.push(format!("extra in the second entry: {}", cards(&self.extra)));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// A common human error which explains a difference between two transcriptions
+/// of a deck, as found by `Deck::suspected_errors`. Positions count from zero.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SuspectedError {
+    /// The cards at `position` and `position + 1` were entered in the wrong order.
+    AdjacentSwap { position: usize },
+    /// Two cards further apart were entered in each other's places.
+    Transposition { first: usize, second: usize },
+    /// A card was entered with the right suit but the wrong face value.
+    MisreadRank {
+        position: usize,
+        expected: Card,
+        found: Card,
+    },
+    /// A card was entered with the right face value, but the other suit of the
+    /// same color: hearts for diamonds, or spades for clubs, and vice versa.
+    SuitConfusion {
+        position: usize,
+        expected: Card,
+        found: Card,
+    },
+}
+
+impl fmt::Display for SuspectedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SuspectedError::AdjacentSwap { position } => {
+                write!(
+                    f,
+                    "positions {} and {} are swapped",
+                    position + 1,
+                    position + 2
+                )
+            }
+            SuspectedError::Transposition { first, second } => {
+                write!(f, "positions {} and {} are swapped", first + 1, second + 1)
+            }
+            SuspectedError::MisreadRank {
+                position,
+                expected,
+                found,
+            } => write!(
+                f,
+                "position {}: {expected} was misread as {found}",
+                position + 1
+            ),
+            SuspectedError::SuitConfusion {
+                position,
+                expected,
+                found,
+            } => write!(
+                f,
+                "position {}: {expected} was confused with {found}",
+                position + 1
+            ),
+        }
+    }
+}
+
+impl Deck {
+    /// Checks whether a second transcription of the `Deck` differs from it only by
+    /// common human errors: swapped cards, misread face values, and suits confused
+    /// with the other suit of the same color. Returns the suspected errors, in order
+    /// of position, or `None` if some difference isn't explained by them, or if the
+    /// transcriptions have different lengths. Identical transcriptions have no errors.
+    ///
+    /// ```
+    /// use cardseed::{Deck, SuspectedError};
+    ///
+    /// let first = "AS 3H KC 3C 7D".parse::<Deck>().unwrap();
+    /// let second = "AS KC 3H 3C 7H".parse::<Deck>().unwrap();
+    /// let errors = first.suspected_errors(&second).unwrap();
+    /// assert_eq!(errors[0], SuspectedError::AdjacentSwap { position: 1 });
+    /// assert_eq!(errors[1].to_string(), "position 5: 7D was confused with 7H");
+    /// assert_eq!(first.suspected_errors(&"AS 3H KC 3C 2S".parse().unwrap()), None);
+    /// ```
+    pub fn suspected_errors(&self, second_entry: &Deck) -> Option<Vec<SuspectedError>> {
+        let (a, b) = (&self.cards, &second_entry.cards);
+        if a.len() != b.len() {
+            return None;
+        }
+
+        let mut errors = Vec::new();
+        let mut explained = vec![false; a.len()];
+        let mut i = 0;
+        while i < a.len() {
+            if explained[i] || a[i] == b[i] {
+                i += 1;
+                continue;
+            }
+
+            let (expected, found) = (a[i], b[i]);
+            if i + 1 < a.len() && a[i + 1] == found && b[i + 1] == expected {
+                errors.push(SuspectedError::AdjacentSwap { position: i });
+                explained[i + 1] = true;
+            } else if let Some(j) = (i + 2..a.len())
+                .find(|&j| !explained[j] && a[j] != b[j] && a[j] == found && b[j] == expected)
+            {
+                errors.push(SuspectedError::Transposition {
+                    first: i,
+                    second: j,
+                });
+                explained[j] = true;
+            } else if expected.suit() == found.suit() {
+                errors.push(SuspectedError::MisreadRank {
+                    position: i,
+                    expected,
+                    found,
+                });
+            } else if expected.value() == found.value()
+                && expected.suit().is_red() == found.suit().is_red()
+            {
+                errors.push(SuspectedError::SuitConfusion {
+                    position: i,
+                    expected,
+                    found,
+                });
+            } else {
+                return None;
+            }
+            i += 1;
+        }
+        Some(errors)
+    }
+
+    /// Compares the `Deck` with a second, independent transcription of the same
+    /// physical deck, and reports where they differ. Entering a deck twice and
+    /// comparing the entries catches most transcription errors before the deck is
+    /// used to derive a secret.
+    ///
+    /// Neighboring cards entered in opposite orders are reported as swaps rather
+    /// than as two mismatches. Cards which appear a different number of times in
+    /// each entry are reported as missing or extra.
+    ///
+    /// ```
+    /// use cardseed::Deck;
+    ///
+    /// let first = "AS 3H KC 3C 7D".parse::<Deck>().unwrap();
+    /// let second = "AS KC 3H 3C 7H".parse::<Deck>().unwrap();
+    /// let report = first.verify_transcription(&second);
+    /// assert!(!report.is_match());
+    /// assert_eq!(report.swaps, vec![1]);
+    /// assert_eq!(report.mismatches[0].position, 4);
+    /// assert_eq!(report.missing, vec!["7D".parse().unwrap()]);
+    /// assert_eq!(report.extra, vec!["7H".parse().unwrap()]);
+    /// assert!(first.verify_transcription(&first).is_match());
+    /// ```
+    pub fn verify_transcription(&self, second_entry: &Deck) -> TranscriptionReport {
+        let (a, b) = (&self.cards, &second_entry.cards);
+        let mut report = TranscriptionReport::default();
+
+        let len = a.len().max(b.len());
+        let mut i = 0;
+        while i < len {
+            let (x, y) = (a.get(i).copied(), b.get(i).copied());
+            if x == y {
+                i += 1;
+                continue;
+            }
+
+            let swapped = i + 1 < len
+                && x.is_some()
+                && y.is_some()
+                && a.get(i + 1).copied() == y
+                && b.get(i + 1).copied() == x;
+            if swapped {
+                report.swaps.push(i);
+                i += 2;
+            } else {
+                report.mismatches.push(Mismatch {
+                    position: i,
+                    first: x,
+                    second: y,
+                });
+                i += 1;
+            }
+        }
+
+        let mut counts = [0i64; DECK_SIZE];
+        for card in a {
+            counts[card.index() as usize] += 1;
+        }
+        for card in b {
+            counts[card.index() as usize] -= 1;
+        }
+        for (index, &count) in counts.iter().enumerate() {
+            let card = Card::from_index(index as u8).expect("index is below DECK_SIZE");
+            for _ in 0..count {
+                report.missing.push(card);
+            }
+            for _ in count..0 {
+                report.extra.push(card);
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deck(s: &str) -> Deck {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn suspected_errors() {
+        let first = deck("AS 2S 3S 4S 5S 6S 7S 8S");
+        assert_eq!(first.suspected_errors(&first), Some(vec![]));
+        assert_eq!(
+            first.suspected_errors(&deck("AS 7S 3S 4S 5S 6S 2S 8C")),
+            Some(vec![
+                SuspectedError::Transposition {
+                    first: 1,
+                    second: 6
+                },
+                SuspectedError::SuitConfusion {
+                    position: 7,
+                    expected: "8S".parse().unwrap(),
+                    found: "8C".parse().unwrap(),
+                },
+            ])
+        );
+        assert_eq!(
+            first.suspected_errors(&deck("2S AS 3S 4S 5S 6S 7S 9S")),
+            Some(vec![
+                SuspectedError::AdjacentSwap { position: 0 },
+                SuspectedError::MisreadRank {
+                    position: 7,
+                    expected: "8S".parse().unwrap(),
+                    found: "9S".parse().unwrap(),
+                },
+            ])
+        );
+        assert_eq!(
+            first.suspected_errors(&deck("AS 2S 3S 4S 5S 6S 7S 8H")),
+            None
+        );
+        assert_eq!(first.suspected_errors(&deck("AS 2S")), None);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn verify_transcription() {
+        let shuffled = Deck::new().shuffle();
+        assert_eq!(
+            shuffled.verify_transcription(&shuffled),
+            TranscriptionReport::default()
+        );
+
+        let report = deck("AS 2S 3S").verify_transcription(&deck("AS 2S"));
+        assert_eq!(
+            report.mismatches,
+            vec![Mismatch {
+                position: 2,
+                first: Some("3S".parse().unwrap()),
+                second: None,
+            }]
+        );
+        assert_eq!(report.missing, vec!["3S".parse().unwrap()]);
+        assert!(report.extra.is_empty());
+
+        let report = deck("AS 2S 3S 4S").verify_transcription(&deck("2S AS 4S 3S"));
+        assert_eq!(report.swaps, vec![0, 2]);
+        assert!(report.mismatches.is_empty());
+        assert!(report.missing.is_empty() && report.extra.is_empty());
+
+        let report = deck("AS 2S").verify_transcription(&deck("AS AS"));
+        assert_eq!(report.missing, vec!["2S".parse().unwrap()]);
+        assert_eq!(report.extra, vec!["AS".parse().unwrap()]);
+        assert_eq!(
+            report.to_string(),
+            "position 2: 2S in the first entry, AS in the second\n\
+             missing from the second entry: 2S\n\
+             extra in the second entry: AS"
+        );
+    }
+}
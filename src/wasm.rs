@@ -0,0 +1,71 @@
+//! JavaScript bindings for using `cardseed` in the browser, built with `wasm-bindgen`.
+//!
+//! Decks are exchanged with JavaScript as strings, and derived secrets as `Uint8Array`s.
+//! Errors are thrown as JavaScript `Error`s carrying the formatted Rust error message.
+
+use crate::deck::Deck;
+use wasm_bindgen::prelude::*;
+
+/// A JavaScript handle to a `Deck`, exported to JavaScript as `Deck`.
+#[wasm_bindgen(js_name = Deck)]
+pub struct JsDeck(Deck);
+
+#[wasm_bindgen(js_class = Deck)]
+impl JsDeck {
+    /// Creates a new deck in canonical order, from the ace of spades to the king of diamonds.
+    #[wasm_bindgen(constructor)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> JsDeck {
+        JsDeck(Deck::new())
+    }
+
+    /// Parses a deck from a string of whitespace-delimited card strings.
+    pub fn parse(s: &str) -> Result<JsDeck, JsError> {
+        Ok(JsDeck(s.parse::<Deck>()?))
+    }
+
+    /// Returns a randomly shuffled copy of the deck, using the browser's secure RNG.
+    /// Throws an error if the RNG is unavailable.
+    pub fn shuffle(&self) -> Result<JsDeck, JsError> {
+        Ok(JsDeck(self.0.try_shuffle()?))
+    }
+
+    /// Formats the deck as a space-delimited string of cards.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_js_string(&self) -> String {
+        self.0.to_string()
+    }
+
+    /// The number of cards in the deck.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.0.cards.len()
+    }
+
+    /// Returns true if the deck contains any duplicate cards.
+    #[wasm_bindgen(js_name = hasDuplicates)]
+    pub fn has_duplicates(&self) -> bool {
+        self.0.has_duplicates()
+    }
+
+    /// Throws an error if the deck is not a complete 52-card deck without duplicates.
+    pub fn validate(&self) -> Result<(), JsError> {
+        Ok(self.0.validate()?)
+    }
+
+    /// Returns the number of bits of entropy in the deck, assuming it was randomly shuffled.
+    #[wasm_bindgen(js_name = entropyBits)]
+    pub fn entropy_bits(&self) -> f64 {
+        self.0.entropy_bits()
+    }
+
+    /// Derives a 32-byte secret from the deck and an optional password.
+    pub fn hash(&self, password: Option<String>) -> Vec<u8> {
+        self.0.hash(password.as_deref()).to_vec()
+    }
+
+    /// Derives a 24-word BIP39 mnemonic from the deck and an optional password.
+    pub fn mnemonic(&self, password: Option<String>) -> String {
+        self.0.mnemonic(password.as_deref()).to_string()
+    }
+}
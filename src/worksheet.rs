@@ -0,0 +1,260 @@
+use crate::card::Card;
+use crate::checkword::CHECKWORDS;
+use crate::deck::Deck;
+use crate::errors;
+use std::fmt::Write;
+
+/// The heading which begins every backup worksheet.
+const HEADING: &str = "CARDSEED BACKUP WORKSHEET";
+
+/// A blank field or card slot, to be filled in by hand.
+const BLANK_FIELD: &str = "____________________";
+const BLANK_SLOT: &str = "__";
+
+/// Options for the worksheet written by `Deck::backup_worksheet`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WorksheetOptions {
+    /// A label identifying the backup, such as the name of a wallet. Left blank to
+    /// be filled in by hand if empty.
+    pub label: String,
+    /// The date of the backup. Left blank to be filled in by hand if empty.
+    pub date: String,
+    /// The number of card slots in each row. Defaults to 13, and zero is treated as one.
+    pub row_len: usize,
+    /// Whether to write the cards of the deck into their slots, rather than leave
+    /// the slots blank. Defaults to false.
+    pub fill: bool,
+    /// The checkword to write into the checkword box, as returned by
+    /// `Deck::checkword`. Left blank to be filled in by hand if `None`.
+    pub checkword: Option<&'static str>,
+}
+
+impl Default for WorksheetOptions {
+    fn default() -> WorksheetOptions {
+        WorksheetOptions {
+            label: String::new(),
+            date: String::new(),
+            row_len: 13,
+            fill: false,
+            checkword: None,
+        }
+    }
+}
+
+/// A completed backup worksheet, read by `Worksheet::parse`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Worksheet {
+    /// The label of the backup, if one was filled in.
+    pub label: Option<String>,
+    /// The date of the backup, if one was filled in.
+    pub date: Option<String>,
+    /// The cards recorded in the slots, in order.
+    pub deck: Deck,
+    /// The checkword, if one was filled in.
+    pub checkword: Option<&'static str>,
+}
+
+impl Deck {
+    /// Writes a plain-text worksheet for recording the `Deck` on paper, with fields
+    /// for a label and date, a numbered slot for each card of the `Deck`, and a box
+    /// for its checkword. Blank slots and fields are drawn as underscores, to be
+    /// filled in by hand, and read back with `Worksheet::parse`.
+    ///
+    /// ```
+    /// use cardseed::{Deck, Worksheet, WorksheetOptions};
+    ///
+    /// let deck = "AS 3H KC 3C".parse::<Deck>().unwrap();
+    /// let options = WorksheetOptions {
+    ///     row_len: 2,
+    ///     ..WorksheetOptions::default()
+    /// };
+    /// let blank = deck.backup_worksheet(&options);
+    /// assert!(blank.contains("1. __  2. __\n3. __  4. __\n"));
+    ///
+    /// let filled = blank
+    ///     .replacen(". __", ". AS", 1)
+    ///     .replacen(". __", ". 3H", 1)
+    ///     .replacen(". __", ". KC", 1)
+    ///     .replacen(". __", ". 3C", 1)
+    ///     .replace("[____________________]", "[geyser]");
+    /// let worksheet = Worksheet::parse(&filled).unwrap();
+    /// assert_eq!(worksheet.deck, deck);
+    /// assert!(worksheet.verify(None));
+    /// ```
+    pub fn backup_worksheet(&self, options: &WorksheetOptions) -> String {
+        let field = |value: &str| {
+            if value.is_empty() {
+                BLANK_FIELD.to_string()
+            } else {
+                value.to_string()
+            }
+        };
+
+        let mut sheet = String::new();
+        writeln!(sheet, "{HEADING}").unwrap();
+        writeln!(sheet).unwrap();
+        writeln!(sheet, "Label: {}", field(&options.label)).unwrap();
+        writeln!(sheet, "Date: {}", field(&options.date)).unwrap();
+        writeln!(sheet).unwrap();
+
+        let width = self.cards.len().to_string().len();
+        let row_len = options.row_len.max(1);
+        for (row, chunk) in self.cards.chunks(row_len).enumerate() {
+            for (i, card) in chunk.iter().enumerate() {
+                let number = row * row_len + i + 1;
+                let slot = match options.fill {
+                    true => card.to_string(),
+                    false => BLANK_SLOT.to_string(),
+                };
+                let gap = if i > 0 { "  " } else { "" };
+                write!(sheet, "{gap}{number:>width$}. {slot}").unwrap();
+            }
+            writeln!(sheet).unwrap();
+        }
+
+        writeln!(sheet).unwrap();
+        writeln!(
+            sheet,
+            "Checkword: [{}]",
+            field(options.checkword.unwrap_or_default())
+        )
+        .unwrap();
+        sheet
+    }
+}
+
+impl Worksheet {
+    /// Reads a worksheet written by `Deck::backup_worksheet` once it has been filled
+    /// in. Fields left blank are read as `None`. Every card slot must be filled, and
+    /// the slots must be numbered in order from one, so that a skipped slot is
+    /// caught. The checkword may be abbreviated to its first four letters.
+    pub fn parse(s: &str) -> Result<Worksheet, errors::WorksheetError> {
+        let mut worksheet = Worksheet {
+            label: None,
+            date: None,
+            deck: Deck { cards: vec![] },
+            checkword: None,
+        };
+
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line == HEADING {
+                continue;
+            } else if let Some(label) = line.strip_prefix("Label:") {
+                worksheet.label = filled(label).map(String::from);
+            } else if let Some(date) = line.strip_prefix("Date:") {
+                worksheet.date = filled(date).map(String::from);
+            } else if let Some(checkword) = line.strip_prefix("Checkword:") {
+                let checkword = checkword
+                    .trim()
+                    .trim_start_matches('[')
+                    .trim_end_matches(']');
+                if let Some(word) = filled(checkword) {
+                    worksheet.checkword = Some(find_checkword(word)?);
+                }
+            } else {
+                parse_slots(line, &mut worksheet.deck)?;
+            }
+        }
+        Ok(worksheet)
+    }
+
+    /// Returns true if the recorded checkword matches the checkword of the recorded
+    /// deck and the given password, or false if it doesn't or none was recorded.
+    pub fn verify(&self, password: Option<&str>) -> bool {
+        self.checkword == Some(self.deck.checkword(password))
+    }
+}
+
+/// Returns the trimmed value of a field, or `None` if it was left blank.
+fn filled(value: &str) -> Option<&str> {
+    let value = value.trim();
+    (!value.chars().all(|c| c == '_')).then_some(value)
+}
+
+/// Finds the checkword with the same first four letters as `word`, ignoring case.
+fn find_checkword(word: &str) -> Result<&'static str, errors::WorksheetError> {
+    let prefix = |w: &str| w.get(..4).unwrap_or(w).to_ascii_lowercase();
+    CHECKWORDS
+        .iter()
+        .find(|&&checkword| prefix(checkword) == prefix(word))
+        .copied()
+        .ok_or_else(|| errors::WorksheetError::Checkword(String::from(word)))
+}
+
+/// Reads a row of numbered card slots, such as `1. AS  2. 3H`, onto the end of `deck`.
+fn parse_slots(line: &str, deck: &mut Deck) -> Result<(), errors::WorksheetError> {
+    let mut tokens = line.split_whitespace();
+    while let Some(token) = tokens.next() {
+        let position = deck.cards.len();
+        let number = token
+            .strip_suffix('.')
+            .and_then(|n| n.parse::<usize>().ok())
+            .ok_or_else(|| errors::WorksheetError::Malformed(String::from(line)))?;
+        if number != position + 1 {
+            return Err(errors::WorksheetError::Slot {
+                expected: position + 1,
+                found: number,
+            });
+        }
+
+        let slot = tokens.next().unwrap_or(BLANK_SLOT);
+        let card = Card::parse_token(slot)
+            .map_err(|source| errors::DeckError::UnknownCard { position, source })?;
+        deck.cards.push(card);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backup_worksheet() {
+        let deck = Deck::new();
+        let options = WorksheetOptions {
+            label: String::from("savings"),
+            fill: true,
+            checkword: Some(deck.checkword(None)),
+            ..WorksheetOptions::default()
+        };
+        let sheet = deck.backup_worksheet(&options);
+        assert!(sheet.starts_with("CARDSEED BACKUP WORKSHEET\n\nLabel: savings\nDate: ____"));
+        assert!(sheet.contains("\n 1. AS   2. 2S"));
+        assert!(sheet.contains("\n40. AD  41. 2D"));
+
+        let worksheet = Worksheet::parse(&sheet).unwrap();
+        assert_eq!(worksheet.label.as_deref(), Some("savings"));
+        assert_eq!(worksheet.date, None);
+        assert_eq!(worksheet.deck, deck);
+        assert!(worksheet.verify(None));
+        assert!(!worksheet.verify(Some("pw")));
+    }
+
+    #[test]
+    fn parse_worksheet() {
+        let blank = Deck::new().backup_worksheet(&WorksheetOptions::default());
+        assert!(matches!(
+            Worksheet::parse(&blank),
+            Err(errors::WorksheetError::Deck(
+                errors::DeckError::UnknownCard { position: 0, .. }
+            ))
+        ));
+        assert_eq!(
+            Worksheet::parse("1. AS  3. 3S"),
+            Err(errors::WorksheetError::Slot {
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            Worksheet::parse("Checkword: [GEYS]").unwrap().checkword,
+            Some("geyser")
+        );
+        assert_eq!(
+            Worksheet::parse("Checkword: [nonsense]"),
+            Err(errors::WorksheetError::Checkword(String::from("nonsense")))
+        );
+        assert!(Worksheet::parse("Notes: none").is_err());
+    }
+}